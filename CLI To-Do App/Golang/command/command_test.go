@@ -92,12 +92,12 @@ func TestExecuteAddTaskWithTitle(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 
-	if len(*tasksList) != 1 {
-		t.Errorf("Expected 1 task, but got: %d", len(*tasksList))
+	if len(tasksList.Items) != 1 {
+		t.Errorf("Expected 1 task, but got: %d", len(tasksList.Items))
 	}
 
-	if (*tasksList)[0].Title != "Title for testing" {
-		t.Errorf("Expected title \"Title for testing\", but got: '%s'", (*tasksList)[0].Title)
+	if tasksList.Items[0].Title != "Title for testing" {
+		t.Errorf("Expected title \"Title for testing\", but got: '%s'", tasksList.Items[0].Title)
 	}
 }
 
@@ -141,8 +141,8 @@ func TestExecuteUpdateTaskWithTitle(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 
-	if (*tasksList)[0].Title != "Title 2 for testing" {
-		t.Errorf("Expected title \"Title 2 for testing\", but got: '%s'", (*tasksList)[0].Title)
+	if tasksList.Items[0].Title != "Title 2 for testing" {
+		t.Errorf("Expected title \"Title 2 for testing\", but got: '%s'", tasksList.Items[0].Title)
 	}
 }
 
@@ -165,8 +165,8 @@ func TestExecuteUpdateTaskWithDescription(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 
-	if (*tasksList)[0].Description != "Description 2 for testing" {
-		t.Errorf("Expected description \"Description 2 for testing\", but got: '%s'", (*tasksList)[0].Description)
+	if tasksList.Items[0].Description != "Description 2 for testing" {
+		t.Errorf("Expected description \"Description 2 for testing\", but got: '%s'", tasksList.Items[0].Description)
 	}
 }
 
@@ -206,7 +206,7 @@ func TestExecuteDeleteTask(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 
-	if (*tasksList)[0].Visible {
+	if tasksList.Items[0].Visible {
 		t.Error("Expected task to be not visible after deletion")
 	}
 }
@@ -248,8 +248,8 @@ func TestExecuteChangeTaskStatusToPending(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 
-	if (*tasksList)[0].Status != tasks.StatusPending {
-		t.Errorf("Expected status \"Pendiente\", but got: %v", (*tasksList)[0].Status.Stringify())
+	if tasksList.Items[0].Status != tasks.StatusPending {
+		t.Errorf("Expected status \"Pendiente\", but got: %v", tasksList.Items[0].Status.Stringify())
 	}
 }
 
@@ -271,8 +271,8 @@ func TestExecuteChangeTaskStatusToInProgress(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 
-	if (*tasksList)[0].Status != tasks.StatusInProgress {
-		t.Errorf("Expected status \"En progreso\", but got: %v", (*tasksList)[0].Status.Stringify())
+	if tasksList.Items[0].Status != tasks.StatusInProgress {
+		t.Errorf("Expected status \"En progreso\", but got: %v", tasksList.Items[0].Status.Stringify())
 	}
 }
 
@@ -294,11 +294,11 @@ func TestExecuteChangeTaskStatusToCompleted(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 
-	if (*tasksList)[0].Status != tasks.StatusCompleted {
-		t.Errorf("Expected status \"Completada\", but got: %v", (*tasksList)[0].Status.Stringify())
+	if tasksList.Items[0].Status != tasks.StatusCompleted {
+		t.Errorf("Expected status \"Completada\", but got: %v", tasksList.Items[0].Status.Stringify())
 	}
 
-	if (*tasksList)[0].CompletedAt.IsZero() {
+	if tasksList.Items[0].CompletedAt.IsZero() {
 		t.Error("Expected CompletedAt attribute to be set")
 	}
 }
@@ -1,6 +1,7 @@
 package storage
 
 import (
+	"encoding/json"
 	"os"
 	"testing"
 	"todo-app/tasks"
@@ -128,16 +129,16 @@ func TestDownloadDataWithValidFile(t *testing.T) {
 
 	// ----------------------------------------------------------
 	
-	if len(downloaded) != 2 {
-		t.Errorf("Expected 2 tasks, but got: %d", len(downloaded))
+	if len(downloaded.Items) != 2 {
+		t.Errorf("Expected 2 tasks, but got: %d", len(downloaded.Items))
 	}
 	
-	if downloaded[0].Title != "Title 1 for testing" {
-		t.Errorf("Expected title \"Title 1 for testing\", but got: '%s'", downloaded[0].Title)
+	if downloaded.Items[0].Title != "Title 1 for testing" {
+		t.Errorf("Expected title \"Title 1 for testing\", but got: '%s'", downloaded.Items[0].Title)
 	}
 	
-	if downloaded[1].Title != "Title 2 for testing" {
-		t.Errorf("Expected title \"Title 2 for testing\", but got '%s'", downloaded[1].Title)
+	if downloaded.Items[1].Title != "Title 2 for testing" {
+		t.Errorf("Expected title \"Title 2 for testing\", but got '%s'", downloaded.Items[1].Title)
 	}
 }
 
@@ -176,23 +177,23 @@ func TestUploadDownloadIntegration(t *testing.T) {
 
 	// ----------------------------------------------------------
 	
-	if len(downloaded) != 1 {
-		t.Errorf("Expected 1 task, but got: %d", len(downloaded))
+	if len(downloaded.Items) != 1 {
+		t.Errorf("Expected 1 task, but got: %d", len(downloaded.Items))
 	}
 	
-	if downloaded[0].Title != "Task 1 for testing" {
-		t.Errorf("Expected title \"Task 1 for testing\", but got: '%s'", downloaded[0].Title)
+	if downloaded.Items[0].Title != "Task 1 for testing" {
+		t.Errorf("Expected title \"Task 1 for testing\", but got: '%s'", downloaded.Items[0].Title)
 	}
 	
-	if downloaded[0].Description != "Description 1 for testing" {
-		t.Errorf("Expected description \"Description 1 for testing\", but got: '%s'", downloaded[0].Description)
+	if downloaded.Items[0].Description != "Description 1 for testing" {
+		t.Errorf("Expected description \"Description 1 for testing\", but got: '%s'", downloaded.Items[0].Description)
 	}
 	
-	if downloaded[0].Status != tasks.StatusCompleted {
-		t.Errorf("Expected status \"Completada\", but got: %v", downloaded[0].Status.Stringify())
+	if downloaded.Items[0].Status != tasks.StatusCompleted {
+		t.Errorf("Expected status \"Completada\", but got: %v", downloaded.Items[0].Status.Stringify())
 	}
 	
-	if downloaded[0].Visible != true {
+	if downloaded.Items[0].Visible != true {
 		t.Error("Expected task to be visible, but it's not")
 	}
 }
@@ -238,23 +239,207 @@ func TestUploadDataWithComplexTasks(t *testing.T) {
 
 	// ----------------------------------------------------------
 	
-	if len(downloaded) != 4 {
-		t.Errorf("Expected 4 tasks, but got: %d", len(downloaded))
+	if len(downloaded.Items) != 4 {
+		t.Errorf("Expected 4 tasks, but got: %d", len(downloaded.Items))
 	}
 	
-	if downloaded[0].Status != tasks.StatusPending {
-		t.Errorf("Expected task 1 to be \"Pendiente\", but got: %v", downloaded[0].Status.Stringify())
+	if downloaded.Items[0].Status != tasks.StatusPending {
+		t.Errorf("Expected task 1 to be \"Pendiente\", but got: %v", downloaded.Items[0].Status.Stringify())
 	}
 	
-	if downloaded[1].Status != tasks.StatusInProgress {
-		t.Errorf("Expected task 2 to be \"En progreso\", but got: %v", downloaded[1].Status.Stringify())
+	if downloaded.Items[1].Status != tasks.StatusInProgress {
+		t.Errorf("Expected task 2 to be \"En progreso\", but got: %v", downloaded.Items[1].Status.Stringify())
 	}
 	
-	if downloaded[2].Status != tasks.StatusCompleted {
-		t.Errorf("Expected task 3 to be \"Completada\", but got: %v", downloaded[2].Status.Stringify())
+	if downloaded.Items[2].Status != tasks.StatusCompleted {
+		t.Errorf("Expected task 3 to be \"Completada\", but got: %v", downloaded.Items[2].Status.Stringify())
 	}
 	
-	if downloaded[3].Visible != false {
+	if downloaded.Items[3].Visible != false {
 		t.Error("Expected task 4 to be not visible, but it is")
 	}
 }
+
+// Test: Upload data function keeps the fields of a task saved by the Rust app
+func TestUploadDataKeepsUnknownFields(t *testing.T) {
+	defer testutil.SuppressOutput(t)()
+	
+	filename := "testing.json"
+
+	defer os.Remove(filename)
+
+	filedata := `[{"ID": 1, "Title": "Title 1 for testing", "Description": "", "Status": 0, "Priority": 3, "Tags": ["trabajo"], "Visible": true}]`
+
+	if e := os.WriteFile(filename, []byte(filedata), 0644); e != nil {
+		t.Fatalf("Unexpected error (while writing): %v", e)
+	}
+	
+	storage := CreateStorage[tasks.Tasks](filename)
+
+	// ----------------------------------------------------------
+	
+	tasksList := tasks.Tasks{}
+
+	if e := storage.DownloadData(&tasksList); e != nil {
+		t.Errorf("Unexpected error (while downloading): %v", e)
+	}
+
+	tasksList.ChangeTaskStatus(1, tasks.StatusCompleted)
+
+	if e := storage.UploadData(tasksList); e != nil {
+		t.Errorf("Unexpected error (while uploading): %v", e)
+	}
+
+	// ----------------------------------------------------------
+
+	saved := []map[string]any{}
+
+	uploaded, _ := os.ReadFile(filename)
+
+	if e := json.Unmarshal(uploaded, &saved); e != nil {
+		t.Fatalf("Unexpected error (while reading): %v", e)
+	}
+
+	// ----------------------------------------------------------
+	
+	if len(saved) != 1 {
+		t.Fatalf("Expected 1 task, but got: %d", len(saved))
+	}
+	
+	if saved[0]["Priority"] != 3.0 {
+		t.Errorf("Expected priority 3, but got: %v", saved[0]["Priority"])
+	}
+	
+	if tags, ok := saved[0]["Tags"].([]any); !ok || len(tags) != 1 || tags[0] != "trabajo" {
+		t.Errorf("Expected tags [trabajo], but got: %v", saved[0]["Tags"])
+	}
+	
+	if saved[0]["Status"] != 2.0 {
+		t.Errorf("Expected status 2, but got: %v", saved[0]["Status"])
+	}
+}
+
+// Test: Download and upload data functions with a file written by the Rust app
+func TestUploadDataWithRustFile(t *testing.T) {
+	defer testutil.SuppressOutput(t)()
+	
+	filename := "testing.json"
+
+	defer os.Remove(filename)
+
+	filedata := `{"NextID": 9, "Tasks": [{"ID": 4, "UUID": "6f1c2b5e-8d3a-4f5e-9a7b-2c4d6e8f0a1b", "Title": "Title 4 for testing", "Description": "", "Status": 1, "Visible": true}]}`
+
+	if e := os.WriteFile(filename, []byte(filedata), 0644); e != nil {
+		t.Fatalf("Unexpected error (while writing): %v", e)
+	}
+	
+	storage := CreateStorage[tasks.Tasks](filename)
+
+	// ----------------------------------------------------------
+	
+	tasksList := tasks.Tasks{}
+
+	if e := storage.DownloadData(&tasksList); e != nil {
+		t.Errorf("Unexpected error (while downloading): %v", e)
+	}
+
+	if e := tasksList.ChangeTaskStatus(4, tasks.StatusCompleted); e != nil {
+		t.Errorf("Unexpected error: %v", e)
+	}
+
+	tasksList.AddTask("Title 9 for testing", "")
+
+	if e := storage.UploadData(tasksList); e != nil {
+		t.Errorf("Unexpected error (while uploading): %v", e)
+	}
+
+	// ----------------------------------------------------------
+
+	saved := struct {
+		NextID int
+		Tasks  []map[string]any
+	}{}
+
+	uploaded, _ := os.ReadFile(filename)
+
+	if e := json.Unmarshal(uploaded, &saved); e != nil {
+		t.Fatalf("Unexpected error (while reading): %v", e)
+	}
+
+	// ----------------------------------------------------------
+	
+	if saved.NextID != 10 {
+		t.Errorf("Expected next ID 10, but got: %d", saved.NextID)
+	}
+	
+	if len(saved.Tasks) != 2 {
+		t.Fatalf("Expected 2 tasks, but got: %d", len(saved.Tasks))
+	}
+	
+	if saved.Tasks[0]["UUID"] != "6f1c2b5e-8d3a-4f5e-9a7b-2c4d6e8f0a1b" || saved.Tasks[0]["Status"] != 2.0 {
+		t.Errorf("Expected task 4 completed with its UUID, but got: %v", saved.Tasks[0])
+	}
+	
+	if saved.Tasks[1]["ID"] != 9.0 {
+		t.Errorf("Expected ID 9, but got: %v", saved.Tasks[1]["ID"])
+	}
+}
+
+// Test: Upload data function keeps the version of a file written by the Rust app
+func TestUploadDataWithVersionedFile(t *testing.T) {
+	defer testutil.SuppressOutput(t)()
+	
+	filename := "testing.json"
+
+	defer os.Remove(filename)
+
+	filedata := `{"version": 2, "next_id": 5, "tasks": [{"ID": 4, "Title": "Title 4 for testing", "Description": "", "Status": 0, "Visible": true}]}`
+
+	if e := os.WriteFile(filename, []byte(filedata), 0644); e != nil {
+		t.Fatalf("Unexpected error (while writing): %v", e)
+	}
+	
+	storage := CreateStorage[tasks.Tasks](filename)
+
+	// ----------------------------------------------------------
+	
+	tasksList := tasks.Tasks{}
+
+	if e := storage.DownloadData(&tasksList); e != nil {
+		t.Errorf("Unexpected error (while downloading): %v", e)
+	}
+
+	tasksList.AddTask("Title 5 for testing", "")
+
+	if e := storage.UploadData(tasksList); e != nil {
+		t.Errorf("Unexpected error (while uploading): %v", e)
+	}
+
+	// ----------------------------------------------------------
+
+	saved := struct {
+		Version int         `json:"version"`
+		NextID  int         `json:"next_id"`
+		Tasks   tasks.Tasks `json:"tasks"`
+	}{}
+
+	uploaded, _ := os.ReadFile(filename)
+
+	if e := json.Unmarshal(uploaded, &saved); e != nil {
+		t.Fatalf("Unexpected error (while reading): %v", e)
+	}
+
+	// ----------------------------------------------------------
+	
+	if saved.Version != 2 {
+		t.Errorf("Expected version 2, but got: %d", saved.Version)
+	}
+	
+	if saved.NextID != 6 {
+		t.Errorf("Expected next ID 6, but got: %d", saved.NextID)
+	}
+	
+	if len(saved.Tasks.Items) != 2 || saved.Tasks.Items[1].ID != 5 {
+		t.Errorf("Expected tasks 4 and 5, but got: %v", saved.Tasks.Items)
+	}
+}
@@ -2,6 +2,19 @@ package tasks
 
 import "time"
 
+// Next ID to assign: the saved counter, or the one after the highest ID
+func (t *Tasks) nextID() int {
+	id := max(t.NextID, 1)
+
+	for _, task := range t.Items {
+		if task.ID >= id {
+			id = task.ID + 1
+		}
+	}
+
+	return id
+}
+
 // Add task method
 func (t *Tasks) AddTask(title string, description string) error {
 	if title == "" {
@@ -9,7 +22,7 @@ func (t *Tasks) AddTask(title string, description string) error {
 	}
 
 	task := Task{
-		ID:          len(*t) + 1,
+		ID:          t.nextID(),
 		Title:       title,
 		Description: description,
 		CreatedAt:   time.Now(),
@@ -19,7 +32,8 @@ func (t *Tasks) AddTask(title string, description string) error {
 		Visible:     true,
 	}
 
-	*t = append(*t, task)
+	t.Items = append(t.Items, task)
+	t.NextID = task.ID + 1
 
 	return nil
 }
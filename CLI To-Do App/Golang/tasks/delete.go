@@ -8,8 +8,10 @@ func (t *Tasks) DeleteTask(id int) error {
 		return e
 	}
 
-	(*t)[id-1].Visible = false
-	(*t)[id-1].UpdatedAt = time.Now()
+	task := &t.Items[t.position(id)]
+
+	task.Visible = false
+	task.UpdatedAt = time.Now()
 
 	return nil
 }
@@ -0,0 +1,137 @@
+package tasks
+
+import "encoding/json"
+
+// Fields of a task used by this app (any other field is kept in Extra)
+var taskFields = []string{"ID", "Title", "Description", "CreatedAt", "UpdatedAt", "CompletedAt", "Status", "Visible"}
+
+// Task fields without the JSON methods below
+type taskJSON Task
+
+// Read a task, keeping the fields this app does not use
+func (task *Task) UnmarshalJSON(data []byte) error {
+	if e := json.Unmarshal(data, (*taskJSON)(task)); e != nil {
+		return e
+	}
+
+	var fields map[string]json.RawMessage
+
+	if e := json.Unmarshal(data, &fields); e != nil {
+		return e
+	}
+
+	for _, name := range taskFields {
+		delete(fields, name)
+	}
+
+	task.Extra = nil
+
+	if len(fields) > 0 {
+		task.Extra = fields
+	}
+
+	return nil
+}
+
+// Write a task along with the fields this app does not use
+func (task Task) MarshalJSON() ([]byte, error) {
+	data, e := json.Marshal(taskJSON(task))
+
+	if e != nil || len(task.Extra) == 0 {
+		return data, e
+	}
+
+	var fields map[string]json.RawMessage
+
+	if e := json.Unmarshal(data, &fields); e != nil {
+		return nil, e
+	}
+
+	for name, value := range task.Extra {
+		fields[name] = value
+	}
+
+	return json.Marshal(fields)
+}
+
+// Keys of an object with the tasks and the next ID to assign
+type fileLayout struct {
+	tasks  string
+	nextID string
+}
+
+// Objects the Rust app stores the tasks in: {"NextID": n, "Tasks": [...]} and, since the
+// versioned format, {"version": 2, "next_id": n, "tasks": [...]}
+var fileLayouts = []fileLayout{
+	{tasks: "Tasks", nextID: "NextID"},
+	{tasks: "tasks", nextID: "next_id"},
+}
+
+// Read the tasks from a list (this app and the first versions of the Rust app) or from an object
+// written by the Rust app, which is kept to save the tasks in the same way
+func (t *Tasks) UnmarshalJSON(data []byte) error {
+	*t = Tasks{}
+
+	var object map[string]json.RawMessage
+
+	if json.Unmarshal(data, &object) != nil {
+		return json.Unmarshal(data, &t.Items)
+	}
+
+	for _, layout := range fileLayouts {
+		items, ok := object[layout.tasks]
+
+		if !ok {
+			continue
+		}
+
+		if e := json.Unmarshal(items, &t.Items); e != nil {
+			return e
+		}
+
+		if nextID, ok := object[layout.nextID]; ok {
+			if e := json.Unmarshal(nextID, &t.NextID); e != nil {
+				return e
+			}
+		}
+
+		t.object = object
+		t.layout = layout
+
+		return nil
+	}
+
+	return ErrorUnknownFormat
+}
+
+// Write the tasks in the same way they were read: a list, or the object of the Rust app with the
+// next ID to assign (the other keys of the object are kept)
+func (t Tasks) MarshalJSON() ([]byte, error) {
+	items := t.Items
+
+	if items == nil {
+		items = []Task{}
+	}
+
+	if t.object == nil {
+		return json.Marshal(items)
+	}
+
+	object := make(map[string]json.RawMessage, len(t.object))
+
+	for key, value := range t.object {
+		object[key] = value
+	}
+
+	var e error
+
+	if object[t.layout.tasks], e = json.Marshal(items); e != nil {
+		return nil, e
+	}
+
+	if object[t.layout.nextID], e = json.Marshal(t.nextID()); e != nil {
+		return nil, e
+	}
+
+	return json.Marshal(object)
+}
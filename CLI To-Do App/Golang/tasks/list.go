@@ -12,7 +12,7 @@ func (t *Tasks) GetTasks() {
 	tasksTable.SetOutputMirror(os.Stdout)
 	tasksTable.AppendHeader(table.Row{"ID", "Título", "Descripción", "Creado en", "Actualizado en", "Estado", "Completado en"})
 
-	for _, task := range *t {
+	for _, task := range t.Items {
 		completedAt := ""
 
 		if task.Status == StatusCompleted {
@@ -8,19 +8,21 @@ func (t *Tasks) ChangeTaskStatus(id int, newStatus TaskStatus) error {
 		return e
 	}
 
+	task := &t.Items[t.position(id)]
+
 	if newStatus < StatusPending || newStatus > StatusCompleted {
 		return ErrorInvalidStatus
 	}
 
-	(*t)[id-1].Status = newStatus
+	task.Status = newStatus
 
 	if newStatus == StatusCompleted {
-		(*t)[id-1].CompletedAt = time.Now()
+		task.CompletedAt = time.Now()
 	} else {
-		(*t)[id-1].CompletedAt = time.Time{}
+		task.CompletedAt = time.Time{}
 	}
 
-	(*t)[id-1].UpdatedAt = time.Now()
+	task.UpdatedAt = time.Now()
 
 	return nil
 }
@@ -112,11 +112,11 @@ func TestAddTaskValid(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 	
-	if len(*tasks) != 1 {
-		t.Errorf("Expected 1 task, but got: %d", len(*tasks))
+	if len(tasks.Items) != 1 {
+		t.Errorf("Expected 1 task, but got: %d", len(tasks.Items))
 	}
 	
-	task := (*tasks)[0]
+	task := tasks.Items[0]
 
 	if task.Title != "Title 1 for testing" {
 		t.Errorf("Expected title \"Title 1 for testing\", but got: '%s'", task.Title)
@@ -166,12 +166,12 @@ func TestUpdateTaskTitle(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 	
-	if (*tasks)[0].Title != "Title 2 for testing" {
-		t.Errorf("Expected title \"Title 2 for testing\", but got: '%s'", (*tasks)[0].Title)
+	if tasks.Items[0].Title != "Title 2 for testing" {
+		t.Errorf("Expected title \"Title 2 for testing\", but got: '%s'", tasks.Items[0].Title)
 	}
 	
-	if (*tasks)[0].Description != "Description 1 for testing" {
-		t.Errorf("Expected description \"Description 1 for testing\", but got: '%s'", (*tasks)[0].Description)
+	if tasks.Items[0].Description != "Description 1 for testing" {
+		t.Errorf("Expected description \"Description 1 for testing\", but got: '%s'", tasks.Items[0].Description)
 	}
 }
 
@@ -189,12 +189,12 @@ func TestUpdateTaskDescription(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 	
-	if (*tasks)[0].Title != "Title 1 for testing" {
-		t.Errorf("Expected title \"Title 1 for testing\", but got: '%s'", (*tasks)[0].Title)
+	if tasks.Items[0].Title != "Title 1 for testing" {
+		t.Errorf("Expected title \"Title 1 for testing\", but got: '%s'", tasks.Items[0].Title)
 	}
 	
-	if (*tasks)[0].Description != "Description 2 for testing" {
-		t.Errorf("Expected description \"Description 2 for testing\", but got: '%s'", (*tasks)[0].Description)
+	if tasks.Items[0].Description != "Description 2 for testing" {
+		t.Errorf("Expected description \"Description 2 for testing\", but got: '%s'", tasks.Items[0].Description)
 	}
 }
 
@@ -212,12 +212,12 @@ func TestUpdateTaskBoth(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 	
-	if (*tasks)[0].Title != "Title 2 for testing" {
-		t.Errorf("Expected title \"Title 2 for testing\", but got: '%s'", (*tasks)[0].Title)
+	if tasks.Items[0].Title != "Title 2 for testing" {
+		t.Errorf("Expected title \"Title 2 for testing\", but got: '%s'", tasks.Items[0].Title)
 	}
 	
-	if (*tasks)[0].Description != "Description 2 for testing" {
-		t.Errorf("Expected description \"Description 2 for testing\", but got: '%s'", (*tasks)[0].Description)
+	if tasks.Items[0].Description != "Description 2 for testing" {
+		t.Errorf("Expected description \"Description 2 for testing\", but got: '%s'", tasks.Items[0].Description)
 	}
 }
 
@@ -257,13 +257,13 @@ func TestUpdateTaskUpdatesTimestamp(t *testing.T) {
 
 	tasks.AddTask("Title 1 for testing", "Description 1 for testing")
 	
-	createdAt := (*tasks)[0].UpdatedAt
+	createdAt := tasks.Items[0].UpdatedAt
 
 	time.Sleep(10 * time.Millisecond)
 	
 	tasks.UpdateTask(1, "Title 2 for testing", "Description 2 for testing")
 	
-	if !(*tasks)[0].UpdatedAt.After(createdAt) {
+	if !tasks.Items[0].UpdatedAt.After(createdAt) {
 		t.Error("Expected UpdatedAt to be updated, but it's not")
 	}
 }
@@ -283,11 +283,11 @@ func TestChangeTaskStatusToPending(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 	
-	if (*tasks)[0].Status != StatusPending {
-		t.Errorf("Expected status \"Pendiente\", but got: %v", (*tasks)[0].Status.Stringify())
+	if tasks.Items[0].Status != StatusPending {
+		t.Errorf("Expected status \"Pendiente\", but got: %v", tasks.Items[0].Status.Stringify())
 	}
 	
-	if !(*tasks)[0].CompletedAt.IsZero() {
+	if !tasks.Items[0].CompletedAt.IsZero() {
 		t.Error("Expected CompletedAt to be cleared, but it's not")
 	}
 }
@@ -306,8 +306,8 @@ func TestChangeTaskStatusToInProgress(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 	
-	if (*tasks)[0].Status != StatusInProgress {
-		t.Errorf("Expected status \"En progreso\", but got: %v", (*tasks)[0].Status.Stringify())
+	if tasks.Items[0].Status != StatusInProgress {
+		t.Errorf("Expected status \"En progreso\", but got: %v", tasks.Items[0].Status.Stringify())
 	}
 }
 
@@ -325,11 +325,11 @@ func TestChangeTaskStatusToCompleted(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 	
-	if (*tasks)[0].Status != StatusCompleted {
-		t.Errorf("Expected status \"Completado\", but got: %v", (*tasks)[0].Status.Stringify())
+	if tasks.Items[0].Status != StatusCompleted {
+		t.Errorf("Expected status \"Completado\", but got: %v", tasks.Items[0].Status.Stringify())
 	}
 	
-	if (*tasks)[0].CompletedAt.IsZero() {
+	if tasks.Items[0].CompletedAt.IsZero() {
 		t.Error("Expected CompletedAt to be set, but it's not")
 	}
 }
@@ -370,13 +370,13 @@ func TestChangeTaskStatusUpdatesTimestamp(t *testing.T) {
 	
 	tasks.AddTask("Title 1 for testing", "Description 1 for testing")
 	
-	createdAt := (*tasks)[0].UpdatedAt
+	createdAt := tasks.Items[0].UpdatedAt
 
 	time.Sleep(10 * time.Millisecond)
 	
 	tasks.ChangeTaskStatus(1, StatusCompleted)
 	
-	if !(*tasks)[0].UpdatedAt.After(createdAt) {
+	if !tasks.Items[0].UpdatedAt.After(createdAt) {
 		t.Error("Expected UpdatedAt to be updated, but it's not")
 	}
 }
@@ -395,7 +395,7 @@ func TestDeleteTask(t *testing.T) {
 		t.Errorf("Unexpected error: %v", e)
 	}
 	
-	if (*tasks)[0].Visible {
+	if tasks.Items[0].Visible {
 		t.Error("Expected task to be not visible, but it is")
 	}
 }
@@ -421,13 +421,48 @@ func TestDeleteTaskUpdatesTimestamp(t *testing.T) {
 	
 	tasks.AddTask("Title 1 for testing", "Description 1 for testing")
 	
-	createdAt := (*tasks)[0].UpdatedAt
+	createdAt := tasks.Items[0].UpdatedAt
 	
 	time.Sleep(10 * time.Millisecond)
 	
 	tasks.DeleteTask(1)
 	
-	if !(*tasks)[0].UpdatedAt.After(createdAt) {
+	if !tasks.Items[0].UpdatedAt.After(createdAt) {
 		t.Error("Expected UpdatedAt to be updated, but it's not")
 	}
 }
+
+// Test: Tasks are found by ID, and new IDs continue after the saved counter
+func TestTasksFoundByID(t *testing.T) {
+	defer testutil.SuppressOutput(t)()
+	
+	tasks := &Tasks{
+		Items: []Task{
+			{ID: 2, Title: "Title 2 for testing", Visible: true},
+			{ID: 5, Title: "Title 5 for testing", Visible: true},
+		},
+		NextID: 8,
+	}
+	
+	if e := tasks.ValidateTaskID(1); e != ErrorInvalidTaskID {
+		t.Errorf("Expected ErrorInvalidTaskID, but got: %v", e)
+	}
+	
+	if e := tasks.UpdateTask(5, "Title updated", ""); e != nil {
+		t.Errorf("Unexpected error: %v", e)
+	}
+	
+	if tasks.Items[1].Title != "Title updated" || tasks.Items[0].Title != "Title 2 for testing" {
+		t.Errorf("Expected only task 5 to be updated, but got: '%s', '%s'", tasks.Items[0].Title, tasks.Items[1].Title)
+	}
+	
+	tasks.AddTask("Title 8 for testing", "")
+	
+	if tasks.Items[2].ID != 8 {
+		t.Errorf("Expected ID 8, but got: %d", tasks.Items[2].ID)
+	}
+	
+	if tasks.NextID != 9 {
+		t.Errorf("Expected next ID 9, but got: %d", tasks.NextID)
+	}
+}
@@ -1,6 +1,7 @@
 package tasks
 
 import (
+	"encoding/json"
 	"errors"
 	"time"
 )
@@ -12,6 +13,7 @@ var (
 	ErrorEmptyTitle    = errors.New("El título de la tarea no puede estar vacío")
 	ErrorEmptyFields   = errors.New("Al menos un campo debe ser proporcionado para actualizar la tarea")
 	ErrorInvalidStatus = errors.New("Estado de tarea inválido")
+	ErrorUnknownFormat = errors.New("Formato del archivo de tareas desconocido")
 )
 
 // Type for task status
@@ -48,7 +50,18 @@ type Task struct {
 	CompletedAt time.Time
 	Status      TaskStatus
 	Visible     bool
+
+	// Fields saved by the Rust app that this app does not use, written back as they were read
+	Extra map[string]json.RawMessage `json:"-"`
 }
 
-// Tasks array
-type Tasks []Task
+// Tasks list, with the next ID to assign (IDs are not reused and do not depend on the position
+// of the task in the list)
+type Tasks struct {
+	Items  []Task
+	NextID int
+
+	// Object the tasks were read from, kept to save them in the same way
+	object map[string]json.RawMessage
+	layout fileLayout
+}
@@ -8,19 +8,21 @@ func (t *Tasks) UpdateTask(id int, title string, description string) error {
 		return e
 	}
 
+	task := &t.Items[t.position(id)]
+
 	if title == "" && description == "" {
 		return ErrorEmptyFields
 	}
 
 	if title != "" {
-		(*t)[id-1].Title = title
+		task.Title = title
 	}
 
 	if description != "" {
-		(*t)[id-1].Description = description
+		task.Description = description
 	}
 
-	(*t)[id-1].UpdatedAt = time.Now()
+	task.UpdatedAt = time.Now()
 
 	return nil
 }
@@ -1,12 +1,25 @@
 package tasks
 
+// Position of the task with the given ID in the list, or -1 when there is none
+func (t *Tasks) position(id int) int {
+	for i, task := range t.Items {
+		if task.ID == id {
+			return i
+		}
+	}
+
+	return -1
+}
+
 // Validate task ID method
 func (t *Tasks) ValidateTaskID(id int) error {
-	if id < 1 || id > len(*t) {
+	i := t.position(id)
+
+	if i < 0 {
 		return ErrorInvalidTaskID
 	}
 
-	if !(*t)[id-1].Visible {
+	if !t.Items[i].Visible {
 		return ErrorTaskNotFound
 	}
 
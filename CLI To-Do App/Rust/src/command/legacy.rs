@@ -0,0 +1,105 @@
+use clap::Args;
+
+use super::{AddArgs, Command, EditArgs, ListArgs, PriorityArg, Selection, StatusArg, TaskIds};
+
+// Legacy flag-based interface (--add, --update <ID>, ...), kept hidden so existing scripts keep working
+#[derive(Args, Debug, Clone, Default)]
+pub struct LegacyFlags {
+    #[command(flatten)]
+    action: LegacyAction,
+
+    // Task attributes: title
+    #[arg(long, hide = true)]
+    title: Option<String>,
+
+    // Task attributes: description
+    #[arg(long, hide = true)]
+    description: Option<String>,
+}
+
+// Legacy actions: only one of them can be used per invocation
+#[derive(Args, Debug, Clone, Default)]
+#[group(multiple = false)]
+struct LegacyAction {
+    // List tasks
+    #[arg(long, hide = true)]
+    list: bool,
+
+    // Add task
+    #[arg(long, hide = true)]
+    add: bool,
+
+    // Update tasks (IDs)
+    #[arg(long, value_name = "IDS", value_parser = super::parse_ids, hide = true)]
+    update: Option<TaskIds>,
+
+    // Delete tasks (IDs)
+    #[arg(long, value_name = "IDS", value_parser = super::parse_ids, hide = true)]
+    delete: Option<TaskIds>,
+
+    // Mark tasks as pending (IDs)
+    #[arg(long, value_name = "IDS", value_parser = super::parse_ids, hide = true)]
+    pending: Option<TaskIds>,
+
+    // Mark tasks as in progress (IDs)
+    #[arg(long = "in-progress", value_name = "IDS", value_parser = super::parse_ids, hide = true)]
+    in_progress: Option<TaskIds>,
+
+    // Mark tasks as completed (IDs)
+    #[arg(long, value_name = "IDS", value_parser = super::parse_ids, hide = true)]
+    completed: Option<TaskIds>,
+}
+
+// LegacyFlags implementation
+impl LegacyFlags {
+    // Translate legacy flags into the equivalent subcommand (None when no legacy action was given)
+    pub fn to_command(&self) -> Option<Command> {
+        let action = &self.action;
+
+        if action.list {
+            return Some(Command::List(ListArgs::default()));
+        }
+
+        if action.add {
+            return Some(Command::Add(AddArgs {
+                title: self.title.clone().unwrap_or_default(),
+                description: self.description.clone().unwrap_or_default(),
+                priority: PriorityArg::Medium,
+                due: None,
+                tags: Vec::new(),
+                project: None,
+                parent: None,
+                depends_on: Vec::new(),
+                repeat: None,
+            }));
+        }
+
+        if let Some(ids) = &action.update {
+            return Some(Command::Edit(EditArgs {
+                ids: Some(ids.clone()),
+                selection: Selection::default(),
+                title: self.title.clone(),
+                description: self.description.clone(),
+                priority: None,
+                due: None,
+                no_due: false,
+                repeat: None,
+                no_repeat: false,
+            }));
+        }
+
+        if let Some(ids) = &action.delete {
+            return Some(Command::Rm { ids: Some(ids.clone()), selection: Selection::default() });
+        }
+
+        let status = [
+            (&action.pending, StatusArg::Pending),
+            (&action.in_progress, StatusArg::InProgress),
+            (&action.completed, StatusArg::Completed),
+        ];
+
+        status.into_iter().find_map(|(ids, status)| {
+            ids.clone().map(|ids| Command::Status { ids: Some(ids), status, cascade: false, force: false, selection: Selection::default() })
+        })
+    }
+}
@@ -1,13 +1,32 @@
-use clap::{ArgAction, Parser};
+use chrono::{DateTime, FixedOffset, TimeDelta};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use comfy_table::{Cell, Color, Table};
+use std::collections::BTreeMap;
 use std::fmt;
+use std::time::Duration;
 
-use crate::tasks::{TaskStatus, Tasks};
+use crate::config::{self, Config, CONFIG_FILE};
+use crate::dates::{self, now_fixed};
+use crate::editor;
+use crate::journal::JournalEntry;
+use crate::query::Query;
+use crate::render::{render_tasks, Columns, Layout, OutputFormat, SortOrder};
+use crate::search::SearchQuery;
+use crate::storage::{open_store, Storage, StorageError, StoreKind, TaskStore};
+use crate::tasks::{normalize_project, normalize_tag, DueFilter, Recurrence, TaskError, TaskFilter, TaskOptions, TaskPriority, TaskStatus, Tasks};
+use crate::tui;
+
+mod legacy;
+
+use legacy::LegacyFlags;
 
 // Errors shown to the user when handling commands
 #[derive(Debug)]
 pub enum CommandError {
     InvalidArgs(String),
     TaskError(String),
+    // A command on several tasks failed for some of them (the others were changed)
+    Partial { failed: usize, total: usize },
 }
 
 // Implement fmt::Display for CommandError
@@ -16,53 +35,661 @@ impl fmt::Display for CommandError {
         match self {
             CommandError::InvalidArgs(s) => write!(f, "{}", s),
             CommandError::TaskError(s) => write!(f, "{}", s),
+            CommandError::Partial { failed, total } => write!(f, "El comando falló en {} de {} tareas", failed, total),
         }
     }
 }
 
-// Define CLI interface, using clap for flags and arguments parsing
+// File where archived tasks are kept
+const ARCHIVE_FILE: &str = "tasks.archive.json";
+
+// Define CLI interface, using clap subcommands (legacy flags are handled by the compatibility layer)
 #[derive(Parser, Debug, Clone)]
-#[command(author, version, about = "CLI To-Do App", long_about = None, disable_help_flag = true)]
+#[command(author, version, about = "CLI To-Do App", long_about = None, args_conflicts_with_subcommands = true)]
 pub struct Commands {
-    // List commands
-    #[arg(long, action = ArgAction::SetTrue)]
-    help: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    legacy: LegacyFlags,
+
+    // Maximum time to wait while another invocation is using the data file
+    #[arg(long, global = true, value_name = "SEGUNDOS", default_value = "5", value_parser = parse_seconds, help = "Segundos de espera si otra ejecución está usando el archivo de tareas")]
+    lock_timeout: Duration,
+
+    // Storage backend (overrides the configuration file)
+    #[arg(long, global = true, value_name = "BACKEND", help = "Dónde se guardan las tareas (por defecto el de todo.toml, o json)")]
+    store: Option<StoreArg>,
+
+    // Configuration file where saved queries are kept
+    #[arg(skip = CONFIG_FILE.to_string())]
+    config_file: String,
 
+    // Configuration (saved queries and list layout)
+    #[arg(skip)]
+    config: Config,
+}
+
+// Supported subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
     // List tasks
-    #[arg(long, action = ArgAction::SetTrue)]
-    list: bool,
+    #[command(about = "Listar todas las tareas")]
+    List(ListArgs),
 
     // Add task
-    #[arg(long, action = ArgAction::SetTrue)]
-    add: bool,
+    #[command(about = "Crear una nueva tarea")]
+    Add(AddArgs),
+
+    // Update task
+    #[command(visible_alias = "update", about = "Actualizar una tarea existente")]
+    Edit(EditArgs),
+
+    // Delete tasks
+    #[command(visible_alias = "delete", about = "Eliminar tareas existentes")]
+    Rm {
+        #[arg(value_name = "IDS", value_parser = parse_ids, required_unless_present = "filter", conflicts_with = "filter", help = IDS_HELP)]
+        ids: Option<TaskIds>,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+
+    // List deleted tasks
+    #[command(about = "Listar las tareas eliminadas")]
+    Trash,
+
+    // Restore deleted tasks
+    #[command(about = "Recuperar tareas eliminadas (junto con las subtareas eliminadas con ellas)")]
+    Restore {
+        #[arg(value_name = "IDS", value_parser = parse_ids, required_unless_present = "filter", conflicts_with = "filter", help = IDS_HELP)]
+        ids: Option<TaskIds>,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+
+    // Permanently remove deleted tasks
+    #[command(about = "Eliminar definitivamente las tareas de la papelera")]
+    Purge {
+        #[arg(long, value_name = "EDAD", value_parser = parse_age, help = "Solo las eliminadas hace más de este tiempo (p. ej. 30d, 2w, 12h)")]
+        older_than: Option<TimeDelta>,
+    },
+
+    // Move old completed tasks to the archive file
+    #[command(about = "Mover las tareas completadas hace tiempo a un archivo aparte")]
+    Archive {
+        #[arg(long, value_name = "EDAD", default_value = "30d", value_parser = parse_age, help = "Solo las completadas hace más de este tiempo (p. ej. 30d, 2w, 12h)")]
+        older_than: TimeDelta,
+
+        #[arg(long, value_name = "ARCHIVO", default_value = ARCHIVE_FILE, help = "Archivo donde se guardan las tareas archivadas")]
+        file: String,
+    },
+
+    // Change task status
+    #[command(allow_missing_positional = true, about = "Cambiar el estado de tareas existentes")]
+    Status {
+        #[arg(value_name = "IDS", value_parser = parse_ids, required_unless_present = "filter", conflicts_with = "filter", help = IDS_HELP)]
+        ids: Option<TaskIds>,
+
+        #[arg(value_name = "ESTADO", help = "Nuevo estado de las tareas")]
+        status: StatusArg,
+
+        #[arg(long, help = "Al completar, completar también las subtareas abiertas")]
+        cascade: bool,
+
+        #[arg(long, conflicts_with = "cascade", help = "Cambiar el estado aunque la tarea tenga dependencias sin completar")]
+        force: bool,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+
+    // Complete tasks
+    #[command(about = "Marcar tareas como completadas (igual que status <IDS> completed)")]
+    Done {
+        #[arg(value_name = "IDS", value_parser = parse_ids, required_unless_present = "filter", conflicts_with = "filter", help = IDS_HELP)]
+        ids: Option<TaskIds>,
+
+        #[arg(long, help = "Completar también las subtareas abiertas")]
+        cascade: bool,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
 
-    // Update task (ID)
-    #[arg(long, default_value_t = 0)]
-    update: i32,
+    // Add tags to tasks
+    #[command(allow_missing_positional = true, about = "Agregar etiquetas a tareas existentes")]
+    Tag {
+        #[arg(value_name = "IDS", value_parser = parse_ids, required_unless_present = "filter", conflicts_with = "filter", help = IDS_HELP)]
+        ids: Option<TaskIds>,
+
+        #[arg(value_name = "+ETIQUETA", required = true, help = "Etiquetas a agregar (p. ej. +trabajo +urgente)")]
+        tags: Vec<String>,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+
+    // Remove tags from tasks
+    #[command(allow_missing_positional = true, about = "Quitar etiquetas de tareas existentes")]
+    Untag {
+        #[arg(value_name = "IDS", value_parser = parse_ids, required_unless_present = "filter", conflicts_with = "filter", help = IDS_HELP)]
+        ids: Option<TaskIds>,
+
+        #[arg(value_name = "+ETIQUETA", required = true, help = "Etiquetas a quitar")]
+        tags: Vec<String>,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+
+    // List tags
+    #[command(about = "Listar las etiquetas con la cantidad de tareas abiertas y completadas")]
+    Tags,
+
+    // Move tasks to another project
+    #[command(visible_alias = "mv", allow_missing_positional = true, about = "Mover tareas existentes a otro proyecto")]
+    Move {
+        #[arg(value_name = "IDS", value_parser = parse_ids, required_unless_present = "filter", conflicts_with = "filter", help = IDS_HELP)]
+        ids: Option<TaskIds>,
+
+        #[arg(value_name = "PROYECTO", help = "Proyecto de destino (p. ej. trabajo.backend)")]
+        project: String,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+
+    // List projects
+    #[command(about = "Listar los proyectos con su progreso")]
+    Projects,
+
+    // Add dependencies to tasks
+    #[command(allow_missing_positional = true, about = "Indicar que tareas dependen de otras")]
+    Depend {
+        #[arg(value_name = "IDS", value_parser = parse_ids, required_unless_present = "filter", conflicts_with = "filter", help = IDS_HELP)]
+        ids: Option<TaskIds>,
+
+        #[arg(value_name = "DEPENDENCIA", required = true, value_delimiter = ',', help = "IDs de las tareas que deben completarse antes")]
+        depends_on: Vec<usize>,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+
+    // Remove dependencies from tasks
+    #[command(allow_missing_positional = true, about = "Quitar dependencias de tareas")]
+    Undepend {
+        #[arg(value_name = "IDS", value_parser = parse_ids, required_unless_present = "filter", conflicts_with = "filter", help = IDS_HELP)]
+        ids: Option<TaskIds>,
+
+        #[arg(value_name = "DEPENDENCIA", required = true, value_delimiter = ',', help = "IDs de las dependencias a quitar")]
+        depends_on: Vec<usize>,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+
+    // List actionable tasks
+    #[command(about = "Listar las tareas pendientes que no están bloqueadas, por prioridad")]
+    Next,
+
+    // Search titles and descriptions
+    #[command(about = "Buscar texto en los títulos y descripciones de las tareas")]
+    Search(SearchArgs),
+
+    // Interactive terminal interface
+    #[command(visible_alias = "ui", about = "Abrir la interfaz interactiva en la terminal")]
+    Tui,
+
+    // Upgrade the data file to the current format version
+    #[command(about = "Actualizar el archivo de tareas a la versión actual del formato")]
+    Migrate {
+        #[arg(long, help = "Mostrar las migraciones que se aplicarían sin modificar el archivo")]
+        dry_run: bool,
+    },
+
+    // Revert the last command
+    #[command(about = "Deshacer el último comando que modificó las tareas")]
+    Undo,
+
+    // Apply again the last undone command
+    #[command(about = "Rehacer el último comando deshecho")]
+    Redo,
+
+    // Show the event history
+    #[command(about = "Mostrar el historial de cambios de las tareas (requiere --store log)")]
+    Log {
+        #[arg(value_name = "ID", help = "Mostrar solo los eventos de esta tarea")]
+        id: Option<usize>,
+    },
+
+    // Manage storage backends
+    #[command(about = "Administrar el almacenamiento de las tareas")]
+    Storage {
+        #[command(subcommand)]
+        action: StorageAction,
+    },
+
+    // Manage saved queries
+    #[command(about = "Administrar las consultas guardadas (se usan como @nombre en list)")]
+    Query {
+        #[command(subcommand)]
+        action: QueryAction,
+    },
+}
 
-    // Delete task (ID)
-    #[arg(long, default_value_t = 0)]
-    delete: i32,
+// Subcommands of the query subcommand
+#[derive(Subcommand, Debug, Clone)]
+pub enum QueryAction {
+    // Save a query under a name
+    #[command(about = "Guardar una consulta con un nombre (reemplaza la que tenga el mismo nombre)")]
+    Save {
+        #[arg(value_name = "NOMBRE", help = "Nombre de la consulta (letras, números, - y _)")]
+        name: String,
 
+        #[arg(value_name = "CONSULTA", help = "Consulta (p. ej. 'status:pending and tag:trabajo')")]
+        query: String,
+    },
+
+    // Show the saved queries
+    #[command(about = "Mostrar las consultas guardadas")]
+    List,
+
+    // Remove a saved query
+    #[command(visible_alias = "rm", about = "Eliminar una consulta guardada")]
+    Remove {
+        #[arg(value_name = "NOMBRE", help = "Nombre de la consulta")]
+        name: String,
+    },
+}
+
+// Subcommands of the storage subcommand
+#[derive(Subcommand, Debug, Clone)]
+pub enum StorageAction {
+    // Copy the tasks from one backend to another
+    #[command(about = "Copiar las tareas de un almacenamiento a otro (p. ej. --from json --to sqlite)")]
+    Convert {
+        #[arg(long, value_name = "BACKEND", help = "Almacenamiento de origen")]
+        from: StoreArg,
+
+        #[arg(long, value_name = "BACKEND", help = "Almacenamiento de destino")]
+        to: StoreArg,
+
+        #[arg(long, value_name = "ARCHIVO", help = "Archivo de origen (por defecto tasks.json, tasks.db o tasks.log)")]
+        from_file: Option<String>,
+
+        #[arg(long, value_name = "ARCHIVO", help = "Archivo de destino (por defecto tasks.json, tasks.db o tasks.log)")]
+        to_file: Option<String>,
+
+        #[arg(long, help = "Reemplazar las tareas que ya haya en el destino")]
+        force: bool,
+    },
+
+    // Fold the event log into a snapshot
+    #[command(about = "Compactar el historial de eventos en una instantánea (descarta el historial anterior)")]
+    Compact,
+}
+
+// Arguments for the list subcommand
+#[derive(Args, Debug, Clone, Default)]
+pub struct ListArgs {
+    // Filter: query expression
+    #[arg(value_name = "CONSULTA", help = "Consulta (p. ej. 'status:pending and (tag:trabajo or priority>=high) and due<friday')")]
+    pub query: Option<String>,
+
+    // Filter: priority
+    #[arg(long, short, value_name = "PRIORIDAD", help = "Mostrar solo las tareas con esta prioridad")]
+    pub priority: Option<PriorityArg>,
+
+    // Filter: due date view
+    #[arg(long, value_name = "VISTA", help = "Mostrar solo las tareas vencidas, que vencen hoy o esta semana")]
+    pub due: Option<DueArg>,
+
+    // Filter: tags the task must have
+    #[arg(long = "tag", value_name = "ETIQUETA", help = "Mostrar solo las tareas con esta etiqueta (se puede repetir)")]
+    pub tags: Vec<String>,
+
+    // Filter: tags the task must not have
+    #[arg(long = "not-tag", value_name = "ETIQUETA", help = "Ocultar las tareas con esta etiqueta (se puede repetir)")]
+    pub not_tags: Vec<String>,
+
+    // Filter: project (including nested projects)
+    #[arg(long, short = 'P', value_name = "PROYECTO", help = "Mostrar solo las tareas del proyecto (incluye subproyectos)")]
+    pub project: Option<String>,
+
+    // Filter: status
+    #[arg(long, short, value_name = "ESTADO", help = "Mostrar solo las tareas con este estado")]
+    pub status: Option<StatusArg>,
+
+    // Filter: blocked tasks
+    #[arg(long, help = "Mostrar solo las tareas bloqueadas por dependencias sin completar")]
+    pub blocked: bool,
+
+    // Filter: actionable tasks
+    #[arg(long, conflicts_with = "blocked", help = "Mostrar solo las tareas que no están bloqueadas")]
+    pub actionable: bool,
+
+    // Output format
+    #[arg(long, short, value_name = "FORMATO", default_value = "table", help = "Formato de salida")]
+    pub format: FormatArg,
+
+    // Sort order
+    #[arg(long, value_name = "CAMPOS", value_parser = parse_sort, help = "Orden de la lista (p. ej. due,-priority,created; - para descendente)")]
+    pub sort: Option<SortOrder>,
+
+    // Columns of the table and Markdown formats
+    #[arg(long, short, value_name = "COLUMNAS", value_parser = parse_columns, help = "Columnas a mostrar (p. ej. id,title,status,due)")]
+    pub columns: Option<Columns>,
+
+    // Show long texts in full
+    #[arg(long, short, help = "Mostrar los textos completos, sin ajustar la tabla al ancho de la terminal")]
+    pub wide: bool,
+}
+
+// Help of the IDs argument of the commands that change tasks
+const IDS_HELP: &str = "IDs de las tareas (p. ej. 3 o 3,5,9-12)";
+
+// IDs of tasks given on the command line, in order and without repetitions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskIds(pub Vec<usize>);
+
+// Tasks a command applies to when they are not given by ID
+#[derive(Args, Debug, Clone, Default)]
+pub struct Selection {
+    // Query selecting the tasks
+    #[arg(long = "where", value_name = "CONSULTA", help = "Aplicar a las tareas que cumplen la consulta (p. ej. tag:sprint-12), en lugar de a los IDS")]
+    pub filter: Option<String>,
+
+    // Skip the confirmation
+    #[arg(long, short, help = "No pedir confirmación aunque el comando afecte a muchas tareas")]
+    pub yes: bool,
+}
+
+// Arguments for the search subcommand
+#[derive(Args, Debug, Clone)]
+pub struct SearchArgs {
+    // Words, phrases and prefixes to search for
+    #[arg(value_name = "TÉRMINOS", required = true, help = "Palabras a buscar: informe, \"pago de la luz\" (frase), fact* (prefijo)")]
+    pub terms: Vec<String>,
+
+    // Maximum number of results
+    #[arg(long, short = 'n', value_name = "N", default_value = "20", help = "Cantidad máxima de resultados")]
+    pub limit: usize,
+}
+
+// Arguments for the add subcommand
+#[derive(Args, Debug, Clone)]
+pub struct AddArgs {
     // Task attributes: title
-    #[arg(long, default_value = "")]
-    title: String,
+    #[arg(long, short, help = "Título de la tarea")]
+    pub title: String,
 
     // Task attributes: description
-    #[arg(long, default_value = "")]
-    description: String,
+    #[arg(long, short, default_value = "", help = "Descripción de la tarea")]
+    pub description: String,
+
+    // Task attributes: priority
+    #[arg(long, short, value_name = "PRIORIDAD", default_value = "medium", help = "Prioridad de la tarea")]
+    pub priority: PriorityArg,
+
+    // Task attributes: due date
+    #[arg(long, value_name = "FECHA", value_parser = parse_date, help = "Fecha de vencimiento (p. ej. mañana, next friday, in 3 days, 2025-11-02 17:00)")]
+    pub due: Option<DateTime<FixedOffset>>,
+
+    // Task attributes: tags
+    #[arg(value_name = "+ETIQUETA", help = "Etiquetas de la tarea (p. ej. +trabajo +urgente)")]
+    pub tags: Vec<String>,
+
+    // Task attributes: project
+    #[arg(long, short = 'P', value_name = "PROYECTO", help = "Proyecto de la tarea (p. ej. trabajo.backend, por defecto el de la tarea padre)")]
+    pub project: Option<String>,
+
+    // Task attributes: parent task
+    #[arg(long, value_name = "ID", help = "Crear como subtarea de otra tarea")]
+    pub parent: Option<usize>,
+
+    // Task attributes: dependencies
+    #[arg(long, value_name = "ID", value_delimiter = ',', help = "IDs de las tareas que deben completarse antes (p. ej. 3,5)")]
+    pub depends_on: Vec<usize>,
+
+    // Task attributes: recurrence rule
+    #[arg(long, value_name = "REGLA", value_parser = parse_recurrence, help = "Repetir la tarea al completarla (daily, weekly:mon,fri, monthly:15, every:3)")]
+    pub repeat: Option<Recurrence>,
+}
+
+// Arguments for the edit subcommand (without attributes, the task is opened in the editor)
+#[derive(Args, Debug, Clone)]
+pub struct EditArgs {
+    #[arg(value_name = "IDS", value_parser = parse_ids, required_unless_present = "filter", conflicts_with = "filter", help = IDS_HELP)]
+    pub ids: Option<TaskIds>,
+
+    #[command(flatten)]
+    pub selection: Selection,
+
+    // Task attributes: title
+    #[arg(long, short, help = "Nuevo título de la tarea")]
+    pub title: Option<String>,
+
+    // Task attributes: description
+    #[arg(long, short, help = "Nueva descripción de la tarea")]
+    pub description: Option<String>,
+
+    // Task attributes: priority
+    #[arg(long, short, value_name = "PRIORIDAD", help = "Nueva prioridad de la tarea")]
+    pub priority: Option<PriorityArg>,
+
+    // Task attributes: due date
+    #[arg(long, value_name = "FECHA", value_parser = parse_date, help = "Nueva fecha de vencimiento (p. ej. mañana, próximo lunes, eow)")]
+    pub due: Option<DateTime<FixedOffset>>,
+
+    // Remove the due date
+    #[arg(long, conflicts_with = "due", help = "Eliminar la fecha de vencimiento")]
+    pub no_due: bool,
+
+    // Task attributes: recurrence rule
+    #[arg(long, value_name = "REGLA", value_parser = parse_recurrence, help = "Nueva regla de repetición (daily, weekly:mon,fri, monthly:15, every:3)")]
+    pub repeat: Option<Recurrence>,
 
-    // Mark task as pending (ID)
-    #[arg(long = "pending", default_value_t = 0)]
-    pending: i32,
+    // Stop repeating the task
+    #[arg(long, conflicts_with = "repeat", help = "Dejar de repetir la tarea")]
+    pub no_repeat: bool,
+}
+
+// Task status as accepted from the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusArg {
+    Pending,
+    InProgress,
+    Completed,
+}
+
+// Convert StatusArg into TaskStatus
+impl From<StatusArg> for TaskStatus {
+    fn from(status: StatusArg) -> Self {
+        match status {
+            StatusArg::Pending => TaskStatus::Pending,
+            StatusArg::InProgress => TaskStatus::InProgress,
+            StatusArg::Completed => TaskStatus::Completed,
+        }
+    }
+}
+
+// Task priority as accepted from the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityArg {
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+// Convert PriorityArg into TaskPriority
+impl From<PriorityArg> for TaskPriority {
+    fn from(priority: PriorityArg) -> Self {
+        match priority {
+            PriorityArg::Low => TaskPriority::Low,
+            PriorityArg::Medium => TaskPriority::Medium,
+            PriorityArg::High => TaskPriority::High,
+            PriorityArg::Urgent => TaskPriority::Urgent,
+        }
+    }
+}
+
+// Due date views as accepted from the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueArg {
+    Overdue,
+    Today,
+    Week,
+}
+
+// Convert DueArg into DueFilter
+impl From<DueArg> for DueFilter {
+    fn from(due: DueArg) -> Self {
+        match due {
+            DueArg::Overdue => DueFilter::Overdue,
+            DueArg::Today => DueFilter::Today,
+            DueArg::Week => DueFilter::Week,
+        }
+    }
+}
+
+// Output formats as accepted from the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormatArg {
+    #[default]
+    Table,
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+    Markdown,
+    Plain,
+}
+
+// Convert FormatArg into OutputFormat
+impl From<FormatArg> for OutputFormat {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Table => OutputFormat::Table,
+            FormatArg::Json => OutputFormat::Json,
+            FormatArg::Ndjson => OutputFormat::Ndjson,
+            FormatArg::Csv => OutputFormat::Csv,
+            FormatArg::Tsv => OutputFormat::Tsv,
+            FormatArg::Markdown => OutputFormat::Markdown,
+            FormatArg::Plain => OutputFormat::Plain,
+        }
+    }
+}
+
+// Storage backends as accepted from the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreArg {
+    Json,
+    Sqlite,
+    Log,
+}
+
+// Convert StoreArg into StoreKind
+impl From<StoreArg> for StoreKind {
+    fn from(store: StoreArg) -> Self {
+        match store {
+            StoreArg::Json => StoreKind::Json,
+            StoreArg::Sqlite => StoreKind::Sqlite,
+            StoreArg::Log => StoreKind::Log,
+        }
+    }
+}
+
+// Parse a date argument (natural language, relative or absolute)
+fn parse_date(value: &str) -> Result<DateTime<FixedOffset>, String> {
+    dates::parse_date_now(value).map_err(|e| e.to_string())
+}
+
+// Parse a number of seconds (decimals allowed)
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("Cantidad de segundos inválida: \"{}\"", value))
+}
+
+// Parse an age argument: a number followed by h (hours), d (days) or w (weeks); days by default
+fn parse_age(value: &str) -> Result<TimeDelta, String> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(position) => value.split_at(position),
+        None => (value, "d"),
+    };
+
+    let age = number.parse::<i64>().ok().and_then(|number| match unit {
+        "h" => TimeDelta::try_hours(number),
+        "d" => TimeDelta::try_days(number),
+        "w" => TimeDelta::try_weeks(number),
+        _ => None,
+    });
+
+    age.ok_or_else(|| format!("Edad inválida: \"{}\" (use p. ej. 30d, 2w o 12h)", value))
+}
 
-    // Mark task as in progress (ID)
-    #[arg(long = "in-progress", default_value_t = 0)]
-    in_progress: i32,
+// Parse a list of task IDs and ranges: "3,5,9-12"
+fn parse_ids(value: &str) -> Result<TaskIds, String> {
+    let invalid = || format!("IDs inválidos: \"{}\" (use p. ej. 3 o 3,5,9-12)", value);
+    let mut ids = Vec::new();
+
+    for part in value.split(',') {
+        let part = part.trim();
+
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (first.trim().parse().map_err(|_| invalid())?, last.trim().parse().map_err(|_| invalid())?),
+            None => {
+                let id = part.parse().map_err(|_| invalid())?;
+                (id, id)
+            }
+        };
+
+        if first > last {
+            return Err(format!("Rango inválido: \"{}\" (el primer ID debe ser menor que el último)", part));
+        }
+
+        for id in first..=last {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+
+    Ok(TaskIds(ids))
+}
+
+// Parse a sort order argument
+fn parse_sort(value: &str) -> Result<SortOrder, String> {
+    value.parse()
+}
 
-    // Mark task as completed (ID)
-    #[arg(long = "completed", default_value_t = 0)]
-    completed: i32,
+// Parse a columns argument
+fn parse_columns(value: &str) -> Result<Columns, String> {
+    value.parse()
+}
+
+// Parse a recurrence rule argument
+fn parse_recurrence(value: &str) -> Result<Recurrence, String> {
+    value.parse::<Recurrence>().map_err(|e| e.to_string())
+}
+
+// Normalize tags given as filters
+fn normalize_tags(tags: &[String]) -> Result<Vec<String>, CommandError> {
+    tags.iter()
+        .map(|tag| normalize_tag(tag).map_err(|e| CommandError::InvalidArgs(format!("{}: \"{}\"", e, tag))))
+        .collect()
+}
+
+// Format a list of task IDs for messages
+fn join_ids(ids: &[usize]) -> String {
+    ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
 }
 
 // Commands implementation
@@ -72,111 +699,854 @@ impl Commands {
         <Self as Parser>::parse()
     }
 
-    // Execute commands on the task list
-    pub fn execute(&self, tasks_list: &mut Tasks) -> Result<(), CommandError> {
-        if self.help {
-            Self::list_commands();
+    // Time to wait for the lock on the data file
+    pub fn lock_timeout(&self) -> Duration {
+        self.lock_timeout
+    }
+
+    // Storage backend selected with --store
+    pub fn store(&self) -> Option<StoreKind> {
+        self.store.map(StoreKind::from)
+    }
+
+    // Use the saved queries and list layout of the configuration (read from file_name)
+    pub fn with_config(mut self, file_name: &str, config: &Config) -> Self {
+        self.config_file = file_name.to_string();
+        self.config = config.clone();
+        self
+    }
+
+    // Resolve the command to run (subcommand first, then legacy flags)
+    fn command(&self) -> Option<Command> {
+        self.command.clone().or_else(|| self.legacy.to_command())
+    }
+
+    // Execute commands: load the tasks from the store, run the command and save the changes
+    // (nothing is saved when the command fails)
+    pub fn execute(&self, store: &mut dyn TaskStore) -> Result<(), CommandError> {
+        let Some(command) = self.command() else {
+            println!("Comando no reconocido\nUse --help para ver la lista de comandos válidos");
             return Ok(());
+        };
+
+        // Commands on the stored data itself do not load the tasks
+        match command {
+            Command::Migrate { dry_run } => return migrate(store, dry_run),
+            Command::Log { id } => return show_log(store, id),
+            Command::Query { action } => return saved_queries(action, &self.config_file, &self.config.queries),
+            Command::Storage { action: StorageAction::Compact } => return compact(store),
+            Command::Storage { action: StorageAction::Convert { from, to, from_file, to_file, force } } => {
+                let from = StoreKind::from(from);
+                let to = StoreKind::from(to);
+                let from_file = from_file.unwrap_or_else(|| from.default_file().to_string());
+                let to_file = to_file.unwrap_or_else(|| to.default_file().to_string());
+
+                return convert((from, &from_file), (to, &to_file), force, self.lock_timeout);
+            }
+            _ => {}
         }
 
-        if self.list {
-            tasks_list.get_tasks();
-            return Ok(());
+        let loaded = store
+            .load()
+            .map_err(|e| CommandError::TaskError(format!("Error al cargar las tareas -> {}", e)))?;
+        let mut tasks_list = loaded.clone();
+
+        match command {
+            Command::Undo | Command::Redo => return undo(store, &loaded, matches!(command, Command::Redo)),
+            Command::Archive { older_than, file } => return archive(store, &loaded, now_fixed() - older_than, &file, self.lock_timeout),
+            Command::Search(args) => return search(store, &loaded, &args),
+            Command::Tui => return interactive(store, loaded, &self.config),
+            _ => {}
         }
 
-        if self.add {
-            if self.title.trim().is_empty() {
+        let label = label(&command);
+
+        // A command that failed only for some tasks still saves the others
+        let result = run(command, &mut tasks_list, &self.config);
+
+        if result.as_ref().is_err_and(|e| !matches!(e, CommandError::Partial { .. })) {
+            return result;
+        }
+
+        record(store, label, &loaded, &tasks_list)?;
+
+        store
+            .save_changes(&loaded, &tasks_list)
+            .map_err(|e| CommandError::TaskError(format!("Error al guardar las tareas -> {}", e)))?;
+
+        result
+    }
+}
+
+// Record the changes of a command so it can be undone (the journal goes before the tasks, so
+// with SQLite it is committed together with them)
+fn record(store: &mut dyn TaskStore, label: String, before: &Tasks, after: &Tasks) -> Result<(), CommandError> {
+    if after == before {
+        return Ok(());
+    }
+
+    let mut journal = store
+        .load_journal()
+        .map_err(|e| CommandError::TaskError(format!("Error al cargar el historial de deshacer -> {}", e)))?;
+    journal.record(label, before, after);
+    store
+        .save_journal(&journal)
+        .map_err(|e| CommandError::TaskError(format!("Error al guardar el historial de deshacer -> {}", e)))
+}
+
+// Run a command on the task list
+fn run(command: Command, tasks_list: &mut Tasks, config: &Config) -> Result<(), CommandError> {
+    match command {
+        Command::List(args) => {
+            let filter = TaskFilter {
+                priority: args.priority.map(TaskPriority::from),
+                due: args.due.map(DueFilter::from),
+                tags: normalize_tags(&args.tags)?,
+                not_tags: normalize_tags(&args.not_tags)?,
+                project: args
+                    .project
+                    .as_deref()
+                    .map(normalize_project)
+                    .transpose()
+                    .map_err(|e| CommandError::InvalidArgs(e.to_string()))?,
+                status: args.status.map(TaskStatus::from),
+                blocked: match (args.blocked, args.actionable) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                },
+            };
+
+            let query = args
+                .query
+                .as_deref()
+                .map(|query| Query::parse(query, &config.queries, now_fixed()))
+                .transpose()
+                .map_err(|e| CommandError::InvalidArgs(format!("Consulta inválida: {}", e)))?;
+
+            // Options given on the command line take precedence over the configured layout
+            let layout = Layout {
+                columns: args.columns.or_else(|| config.list.columns.clone()).unwrap_or_default(),
+                sort: args.sort.or_else(|| config.list.sort.clone()).unwrap_or_default(),
+                wide: args.wide || config.list.wide,
+                width: None,
+            };
+
+            print_tasks(tasks_list, &filter, query.as_ref(), args.format.into(), &layout)?;
+        }
+
+        Command::Add(args) => {
+            if args.title.trim().is_empty() {
                 return Err(CommandError::InvalidArgs(
-                    "El título es obligatorio. Use: --add --title \"Título de la tarea\"".to_string(),
+                    "El título es obligatorio. Use: add --title \"Título de la tarea\"".to_string(),
                 ));
             }
-            
-            if let Err(e) = tasks_list.add_task(self.title.clone(), self.description.clone()) {
+
+            let options = TaskOptions {
+                priority: args.priority.into(),
+                due_at: args.due,
+                tags: args.tags,
+                project: args.project,
+                parent: args.parent,
+                depends_on: args.depends_on,
+                recurrence: args.repeat,
+            };
+
+            if let Err(e) = tasks_list.add_task_with(args.title.clone(), args.description, options) {
                 return Err(CommandError::TaskError(format!("Error al crear tarea -> {}", e)));
             }
 
-            println!("Tarea creada: \"{}\"", self.title);
-            return Ok(());
+            println!("Tarea creada: \"{}\"", args.title);
         }
 
-        if self.update != 0 {
-            if self.title.trim().is_empty() && self.description.trim().is_empty() {
-                return Err(CommandError::InvalidArgs("Al menos un atributo debe ser proporcionado para actualizar la tarea. Use: --update <ID> --title \"Título de la tarea\" --description \"Descripción de la tarea\"".to_string()));
+        Command::Edit(args) => {
+            let title = args.title.unwrap_or_default();
+            let description = args.description.unwrap_or_default();
+
+            let has_text = !title.trim().is_empty() || !description.trim().is_empty();
+
+            let due_at = if args.no_due { Some(None) } else { args.due.map(Some) };
+            let recurrence = if args.no_repeat { Some(None) } else { args.repeat.map(Some) };
+
+            // Without attributes, a single task is opened in the editor
+            if !has_text && args.priority.is_none() && due_at.is_none() && recurrence.is_none() {
+                let editor = config.editor.clone().or_else(editor::terminal_editor);
+
+                return match (args.ids, editor) {
+                    (Some(TaskIds(ids)), Some(editor)) if ids.len() == 1 => edit_in_editor(tasks_list, ids[0], &editor),
+                    _ => Err(CommandError::InvalidArgs("Al menos un atributo debe ser proporcionado para actualizar la tarea. Use: edit <ID> --title \"Título de la tarea\" --description \"Descripción de la tarea\" --priority <PRIORIDAD> --due <FECHA>, o edit <ID> en una terminal para abrir el editor".to_string())),
+                };
             }
 
-            if let Err(e) = tasks_list.update_task(self.update as usize, self.title.clone(), self.description.clone()) {
-                return Err(CommandError::TaskError(format!("Error al actualizar tarea -> {}", e)));
+            let ids = select(tasks_list, args.ids, &args.selection, config, false)?;
+
+            apply_each(&ids, "actualizar tarea", |id| {
+                if has_text {
+                    tasks_list.update_task(id, title.clone(), description.clone())?;
+                }
+
+                if let Some(priority) = args.priority {
+                    tasks_list.change_task_priority(id, priority.into())?;
+                }
+
+                if let Some(due_at) = due_at {
+                    tasks_list.change_task_due_date(id, due_at)?;
+                }
+
+                if let Some(recurrence) = &recurrence {
+                    tasks_list.change_task_recurrence(id, recurrence.clone())?;
+                }
+
+                println!("Tarea {} actualizada", id);
+                Ok(())
+            })?;
+        }
+
+        Command::Rm { ids, selection } => {
+            let ids = select(tasks_list, ids, &selection, config, false)?;
+
+            apply_each(&ids, "eliminar tarea", |id| {
+                tasks_list.delete_task(id)?;
+                println!("Tarea {} eliminada", id);
+                Ok(())
+            })?;
+        }
+
+        Command::Done { ids, cascade, selection } => {
+            return run(Command::Status { ids, status: StatusArg::Completed, cascade, force: false, selection }, tasks_list, config);
+        }
+
+        Command::Status { ids, status, cascade, force, selection } => {
+            let status = TaskStatus::from(status);
+            let ids = select(tasks_list, ids, &selection, config, false)?;
+
+            apply_each(&ids, "cambiar estado de la tarea", |id| change_status(tasks_list, id, status, cascade, force))?;
+        }
+
+        Command::Tag { ids, tags, selection } => {
+            let ids = select(tasks_list, ids, &selection, config, false)?;
+
+            apply_each(&ids, "agregar etiquetas", |id| {
+                tasks_list.add_task_tags(id, &tags)?;
+                println!("Etiquetas agregadas a la tarea {}", id);
+                Ok(())
+            })?;
+        }
+
+        Command::Untag { ids, tags, selection } => {
+            let ids = select(tasks_list, ids, &selection, config, false)?;
+
+            apply_each(&ids, "quitar etiquetas", |id| {
+                tasks_list.remove_task_tags(id, &tags)?;
+                println!("Etiquetas quitadas de la tarea {}", id);
+                Ok(())
+            })?;
+        }
+
+        Command::Tags => {
+            tasks_list.get_tags();
+        }
+
+        Command::Move { ids, project, selection } => {
+            let ids = select(tasks_list, ids, &selection, config, false)?;
+
+            apply_each(&ids, "mover tarea", |id| {
+                tasks_list.move_task(id, &project)?;
+
+                if let Some(task) = tasks_list.get_task(id) {
+                    println!("Tarea {} movida al proyecto \"{}\"", id, task.project);
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Command::Projects => {
+            tasks_list.get_projects();
+        }
+
+        Command::Depend { ids, depends_on, selection } => {
+            let ids = select(tasks_list, ids, &selection, config, false)?;
+
+            apply_each(&ids, "agregar dependencias", |id| {
+                tasks_list.add_task_dependencies(id, &depends_on)?;
+                println!("Tarea {} depende de: {}", id, join_ids(&depends_on));
+                Ok(())
+            })?;
+        }
+
+        Command::Undepend { ids, depends_on, selection } => {
+            let ids = select(tasks_list, ids, &selection, config, false)?;
+
+            apply_each(&ids, "quitar dependencias", |id| {
+                tasks_list.remove_task_dependencies(id, &depends_on)?;
+                println!("Dependencias quitadas de la tarea {}", id);
+                Ok(())
+            })?;
+        }
+
+        Command::Next => {
+            let filter = TaskFilter {
+                status: Some(TaskStatus::Pending),
+                blocked: Some(false),
+                ..Default::default()
+            };
+
+            let layout = Layout { columns: config.list.columns.clone().unwrap_or_default(), wide: config.list.wide, ..Default::default() };
+
+            print_tasks(tasks_list, &filter, None, OutputFormat::Table, &layout)?;
+        }
+
+        Command::Trash => {
+            tasks_list.get_trash();
+        }
+
+        Command::Restore { ids, selection } => {
+            let ids = select(tasks_list, ids, &selection, config, true)?;
+
+            apply_each(&ids, "recuperar tarea", |id| {
+                let restored = tasks_list.restore_task(id)?;
+                println!("Tarea {} recuperada", id);
+
+                if restored.len() > 1 {
+                    println!("Subtareas recuperadas: {}", join_ids(&restored[1..]));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Command::Purge { older_than } => {
+            let purged = tasks_list.purge_tasks(older_than.map(|age| now_fixed() - age));
+
+            if purged.is_empty() {
+                println!("No hay tareas que eliminar definitivamente");
+            } else {
+                println!("Tareas eliminadas definitivamente: {}", join_ids(&purged));
             }
+        }
 
-            println!("Tarea {} actualizada", self.update);
-            return Ok(());
+        // Handled by execute before loading the tasks
+        Command::Migrate { .. }
+        | Command::Archive { .. }
+        | Command::Undo
+        | Command::Redo
+        | Command::Log { .. }
+        | Command::Storage { .. }
+        | Command::Query { .. }
+        | Command::Search(_)
+        | Command::Tui => {}
+    }
+
+    Ok(())
+}
+
+// Open the terminal interface (changes are saved as they are made)
+fn interactive(store: &mut dyn TaskStore, loaded: Tasks, config: &Config) -> Result<(), CommandError> {
+    use std::io::IsTerminal;
+
+    if !std::io::stdout().is_terminal() {
+        return Err(CommandError::InvalidArgs("La interfaz interactiva necesita una terminal".to_string()));
+    }
+
+    tui::run(store, loaded, config.queries.clone()).map_err(|e| CommandError::TaskError(format!("Error en la interfaz interactiva -> {}", e)))
+}
+
+// Edit a task in the editor
+fn edit_in_editor(tasks_list: &mut Tasks, id: usize, editor: &str) -> Result<(), CommandError> {
+    match editor::edit_task(tasks_list, id, editor) {
+        Ok(true) => println!("Tarea {} actualizada", id),
+        Ok(false) => println!("Tarea {} sin cambios", id),
+        Err(e) => return Err(CommandError::TaskError(format!("Error al editar la tarea -> {}", e))),
+    }
+
+    Ok(())
+}
+
+// Change the status of a task, reporting the subtasks completed with it and the next repetition
+fn change_status(tasks_list: &mut Tasks, id: usize, status: TaskStatus, cascade: bool, force: bool) -> Result<(), TaskError> {
+    let result = if force {
+        let blockers = tasks_list.blockers(id);
+
+        if status != TaskStatus::Pending && !blockers.is_empty() {
+            eprintln!("Aviso: la tarea {} depende de tareas sin completar ({})", id, join_ids(&blockers));
         }
 
-        if self.delete != 0 {
-            if let Err(e) = tasks_list.delete_task(self.delete as usize) {
-                return Err(CommandError::TaskError(format!("Error al eliminar tarea -> {}", e)));
+        tasks_list.force_task_status(id, status)
+    } else if status == TaskStatus::Completed && cascade {
+        tasks_list.complete_task_tree(id).map(|completed| {
+            if !completed.is_empty() {
+                println!("Subtareas completadas: {}", join_ids(&completed));
             }
 
-            println!("Tarea {} eliminada", self.delete);
+            None
+        })
+    } else {
+        // Completing a parent with open subtasks only warns (use --cascade to complete them)
+        if status == TaskStatus::Completed && tasks_list.validate_task_id(id).is_ok() {
+            let open = tasks_list.open_descendants(id);
+
+            if !open.is_empty() {
+                eprintln!("Aviso: la tarea {} tiene subtareas abiertas ({}). Use --cascade para completarlas", id, join_ids(&open));
+            }
+        }
+
+        tasks_list.change_task_status(id, status)
+    };
+
+    let next = result?;
+
+    println!("Tarea {} marcada como \"{}\"", id, status.stringify());
+
+    // Completing a recurring task creates its next instance
+    if let Some(task) = next.and_then(|next| tasks_list.get_task(next)) {
+        match task.due_at {
+            Some(due) => println!("Próxima repetición: tarea {} (vence {})", task.id, due.format("%d-%m-%Y %H:%M")),
+            None => println!("Próxima repetición: tarea {}", task.id),
+        }
+    }
+
+    Ok(())
+}
+
+// Migrate the data file, reporting the applied (or pending) migrations
+fn migrate(store: &mut dyn TaskStore, dry_run: bool) -> Result<(), CommandError> {
+    let report = match store.migrate(dry_run) {
+        Ok(Some(report)) => report,
+        Ok(None) => {
+            println!("No hay tareas guardadas que migrar");
             return Ok(());
         }
+        Err(e) => return Err(CommandError::TaskError(format!("Error al migrar el archivo de tareas -> {}", e))),
+    };
+
+    if report.steps.is_empty() {
+        println!("El archivo de tareas ya está en la versión {}", report.to);
+        return Ok(());
+    }
+
+    println!("Versión del archivo de tareas: {}", report.from);
+
+    for step in &report.steps {
+        println!("  {}", step);
+    }
+
+    if dry_run {
+        println!("Simulación: el archivo no se modificó");
+    } else {
+        println!("Archivo de tareas actualizado a la versión {}", report.to);
+    }
+
+    Ok(())
+}
 
-        if self.pending != 0 {
-            if let Err(e) = tasks_list.change_task_status(self.pending as usize, TaskStatus::Pending) {
-                return Err(CommandError::TaskError(format!("Error al cambiar estado de la tarea -> {}", e)));
+// Print the tasks that pass the filter and the query in the given format (an empty NDJSON list
+// prints nothing)
+fn print_tasks(tasks_list: &Tasks, filter: &TaskFilter, query: Option<&Query>, format: OutputFormat, layout: &Layout) -> Result<(), CommandError> {
+    let output = render_tasks(tasks_list, filter, query, format, layout, now_fixed())
+        .map_err(|e| CommandError::TaskError(format!("Error al mostrar las tareas -> {}", e)))?;
+
+    if !output.is_empty() {
+        println!("{}", output);
+    }
+
+    Ok(())
+}
+
+// Description of a command for the undo/redo journal
+fn label(command: &Command) -> String {
+    match command {
+        Command::Add(args) => format!("crear la tarea \"{}\"", args.title),
+        Command::Edit(args) => format!("editar {}", targets(&args.ids, &args.selection)),
+        Command::Rm { ids, selection } => format!("eliminar {}", targets(ids, selection)),
+        Command::Restore { ids, selection } => format!("recuperar {}", targets(ids, selection)),
+        Command::Purge { .. } => "vaciar la papelera".to_string(),
+        Command::Status { ids, status, selection, .. } => format!("marcar {} como \"{}\"", targets(ids, selection), TaskStatus::from(*status).stringify()),
+        Command::Done { ids, selection, .. } => format!("completar {}", targets(ids, selection)),
+        Command::Tag { ids, selection, .. } => format!("agregar etiquetas a {}", targets(ids, selection)),
+        Command::Untag { ids, selection, .. } => format!("quitar etiquetas de {}", targets(ids, selection)),
+        Command::Move { ids, project, selection } => format!("mover {} al proyecto {}", targets(ids, selection), project),
+        Command::Depend { ids, selection, .. } => format!("agregar dependencias a {}", targets(ids, selection)),
+        Command::Undepend { ids, selection, .. } => format!("quitar dependencias de {}", targets(ids, selection)),
+        _ => "otro comando".to_string(),
+    }
+}
+
+// Tasks a command applies to, for the journal: "la tarea 3", "las tareas 3, 5" or the query
+fn targets(ids: &Option<TaskIds>, selection: &Selection) -> String {
+    match (ids, &selection.filter) {
+        (Some(TaskIds(ids)), _) if ids.len() == 1 => format!("la tarea {}", ids[0]),
+        (Some(TaskIds(ids)), _) => format!("las tareas {}", join_ids(ids)),
+        (None, Some(filter)) => format!("las tareas que cumplen \"{}\"", filter),
+        (None, None) => "ninguna tarea".to_string(),
+    }
+}
+
+// IDs of the tasks a command applies to: the given IDs or the tasks matching --where (deleted
+// tasks when restoring). Asks for confirmation when there are more than the configured limit
+fn select(tasks_list: &Tasks, ids: Option<TaskIds>, selection: &Selection, config: &Config, deleted: bool) -> Result<Vec<usize>, CommandError> {
+    let ids = match (ids, &selection.filter) {
+        (Some(_), Some(_)) => return Err(CommandError::InvalidArgs("Indique los IDs o --where, pero no ambos".to_string())),
+        (None, None) => return Err(CommandError::InvalidArgs("Indique los IDs de las tareas (p. ej. 3,5,9-12) o una consulta con --where".to_string())),
+        (Some(TaskIds(ids)), None) => ids,
+        (None, Some(filter)) => {
+            let now = now_fixed();
+            let query = Query::parse(filter, &config.queries, now).map_err(|e| CommandError::InvalidArgs(format!("Consulta inválida: {}", e)))?;
+
+            let ids: Vec<usize> = tasks_list
+                .iter()
+                .filter(|task| task.visible != deleted && query.matches(tasks_list, task, now))
+                .map(|task| task.id)
+                .collect();
+
+            if ids.is_empty() {
+                println!("Ninguna tarea cumple la consulta");
             }
-            
-            println!("Tarea {} marcada como \"Pendiente\"", self.pending);
-            return Ok(());
+
+            ids
         }
+    };
 
-        if self.in_progress != 0 {
-            if let Err(e) = tasks_list.change_task_status(self.in_progress as usize, TaskStatus::InProgress) {
-                return Err(CommandError::TaskError(format!("Error al cambiar estado de la tarea -> {}", e)));
+    if ids.len() > config.bulk.confirm_above && !selection.yes {
+        match confirm(&format!("El comando afecta a {} tareas ({}). ¿Continuar? [s/N] ", ids.len(), join_ids(&ids))) {
+            Some(true) => {}
+            Some(false) => {
+                println!("Operación cancelada");
+                return Ok(Vec::new());
             }
-            
-            println!("Tarea {} marcada como \"En Progreso\"", self.in_progress);
-            return Ok(());
+            None => return Err(CommandError::InvalidArgs(format!("El comando afecta a {} tareas; use --yes para confirmarlo", ids.len()))),
         }
+    }
+
+    Ok(ids)
+}
 
-        if self.completed != 0 {
-            if let Err(e) = tasks_list.change_task_status(self.completed as usize, TaskStatus::Completed) {
-                return Err(CommandError::TaskError(format!("Error al cambiar estado de la tarea -> {}", e)));
+// Ask a yes/no question on the terminal (None when there is no terminal to ask)
+#[cfg(not(test))]
+fn confirm(question: &str) -> Option<bool> {
+    use std::io::{self, IsTerminal, Write};
+
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+
+    print!("{}", question);
+    io::stdout().flush().ok()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).ok()?;
+
+    Some(matches!(answer.trim().to_lowercase().as_str(), "s" | "si" | "sí" | "y" | "yes"))
+}
+
+// Alternative function for testing (there is never a terminal)
+#[cfg(test)]
+fn confirm(_question: &str) -> Option<bool> {
+    None
+}
+
+// Apply a change to each task. With a single task a failure is the error of the command; with
+// several, failures are reported per task and the other tasks are still changed
+fn apply_each<F>(ids: &[usize], action: &str, mut apply: F) -> Result<(), CommandError>
+where
+    F: FnMut(usize) -> Result<(), TaskError>,
+{
+    if let [id] = ids {
+        return apply(*id).map_err(|e| CommandError::TaskError(format!("Error al {} -> {}", action, e)));
+    }
+
+    let mut failed = 0;
+
+    for &id in ids {
+        if let Err(e) = apply(id) {
+            eprintln!("Error al {} {} -> {}", action, id, e);
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        return Err(CommandError::Partial { failed, total: ids.len() });
+    }
+
+    Ok(())
+}
+
+// Move the tasks completed before the given moment to the archive file (they are added to the
+// tasks already archived). The archive is written first, so a failure never loses tasks. Undoing
+// the command brings the tasks back to the list (the copies in the archive stay)
+fn archive(store: &mut dyn TaskStore, loaded: &Tasks, completed_before: DateTime<FixedOffset>, file: &str, lock_timeout: Duration) -> Result<(), CommandError> {
+    let error = |e: StorageError| CommandError::TaskError(format!("Error al archivar las tareas -> {}", e));
+
+    let mut tasks_list = loaded.clone();
+    let archived = tasks_list.archive_tasks(completed_before);
+
+    if archived.is_empty() {
+        println!("No hay tareas completadas que archivar");
+        return Ok(());
+    }
+
+    let storage: Storage<Tasks> = Storage::new(file.to_string()).with_backup(true).with_lock_timeout(lock_timeout);
+    let _lock = storage.lock().map_err(error)?;
+    let mut archive = storage.download_data().map_err(error)?.unwrap_or_default();
+    let ids: Vec<usize> = archived.iter().map(|task| task.id).collect();
+
+    for task in archived {
+        archive.put_task(task);
+    }
+
+    storage.upload_data(&archive).map_err(error)?;
+    record(store, format!("archivar las tareas {}", join_ids(&ids)), loaded, &tasks_list)?;
+    store.save_changes(loaded, &tasks_list).map_err(error)?;
+
+    println!("Tareas archivadas en {}: {}", file, join_ids(&ids));
+
+    Ok(())
+}
+
+// Print the tasks changed by undoing or redoing a command
+fn print_entry(entry: &JournalEntry, redo: bool) {
+    println!("{}: {}", if redo { "Rehecho" } else { "Deshecho" }, entry.command);
+
+    for change in &entry.changes {
+        let (from, to) = if redo { (&change.before, &change.after) } else { (&change.after, &change.before) };
+
+        match (from, to) {
+            (None, Some(task)) => println!("  Tarea {} \"{}\" restaurada", task.id, task.title),
+            (Some(task), None) => println!("  Tarea {} \"{}\" quitada", task.id, task.title),
+            (Some(_), Some(task)) => println!("  Tarea {} \"{}\" ({})", task.id, task.title, task.status.stringify()),
+            (None, None) => {}
+        }
+    }
+}
+
+// Undo (or redo) the last command recorded in the journal
+fn undo(store: &mut dyn TaskStore, loaded: &Tasks, redo: bool) -> Result<(), CommandError> {
+    let action = if redo { "rehacer" } else { "deshacer" };
+    let error = |e: String| CommandError::TaskError(format!("Error al {} -> {}", action, e));
+
+    let mut journal = store.load_journal().map_err(|e| error(e.to_string()))?;
+    let mut tasks_list = loaded.clone();
+
+    let entry = if redo { journal.redo(&mut tasks_list) } else { journal.undo(&mut tasks_list) };
+
+    let Some(entry) = entry.map_err(|e| error(e.to_string()))? else {
+        println!("No hay ningún comando que {}", action);
+        return Ok(());
+    };
+
+    store.save_journal(&journal).map_err(|e| error(e.to_string()))?;
+    store.save_changes(loaded, &tasks_list).map_err(|e| error(e.to_string()))?;
+    print_entry(&entry, redo);
+
+    Ok(())
+}
+
+// Error for backends that do not keep a history of changes
+fn no_history() -> CommandError {
+    CommandError::InvalidArgs("El almacenamiento actual no guarda un historial de cambios (use --store log)".to_string())
+}
+
+// Show the recorded events, of all tasks or of one
+fn show_log(store: &mut dyn TaskStore, id: Option<usize>) -> Result<(), CommandError> {
+    let entries = store
+        .events()
+        .map_err(|e| CommandError::TaskError(format!("Error al leer el historial -> {}", e)))?
+        .ok_or_else(no_history)?;
+
+    let entries: Vec<_> = entries.into_iter().filter(|entry| id.is_none_or(|id| entry.event.task_id() == id)).collect();
+
+    if entries.is_empty() {
+        match id {
+            Some(id) => println!("No hay eventos de la tarea {}", id),
+            None => println!("No hay eventos en el historial"),
+        }
+
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.set_header(vec![Cell::new("Nº"), Cell::new("Fecha"), Cell::new("Tarea"), Cell::new("Evento")]);
+
+    for entry in entries {
+        table.add_row(vec![
+            Cell::new(entry.seq.to_string()),
+            Cell::new(entry.at.format("%d-%m-%Y %H:%M:%S").to_string()),
+            Cell::new(entry.event.task_id().to_string()),
+            Cell::new(entry.event.stringify()),
+        ]);
+    }
+
+    println!("{}", table);
+
+    Ok(())
+}
+
+// Search the titles and descriptions of the visible tasks, most relevant first, with the matches
+// highlighted. The search index kept by the store is brought up to date first
+fn search(store: &mut dyn TaskStore, tasks_list: &Tasks, args: &SearchArgs) -> Result<(), CommandError> {
+    // An argument with spaces (quoted in the shell) is a phrase
+    let terms: Vec<String> = args
+        .terms
+        .iter()
+        .map(|term| if term.contains(char::is_whitespace) && !term.contains('"') { format!("\"{}\"", term) } else { term.clone() })
+        .collect();
+    let query = SearchQuery::parse(&terms.join(" ")).map_err(|e| CommandError::InvalidArgs(e.to_string()))?;
+
+    let mut index = store
+        .load_index()
+        .map_err(|e| CommandError::TaskError(format!("Error al leer el índice de búsqueda -> {}", e)))?;
+
+    // The index is only a cache: when it cannot be written it is built again next time
+    if index.update(tasks_list) {
+        let _ = store.save_index(&index);
+    }
+
+    let hits = index.search(&query);
+
+    if hits.is_empty() {
+        println!("No se encontraron tareas");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.set_header(vec![Cell::new("ID"), Cell::new("Título"), Cell::new("Descripción"), Cell::new("Proyecto"), Cell::new("Estado"), Cell::new("Relevancia")]);
+
+    // Cells with matches are highlighted, with the matching words between brackets
+    let highlighted = |text: &str| match query.highlight(text) {
+        Some(text) => Cell::new(text).fg(Color::Yellow),
+        None => Cell::new(text),
+    };
+
+    for hit in hits.iter().take(args.limit) {
+        let Some(task) = tasks_list.get_task(hit.id) else {
+            continue;
+        };
+
+        table.add_row(vec![
+            Cell::new(task.id.to_string()),
+            highlighted(&task.title),
+            highlighted(&task.description),
+            Cell::new(&task.project),
+            Cell::new(task.status.stringify()),
+            Cell::new(format!("{:.2}", hit.score)),
+        ]);
+    }
+
+    println!("{}", table);
+
+    if hits.len() > args.limit {
+        println!("Mostrando {} de {} resultados (use --limit para ver más)", args.limit, hits.len());
+    }
+
+    Ok(())
+}
+
+// Save, list or remove the saved queries of the configuration file
+fn saved_queries(action: QueryAction, file_name: &str, queries: &BTreeMap<String, String>) -> Result<(), CommandError> {
+    let error = |e: config::ConfigError| CommandError::TaskError(format!("Error al guardar la configuración -> {}", e));
+
+    match action {
+        QueryAction::Save { name, query } => {
+            let valid = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+
+            if !valid {
+                return Err(CommandError::InvalidArgs(format!("Nombre de consulta inválido: \"{}\" (use letras, números, - y _)", name)));
             }
-            
-            println!("Tarea {} marcada como \"Completada\"", self.completed);
-            return Ok(());
+
+            // The query is checked with the other saved queries, including the one it replaces
+            let mut saved = queries.clone();
+            saved.insert(name.clone(), query.clone());
+            Query::parse(&query, &saved, now_fixed()).map_err(|e| CommandError::InvalidArgs(format!("Consulta inválida: {}", e)))?;
+
+            config::save_query(file_name, &name, &query).map_err(error)?;
+            println!("Consulta @{} guardada en {}", name, file_name);
         }
 
-        println!("Comando no reconocido\nUse --help para ver la lista de comandos válidos");
-        Ok(())
+        QueryAction::List => {
+            if queries.is_empty() {
+                println!("No hay consultas guardadas");
+                return Ok(());
+            }
+
+            let mut table = Table::new();
+            table.set_header(vec![Cell::new("Nombre"), Cell::new("Consulta")]);
+
+            for (name, query) in queries {
+                table.add_row(vec![Cell::new(format!("@{}", name)), Cell::new(query)]);
+            }
+
+            println!("{}", table);
+        }
+
+        QueryAction::Remove { name } => {
+            if !config::remove_query(file_name, &name).map_err(error)? {
+                return Err(CommandError::InvalidArgs(format!("No hay ninguna consulta guardada llamada @{}", name)));
+            }
+
+            println!("Consulta @{} eliminada", name);
+        }
     }
 
-    // Print list of supported commands
-    pub fn list_commands() {
-        println!("  --list");
-        println!("      Listar todas las tareas\n");
-        println!("  --add --title \"Título\" --description \"Descripción\"");
-        println!("      Crear una nueva tarea (es obligatorio proporcionar el título)\n");
-        println!("  --update <ID> --title \"Nuevo título\" --description \"Nueva descripción\"");
-        println!("      Actualizar una tarea existente (es obligatorio proporcionar al menos un atributo)\n");
-        println!("  --delete <ID>");
-        println!("      Eliminar una tarea existente\n");
-        println!("  --pending <ID>");
-        println!("      Marcar una tarea existente como: Pendiente\n");
-        println!("  --in-progress <ID>");
-        println!("      Marcar una tarea existente como: En Progreso\n");
-        println!("  --completed <ID>");
-        println!("      Marcar una tarea existente como: Completada\n");
-        println!("  --help");
-        println!("      Listar los comandos válidos");
+    Ok(())
+}
+
+// Fold the event log into a snapshot
+fn compact(store: &mut dyn TaskStore) -> Result<(), CommandError> {
+    let removed = store
+        .compact()
+        .map_err(|e| CommandError::TaskError(format!("Error al compactar el historial -> {}", e)))?
+        .ok_or_else(no_history)?;
+
+    println!("Historial compactado: {} eventos incluidos en la instantánea", removed);
+
+    Ok(())
+}
+
+// Copy all tasks from one backend to another, checking that nothing was lost
+fn convert(from: (StoreKind, &str), to: (StoreKind, &str), force: bool, lock_timeout: Duration) -> Result<(), CommandError> {
+    let error = |e: StorageError| CommandError::TaskError(format!("Error al convertir las tareas -> {}", e));
+
+    if from == to {
+        return Err(CommandError::InvalidArgs("El origen y el destino son el mismo almacenamiento".to_string()));
+    }
+
+    if !std::path::Path::new(from.1).exists() {
+        return Err(CommandError::InvalidArgs(format!("El archivo de origen {} no existe", from.1)));
+    }
+
+    let tasks = open_store(from.0, from.1, lock_timeout).and_then(|mut source| source.load()).map_err(error)?;
+    let mut target = open_store(to.0, to.1, lock_timeout).map_err(error)?;
+    let existing = target.load().map_err(error)?;
+
+    if !existing.is_empty() && !force {
+        return Err(CommandError::InvalidArgs(format!(
+            "El destino {} ya contiene {} tareas. Use --force para reemplazarlas",
+            to.1,
+            existing.len()
+        )));
     }
+
+    target.save(&tasks).map_err(error)?;
+
+    // Read the copy back and compare it task by task (backends may return them in another order)
+    let copied = target.load().map_err(error)?;
+    let complete = copied.len() == tasks.len()
+        && copied.next_id() == tasks.next_id()
+        && tasks.iter().all(|task| copied.get_task(task.id) == Some(task));
+
+    if !complete {
+        return Err(CommandError::TaskError(format!("Error al convertir las tareas -> la copia en {} no coincide con el origen", to.1)));
+    }
+
+    println!("{} tareas copiadas de {} a {}", tasks.len(), from.1, to.1);
+
+    Ok(())
 }
 
 // Alternative function for testing: simulates null task list using Option
 #[cfg(test)]
 impl Commands {
-    pub fn execute_option(&self, tasks_opt: Option<&mut Tasks>) -> Result<(), CommandError> {
+    pub fn execute_option(&self, tasks_opt: Option<&mut dyn TaskStore>) -> Result<(), CommandError> {
         match tasks_opt {
             Some(tasks) => self.execute(tasks),
             None => Err(CommandError::InvalidArgs(
@@ -188,4 +1558,4 @@ impl Commands {
 
 // Test configuration
 #[cfg(test)]
-mod tests;
\ No newline at end of file
+mod tests;
@@ -1,18 +1,18 @@
 use super::{Commands, CommandError};
-use crate::tasks::{Tasks, TaskStatus};
+use crate::storage::{JsonStore, LogStore, MemoryStore, SqliteStore, Storage, StoreKind, TaskStore};
+use crate::tasks::{TaskOptions, TaskPriority, TaskStatus};
+use clap::error::ErrorKind;
 use clap::Parser;
+use std::time::Duration;
 
 // Helper for stdout/stderr supression during tests
 fn suppress_output<T>(f: impl FnOnce() -> T) -> T { f() }
 
 #[test]
-// Test: --help command
+// Test: --help command (generated by clap)
 fn test_execute_help() {
-    suppress_output(|| {
-        let commands = Commands::parse_from(["test-bin", "--help"]);
-        let mut tasks = Tasks::default();
-        assert!(commands.execute(&mut tasks).is_ok());
-    });
+    let err = Commands::try_parse_from(["test-bin", "--help"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DisplayHelp);
 }
 
 #[test]
@@ -20,7 +20,7 @@ fn test_execute_help() {
 fn test_execute_list() {
     suppress_output(|| {
         let commands = Commands::parse_from(["test-bin", "--list"]);
-        let mut tasks = Tasks::default();
+        let mut tasks = MemoryStore::default();
         assert!(commands.execute(&mut tasks).is_ok());
     });
 }
@@ -30,7 +30,7 @@ fn test_execute_list() {
 fn test_execute_add_task_without_title() {
     suppress_output(|| {
         let commands = Commands::parse_from(["test-bin", "--add", "--description", "Description for testing"]);
-        let mut tasks = Tasks::default();
+        let mut tasks = MemoryStore::default();
         let err = commands.execute(&mut tasks).unwrap_err();
         assert!(matches!(err, CommandError::InvalidArgs(_)));
     });
@@ -43,7 +43,7 @@ fn test_execute_add_task_with_title() {
         let commands = Commands::parse_from([
             "test-bin", "--add", "--title", "Title for testing", "--description", "Description for testing"
         ]);
-        let mut tasks = Tasks::default();
+        let mut tasks = MemoryStore::default();
         assert!(commands.execute(&mut tasks).is_ok());
         assert_eq!(tasks.len(), 1);
         assert_eq!(tasks[0].title, "Title for testing");
@@ -54,8 +54,8 @@ fn test_execute_add_task_with_title() {
 // Test: --update command without attributes
 fn test_execute_update_task_without_attributes() {
     suppress_output(|| {
-        let mut tasks = Tasks::default();
-        tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
         let commands = Commands::parse_from(["test-bin", "--update", "1"]);
         let err = commands.execute(&mut tasks).unwrap_err();
         assert!(matches!(err, CommandError::InvalidArgs(_)));
@@ -66,8 +66,8 @@ fn test_execute_update_task_without_attributes() {
 // Test: --update command with --title
 fn test_execute_update_task_with_title() {
     suppress_output(|| {
-        let mut tasks = Tasks::default();
-        tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
         let commands = Commands::parse_from(["test-bin", "--update", "1", "--title", "Title 2 for testing"]);
         assert!(commands.execute(&mut tasks).is_ok());
         assert_eq!(tasks[0].title, "Title 2 for testing");
@@ -78,8 +78,8 @@ fn test_execute_update_task_with_title() {
 // Test: --update command with --description
 fn test_execute_update_task_with_description() {
     suppress_output(|| {
-        let mut tasks = Tasks::default();
-        tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
         let commands = Commands::parse_from(["test-bin", "--update", "1", "--description", "Description 2 for testing"]);
         assert!(commands.execute(&mut tasks).is_ok());
         assert_eq!(tasks[0].description, "Description 2 for testing");
@@ -90,7 +90,7 @@ fn test_execute_update_task_with_description() {
 // Test: --update command on non-existent task
 fn test_execute_update_non_existent_task() {
     suppress_output(|| {
-        let mut tasks = Tasks::default();
+        let mut tasks = MemoryStore::default();
         let commands = Commands::parse_from(["test-bin", "--update", "1", "--title", "X"]);
         let err = commands.execute(&mut tasks).unwrap_err();
         assert!(matches!(err, CommandError::TaskError(_)));
@@ -101,8 +101,8 @@ fn test_execute_update_non_existent_task() {
 // Test: --delete command
 fn test_execute_delete_task() {
     suppress_output(|| {
-        let mut tasks = Tasks::default();
-        tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
         let commands = Commands::parse_from(["test-bin", "--delete", "1"]);
         assert!(commands.execute(&mut tasks).is_ok());
         assert!(!tasks[0].visible);
@@ -113,7 +113,7 @@ fn test_execute_delete_task() {
 // Test: --delete command on non-existent task
 fn test_execute_delete_non_existent_task() {
     suppress_output(|| {
-        let mut tasks = Tasks::default();
+        let mut tasks = MemoryStore::default();
         let commands = Commands::parse_from(["test-bin", "--delete", "1"]);
         let err = commands.execute(&mut tasks).unwrap_err();
         assert!(matches!(err, CommandError::TaskError(_)));
@@ -124,8 +124,8 @@ fn test_execute_delete_non_existent_task() {
 // Test: --pending command
 fn test_execute_change_task_status_to_pending() {
     suppress_output(|| {
-        let mut tasks = Tasks::default();
-        tasks.add_task("Title".into(), "Desc".into()).unwrap();
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title".into(), "Desc".into(), TaskOptions::default()).unwrap();
         tasks.change_task_status(1, TaskStatus::Completed).unwrap();
         let commands = Commands::parse_from(["test-bin", "--pending", "1"]);
         assert!(commands.execute(&mut tasks).is_ok());
@@ -137,8 +137,8 @@ fn test_execute_change_task_status_to_pending() {
 // Test: --in-progress command
 fn test_execute_change_task_status_to_in_progress() {
     suppress_output(|| {
-        let mut tasks = Tasks::default();
-        tasks.add_task("Title".into(), "Desc".into()).unwrap();
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title".into(), "Desc".into(), TaskOptions::default()).unwrap();
         let commands = Commands::parse_from(["test-bin", "--in-progress", "1"]);
         assert!(commands.execute(&mut tasks).is_ok());
         assert_eq!(tasks[0].status, TaskStatus::InProgress);
@@ -149,8 +149,8 @@ fn test_execute_change_task_status_to_in_progress() {
 // Test: --completed command
 fn test_execute_change_task_status_to_completed() {
     suppress_output(|| {
-        let mut tasks = Tasks::default();
-        tasks.add_task("Title".into(), "Desc".into()).unwrap();
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title".into(), "Desc".into(), TaskOptions::default()).unwrap();
         let commands = Commands::parse_from(["test-bin", "--completed", "1"]);
         assert!(commands.execute(&mut tasks).is_ok());
         assert_eq!(tasks[0].status, TaskStatus::Completed);
@@ -162,7 +162,7 @@ fn test_execute_change_task_status_to_completed() {
 // Test: change status  (--pending, --in-progress, --completed) on non-existent task
 fn test_execute_change_status_non_existent_task() {
     suppress_output(|| {
-        let mut tasks = Tasks::default();
+        let mut tasks = MemoryStore::default();
         let commands = Commands::parse_from(["test-bin", "--pending", "1"]);
         let err = commands.execute(&mut tasks).unwrap_err();
         assert!(matches!(err, CommandError::TaskError(_)));
@@ -174,7 +174,7 @@ fn test_execute_change_status_non_existent_task() {
 fn test_execute_no_command() {
     suppress_output(|| {
         let commands = Commands::parse_from(["test-bin"]);
-        let mut tasks = Tasks::default();
+        let mut tasks = MemoryStore::default();
         assert!(commands.execute(&mut tasks).is_ok());
     });
 }
@@ -183,8 +183,785 @@ fn test_execute_no_command() {
 // Test: execute with null tasks (with Option::None)
 fn test_execute_with_nil_tasks() {
     let commands = Commands::parse_from(["test-bin", "--list"]);
-    let mut tasks = Tasks::default();
+    let mut tasks = MemoryStore::default();
     assert!(commands.execute_option(Some(&mut tasks)).is_ok());
     let err = commands.execute_option(None).unwrap_err();
     assert!(matches!(err, CommandError::InvalidArgs(_)));
-}
\ No newline at end of file
+}
+
+#[test]
+// Test: add subcommand
+fn test_execute_add_subcommand() {
+    suppress_output(|| {
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Title for testing", "-d", "Description for testing"]);
+        let mut tasks = MemoryStore::default();
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].description, "Description for testing");
+    });
+}
+
+#[test]
+// Test: add subcommand without --title
+fn test_parse_add_subcommand_without_title() {
+    let err = Commands::try_parse_from(["test-bin", "add"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+}
+
+#[test]
+// Test: edit subcommand (and its "update" alias)
+fn test_execute_edit_subcommand() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+        let commands = Commands::parse_from(["test-bin", "edit", "1", "--title", "Title 2 for testing"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        let commands = Commands::parse_from(["test-bin", "update", "1", "--description", "Description 2 for testing"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].title, "Title 2 for testing");
+        assert_eq!(tasks[0].description, "Description 2 for testing");
+    });
+}
+
+#[test]
+// Test: edit subcommand without attributes (opens the editor, only for a single task)
+fn test_execute_edit_subcommand_in_editor() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+        tasks.add_task_with("Title 2 for testing".into(), "".into(), TaskOptions::default()).unwrap();
+
+        // Without a terminal there is no editor to open
+        let result = Commands::parse_from(["test-bin", "edit", "1"]).execute(&mut tasks);
+        assert!(matches!(result, Err(CommandError::InvalidArgs(_))));
+
+        let config = crate::config::Config { editor: Some("sed -i 's/^priority = .*/priority = \"urgent\"/'".into()), ..Default::default() };
+        let with_editor = |args: &[&str]| Commands::parse_from(args).with_config("todo.toml", &config);
+        assert!(with_editor(&["test-bin", "edit", "1"]).execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].priority, TaskPriority::Urgent);
+        assert_eq!(tasks[0].description, "Description 1 for testing");
+        assert!(matches!(with_editor(&["test-bin", "edit", "1,2"]).execute(&mut tasks), Err(CommandError::InvalidArgs(_))));
+        assert!(matches!(with_editor(&["test-bin", "edit", "7"]).execute(&mut tasks), Err(CommandError::TaskError(_))));
+
+        // The change can be undone like any other edit
+        assert!(with_editor(&["test-bin", "undo"]).execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].priority, TaskPriority::Medium);
+    });
+}
+
+#[test]
+// Test: rm subcommand
+fn test_execute_rm_subcommand() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title".into(), "Desc".into(), TaskOptions::default()).unwrap();
+        let commands = Commands::parse_from(["test-bin", "rm", "1"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert!(!tasks[0].visible);
+    });
+}
+
+#[test]
+// Test: status subcommand
+fn test_execute_status_subcommand() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title".into(), "Desc".into(), TaskOptions::default()).unwrap();
+        let commands = Commands::parse_from(["test-bin", "status", "1", "in-progress"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].status, TaskStatus::InProgress);
+    });
+}
+
+#[test]
+// Test: status subcommand with invalid status value
+fn test_parse_status_subcommand_invalid_value() {
+    let err = Commands::try_parse_from(["test-bin", "status", "1", "archived"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidValue);
+}
+
+#[test]
+// Test: conflicting legacy flags (--add --delete)
+fn test_parse_conflicting_legacy_flags() {
+    let err = Commands::try_parse_from(["test-bin", "--add", "--title", "X", "--delete", "3"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+}
+
+#[test]
+// Test: legacy flags mixed with a subcommand
+fn test_parse_legacy_flags_with_subcommand() {
+    assert!(Commands::try_parse_from(["test-bin", "--delete", "1", "list"]).is_err());
+}
+
+#[test]
+// Test: non-numeric task ID
+fn test_parse_non_numeric_id() {
+    let err = Commands::try_parse_from(["test-bin", "--delete", "abc"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    let err = Commands::try_parse_from(["test-bin", "rm", "-1"]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::ValueValidation | ErrorKind::UnknownArgument));
+}
+
+#[test]
+// Test: add subcommand with --priority
+fn test_execute_add_with_priority() {
+    suppress_output(|| {
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Title for testing", "--priority", "high"]);
+        let mut tasks = MemoryStore::default();
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].priority, TaskPriority::High);
+    });
+}
+
+#[test]
+// Test: edit subcommand with --priority only
+fn test_execute_edit_priority_only() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+        let commands = Commands::parse_from(["test-bin", "edit", "1", "--priority", "urgent"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].priority, TaskPriority::Urgent);
+        assert_eq!(tasks[0].title, "Title 1 for testing");
+    });
+}
+
+#[test]
+// Test: list subcommand with --priority
+fn test_execute_list_with_priority() {
+    suppress_output(|| {
+        let commands = Commands::parse_from(["test-bin", "list", "--priority", "low"]);
+        let mut tasks = MemoryStore::default();
+        assert!(commands.execute(&mut tasks).is_ok());
+    });
+}
+
+#[test]
+// Test: add subcommand with --due
+fn test_execute_add_with_due_date() {
+    suppress_output(|| {
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Title for testing", "--due", "2025-11-02T17:00:00-04:00"]);
+        let mut tasks = MemoryStore::default();
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].due_at.unwrap().to_rfc3339(), "2025-11-02T17:00:00-04:00");
+    });
+}
+
+#[test]
+// Test: add subcommand with invalid --due
+fn test_parse_add_with_invalid_due_date() {
+    let err = Commands::try_parse_from(["test-bin", "add", "--title", "X", "--due", "someday"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ValueValidation);
+}
+
+#[test]
+// Test: edit subcommand with --due and --no-due
+fn test_execute_edit_due_date() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+        let commands = Commands::parse_from(["test-bin", "edit", "1", "--due", "2025-11-02T17:00:00-04:00"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert!(tasks[0].due_at.is_some());
+        let commands = Commands::parse_from(["test-bin", "edit", "1", "--no-due"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert!(tasks[0].due_at.is_none());
+        assert!(Commands::try_parse_from(["test-bin", "edit", "1", "--no-due", "--due", "2025-11-02T17:00:00-04:00"]).is_err());
+    });
+}
+
+#[test]
+// Test: list subcommand with --due views
+fn test_execute_list_with_due_views() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        for view in ["overdue", "today", "week"] {
+            let commands = Commands::parse_from(["test-bin", "list", "--due", view]);
+            assert!(commands.execute(&mut tasks).is_ok());
+        }
+    });
+}
+
+#[test]
+// Test: add subcommand with a natural-language --due
+fn test_execute_add_with_natural_due_date() {
+    suppress_output(|| {
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Title for testing", "--due", "mañana 17:00"]);
+        let mut tasks = MemoryStore::default();
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].due_at.unwrap().format("%H:%M").to_string(), "17:00");
+    });
+}
+
+#[test]
+// Test: add subcommand with +tags
+fn test_execute_add_with_tags() {
+    suppress_output(|| {
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Title for testing", "+work", "+urgent"]);
+        let mut tasks = MemoryStore::default();
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert!(tasks[0].tags.contains("work") && tasks[0].tags.contains("urgent"));
+    });
+}
+
+#[test]
+// Test: tag and untag subcommands
+fn test_execute_tag_untag() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Title".into(), "Desc".into(), TaskOptions::default()).unwrap();
+        let commands = Commands::parse_from(["test-bin", "tag", "1", "+work", "blocked"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].tags.len(), 2);
+        let commands = Commands::parse_from(["test-bin", "untag", "1", "+blocked"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].tags.len(), 1);
+        let commands = Commands::parse_from(["test-bin", "tag", "2", "+work"]);
+        assert!(matches!(commands.execute(&mut tasks).unwrap_err(), CommandError::TaskError(_)));
+        assert!(Commands::try_parse_from(["test-bin", "tag", "1"]).is_err());
+    });
+}
+
+#[test]
+// Test: list subcommand with --tag and --not-tag, and tags subcommand
+fn test_execute_list_with_tags() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        let commands = Commands::parse_from(["test-bin", "list", "--tag", "work", "--not-tag", "+blocked"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        let commands = Commands::parse_from(["test-bin", "list", "--tag", "two words"]);
+        assert!(matches!(commands.execute(&mut tasks).unwrap_err(), CommandError::InvalidArgs(_)));
+        let commands = Commands::parse_from(["test-bin", "tags"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+    });
+}
+
+#[test]
+// Test: add subcommand with --project, list --project, move and projects subcommands
+fn test_execute_projects() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Title for testing", "--project", "work.backend"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].project, "work.backend");
+        let commands = Commands::parse_from(["test-bin", "list", "--project", "work"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        let commands = Commands::parse_from(["test-bin", "move", "1", "home"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].project, "home");
+        let commands = Commands::parse_from(["test-bin", "projects"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        let commands = Commands::parse_from(["test-bin", "list", "--project", "bad project"]);
+        assert!(matches!(commands.execute(&mut tasks).unwrap_err(), CommandError::InvalidArgs(_)));
+    });
+}
+
+#[test]
+// Test: add subcommand with --parent, status with and without --cascade
+fn test_execute_subtasks() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Parent".into(), "".into(), TaskOptions::default()).unwrap();
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Child", "--parent", "1"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[1].parent, Some(1));
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Orphan", "--parent", "7"]);
+        assert!(matches!(commands.execute(&mut tasks).unwrap_err(), CommandError::TaskError(_)));
+
+        // Without --cascade the parent is completed and the child stays open
+        let commands = Commands::parse_from(["test-bin", "status", "1", "completed"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[0].status, TaskStatus::Completed);
+        assert_eq!(tasks[1].status, TaskStatus::Pending);
+
+        let commands = Commands::parse_from(["test-bin", "status", "1", "completed", "--cascade"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[1].status, TaskStatus::Completed);
+    });
+}
+
+#[test]
+// Test: depend, undepend and next subcommands, status of blocked tasks
+fn test_execute_dependencies() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Dependency".into(), "".into(), TaskOptions::default()).unwrap();
+        tasks.add_task_with("Blocked".into(), "".into(), TaskOptions::default()).unwrap();
+        let commands = Commands::parse_from(["test-bin", "depend", "2", "1"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        let commands = Commands::parse_from(["test-bin", "depend", "1", "2"]);
+        assert!(matches!(commands.execute(&mut tasks).unwrap_err(), CommandError::TaskError(_)));
+        let commands = Commands::parse_from(["test-bin", "status", "2", "in-progress"]);
+        assert!(matches!(commands.execute(&mut tasks).unwrap_err(), CommandError::TaskError(_)));
+        let commands = Commands::parse_from(["test-bin", "next"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        let commands = Commands::parse_from(["test-bin", "status", "2", "in-progress", "--force"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks[1].status, TaskStatus::InProgress);
+        let commands = Commands::parse_from(["test-bin", "undepend", "2", "1"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert!(!tasks.is_blocked(2));
+    });
+}
+
+#[test]
+// Test: add subcommand with --depends-on and list --blocked/--actionable
+fn test_execute_add_with_dependencies() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Dependency 1".into(), "".into(), TaskOptions::default()).unwrap();
+        tasks.add_task_with("Dependency 2".into(), "".into(), TaskOptions::default()).unwrap();
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Blocked", "--depends-on", "1,2"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks.blockers(3), vec![1, 2]);
+        for flag in ["--blocked", "--actionable"] {
+            let commands = Commands::parse_from(["test-bin", "list", flag]);
+            assert!(commands.execute(&mut tasks).is_ok());
+        }
+        assert!(Commands::try_parse_from(["test-bin", "list", "--blocked", "--actionable"]).is_err());
+    });
+}
+
+#[test]
+// Test: add and edit subcommands with --repeat, completing a recurring task
+fn test_execute_recurrence() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Report", "--due", "2025-11-07 17:00", "--repeat", "weekly:fri"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert!(tasks[0].recurrence.is_some());
+        let commands = Commands::parse_from(["test-bin", "status", "1", "completed"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert_eq!(tasks.len(), 2);
+        let commands = Commands::parse_from(["test-bin", "edit", "2", "--no-repeat"]);
+        assert!(commands.execute(&mut tasks).is_ok());
+        assert!(tasks[1].recurrence.is_none());
+        assert!(Commands::try_parse_from(["test-bin", "add", "--title", "Report", "--repeat", "hourly"]).is_err());
+        assert!(Commands::try_parse_from(["test-bin", "edit", "2", "--repeat", "daily", "--no-repeat"]).is_err());
+    });
+}
+
+#[test]
+// Test: --lock-timeout option (with subcommands and legacy flags)
+fn test_parse_lock_timeout() {
+    let commands = Commands::parse_from(["test-bin", "list"]);
+    assert_eq!(commands.lock_timeout(), Duration::from_secs(5));
+    let commands = Commands::parse_from(["test-bin", "list", "--lock-timeout", "0.5"]);
+    assert_eq!(commands.lock_timeout(), Duration::from_millis(500));
+    let commands = Commands::parse_from(["test-bin", "status", "1", "completed", "--lock-timeout", "10"]);
+    assert_eq!(commands.lock_timeout(), Duration::from_secs(10));
+    let commands = Commands::parse_from(["test-bin", "--list", "--lock-timeout", "1"]);
+    assert_eq!(commands.lock_timeout(), Duration::from_secs(1));
+    assert!(Commands::try_parse_from(["test-bin", "list", "--lock-timeout", "-1"]).is_err());
+}
+
+#[test]
+// Test: commands on a JSON file store (migrate, read-only commands and changes)
+fn test_execute_json_store() {
+    suppress_output(|| {
+        let filename = std::env::temp_dir().join(format!("command_store_{}.json", std::process::id()));
+        let filename = filename.to_string_lossy().into_owned();
+        let legacy = r#"[{"ID": 1, "Title": "X", "Description": "", "CreatedAt": "2024-01-01T00:00:00+00:00",
+            "UpdatedAt": "2024-01-01T00:00:00+00:00", "CompletedAt": "0001-01-01T00:00:00Z", "Status": 0, "Visible": true}]"#;
+        std::fs::write(&filename, legacy).unwrap();
+        let mut store = JsonStore::new(Storage::new(filename.clone()));
+
+        // Dry runs and read-only commands leave the file untouched
+        for args in [vec!["test-bin", "migrate", "--dry-run"], vec!["test-bin", "list"]] {
+            assert!(Commands::parse_from(args).execute(&mut store).is_ok());
+            assert_eq!(std::fs::read_to_string(&filename).unwrap(), legacy);
+        }
+
+        let commands = Commands::parse_from(["test-bin", "add", "--title", "Y"]);
+        assert!(commands.execute(&mut store).is_ok());
+        let content = std::fs::read_to_string(&filename).unwrap();
+        assert!(content.contains("\"version\": 2"));
+        assert!(content.contains("\"Title\": \"Y\""));
+
+        let _ = std::fs::remove_file(&filename);
+        let _ = std::fs::remove_file(format!("{}.bak", filename));
+        let _ = std::fs::remove_file(format!("{}.lock", filename));
+    });
+}
+
+#[test]
+// Test: nothing is saved when a command fails halfway
+fn test_execute_failed_command_is_not_saved() {
+    suppress_output(|| {
+        let mut tasks = MemoryStore::default();
+        tasks.add_task_with("Parent".into(), "".into(), TaskOptions::default()).unwrap();
+        tasks.add_task_with("Child 1".into(), "".into(), TaskOptions { parent: Some(1), ..Default::default() }).unwrap();
+        tasks.add_task_with("Child 2".into(), "".into(), TaskOptions { parent: Some(1), ..Default::default() }).unwrap();
+        tasks.add_task_with("Dependency".into(), "".into(), TaskOptions::default()).unwrap();
+        tasks.add_task_dependencies(3, &[4]).unwrap();
+        let commands = Commands::parse_from(["test-bin", "status", "1", "completed", "--cascade"]);
+        assert!(commands.execute(&mut tasks).is_err());
+        assert!(tasks.iter().all(|task| task.status == TaskStatus::Pending));
+    });
+}
+
+#[test]
+// Test: --store option
+fn test_parse_store() {
+    assert_eq!(Commands::parse_from(["test-bin", "list"]).store(), None);
+    assert!(Commands::try_parse_from(["test-bin", "list", "--store", "memory"]).is_err());
+    assert_eq!(Commands::parse_from(["test-bin", "list", "--store", "sqlite"]).store(), Some(StoreKind::Sqlite));
+    assert_eq!(Commands::parse_from(["test-bin", "log", "--store", "log"]).store(), Some(StoreKind::Log));
+    assert!(Commands::try_parse_from(["test-bin", "list", "--store", "paper"]).is_err());
+}
+
+#[test]
+// Test: storage convert command (JSON file to SQLite database)
+fn test_execute_storage_convert() {
+    suppress_output(|| {
+        let base = std::env::temp_dir().join(format!("command_convert_{}", std::process::id()));
+        let json = format!("{}.json", base.to_string_lossy());
+        let db = format!("{}.db", base.to_string_lossy());
+        let mut memory = MemoryStore::default();
+        let convert = ["test-bin", "storage", "convert", "--from", "json", "--to", "sqlite", "--from-file", &json, "--to-file", &db];
+
+        // Missing source file
+        let result = Commands::parse_from(convert).execute(&mut memory);
+        assert!(matches!(result, Err(CommandError::InvalidArgs(_))));
+        assert!(!std::path::Path::new(&db).exists());
+
+        let mut store = JsonStore::new(Storage::new(json.clone()));
+        for args in [
+            vec!["test-bin", "add", "--title", "X", "--priority", "high", "+trabajo", "--repeat", "daily"],
+            vec!["test-bin", "add", "--title", "Y", "--depends-on", "1"],
+            vec!["test-bin", "add", "--title", "Z"],
+            vec!["test-bin", "rm", "3"],
+        ] {
+            assert!(Commands::parse_from(args).execute(&mut store).is_ok());
+        }
+        let tasks = store.load().unwrap();
+        drop(store);
+
+        assert!(Commands::parse_from(convert).execute(&mut memory).is_ok());
+        assert_eq!(SqliteStore::open(&db, Duration::ZERO).unwrap().load().unwrap(), tasks);
+        assert!(memory.is_empty());
+
+        // A target with tasks is only replaced with --force
+        let result = Commands::parse_from(convert).execute(&mut memory);
+        assert!(matches!(result, Err(CommandError::InvalidArgs(_))));
+        let mut forced = convert.to_vec();
+        forced.push("--force");
+        assert!(Commands::parse_from(forced).execute(&mut memory).is_ok());
+
+        // Same source and target
+        let same = ["test-bin", "storage", "convert", "--from", "json", "--to", "json", "--from-file", &json, "--to-file", &json];
+        assert!(matches!(Commands::parse_from(same).execute(&mut memory), Err(CommandError::InvalidArgs(_))));
+
+        let _ = std::fs::remove_file(&json);
+        let _ = std::fs::remove_file(format!("{}.lock", json));
+        let _ = std::fs::remove_file(&db);
+    });
+}
+
+#[test]
+// Test: log and storage compact commands (only with the event log store)
+fn test_execute_log() {
+    suppress_output(|| {
+        let mut memory = MemoryStore::default();
+        for args in [vec!["test-bin", "log"], vec!["test-bin", "storage", "compact"]] {
+            assert!(matches!(Commands::parse_from(args).execute(&mut memory), Err(CommandError::InvalidArgs(_))));
+        }
+
+        let filename = std::env::temp_dir().join(format!("command_log_{}.log", std::process::id()));
+        let filename = filename.to_string_lossy().into_owned();
+        for args in [
+            vec!["test-bin", "add", "--title", "X"],
+            vec!["test-bin", "status", "1", "completed"],
+            vec!["test-bin", "log", "1"],
+            vec!["test-bin", "log", "2"],
+            vec!["test-bin", "storage", "compact"],
+            vec!["test-bin", "log"],
+        ] {
+            let mut store = LogStore::new(Storage::new(filename.clone()));
+            assert!(Commands::parse_from(args).execute(&mut store).is_ok());
+        }
+
+        let mut store = LogStore::new(Storage::new(filename.clone()));
+        assert_eq!(store.load().unwrap()[0].status, TaskStatus::Completed);
+        let _ = std::fs::remove_file(&filename);
+        let _ = std::fs::remove_file(format!("{}.snapshot", filename));
+        let _ = std::fs::remove_file(format!("{}.lock", filename));
+    });
+}
+
+#[test]
+// Test: undo and redo commands
+fn test_execute_undo_redo() {
+    suppress_output(|| {
+        let mut store = MemoryStore::default();
+
+        // Nothing to undo yet
+        assert!(Commands::parse_from(["test-bin", "undo"]).execute(&mut store).is_ok());
+
+        for args in [vec!["test-bin", "add", "--title", "X"], vec!["test-bin", "--completed", "1"], vec!["test-bin", "--delete", "1"]] {
+            assert!(Commands::parse_from(args).execute(&mut store).is_ok());
+        }
+        assert!(!store[0].visible);
+
+        assert!(Commands::parse_from(["test-bin", "undo"]).execute(&mut store).is_ok());
+        assert!(store[0].visible);
+        assert_eq!(store[0].status, TaskStatus::Completed);
+        assert!(Commands::parse_from(["test-bin", "undo"]).execute(&mut store).is_ok());
+        assert_eq!(store[0].status, TaskStatus::Pending);
+
+        // Read-only commands do not discard the redo history
+        assert!(Commands::parse_from(["test-bin", "list"]).execute(&mut store).is_ok());
+        assert!(Commands::parse_from(["test-bin", "redo"]).execute(&mut store).is_ok());
+        assert_eq!(store[0].status, TaskStatus::Completed);
+
+        assert!(Commands::parse_from(["test-bin", "undo"]).execute(&mut store).is_ok());
+        assert!(Commands::parse_from(["test-bin", "undo"]).execute(&mut store).is_ok());
+        assert!(store.is_empty());
+        assert_eq!(store.next_id(), 2);
+
+        // A task changed outside the journal is not reverted
+        assert!(Commands::parse_from(["test-bin", "redo"]).execute(&mut store).is_ok());
+        store.update_task(1, "Y".into(), "".into()).unwrap();
+        let result = Commands::parse_from(["test-bin", "undo"]).execute(&mut store);
+        assert!(matches!(result, Err(CommandError::TaskError(_))));
+        assert_eq!(store[0].title, "Y");
+    });
+}
+
+#[test]
+// Test: trash, restore and purge commands
+fn test_execute_trash_restore_purge() {
+    suppress_output(|| {
+        let mut store = MemoryStore::default();
+        store.add_task_with("X".into(), "".into(), TaskOptions::default()).unwrap();
+        store.add_task_with("Y".into(), "".into(), TaskOptions::default()).unwrap();
+
+        for args in [vec!["test-bin", "rm", "1"], vec!["test-bin", "rm", "2"], vec!["test-bin", "trash"], vec!["test-bin", "restore", "1"]] {
+            assert!(Commands::parse_from(args).execute(&mut store).is_ok());
+        }
+        assert!(store[0].visible);
+        assert!(matches!(Commands::parse_from(["test-bin", "restore", "1"]).execute(&mut store), Err(CommandError::TaskError(_))));
+
+        // Recently deleted tasks are kept with --older-than
+        assert!(Commands::parse_from(["test-bin", "purge", "--older-than", "30d"]).execute(&mut store).is_ok());
+        assert_eq!(store.len(), 2);
+        assert!(Commands::parse_from(["test-bin", "purge"]).execute(&mut store).is_ok());
+        assert_eq!(store.len(), 1);
+
+        // Purging can be undone
+        assert!(Commands::parse_from(["test-bin", "undo"]).execute(&mut store).is_ok());
+        assert_eq!(store.len(), 2);
+    });
+}
+
+#[test]
+// Test: --older-than option
+fn test_parse_age() {
+    let parse = |age: &str| match Commands::try_parse_from(["test-bin", "purge", "--older-than", age]).unwrap().command {
+        Some(super::Command::Purge { older_than }) => older_than,
+        _ => None,
+    };
+    assert_eq!(parse("30d"), chrono::TimeDelta::try_days(30));
+    assert_eq!(parse("30"), chrono::TimeDelta::try_days(30));
+    assert_eq!(parse("2w"), chrono::TimeDelta::try_weeks(2));
+    assert_eq!(parse("12h"), chrono::TimeDelta::try_hours(12));
+    for age in ["", "d", "3m", "-1d"] {
+        assert!(Commands::try_parse_from(["test-bin", "purge", "--older-than", age]).is_err());
+    }
+}
+
+#[test]
+// Test: archive command (archived tasks are added to the archive file, and it can be undone)
+fn test_execute_archive() {
+    suppress_output(|| {
+        let filename = std::env::temp_dir().join(format!("command_archive_{}.json", std::process::id()));
+        let filename = filename.to_string_lossy().into_owned();
+        let mut store = MemoryStore::default();
+
+        for title in ["X", "Y", "Z"] {
+            store.add_task_with(title.into(), "".into(), TaskOptions::default()).unwrap();
+        }
+
+        for id in [1, 2] {
+            store.change_task_status(id, TaskStatus::Completed).unwrap();
+        }
+
+        // Recently completed tasks stay with the default age
+        assert!(Commands::parse_from(["test-bin", "archive", "--file", &filename]).execute(&mut store).is_ok());
+        assert_eq!(store.len(), 3);
+        assert!(!std::path::Path::new(&filename).exists());
+
+        let archive_now = ["test-bin", "archive", "--older-than", "0d", "--file", &filename];
+        assert!(Commands::parse_from(archive_now).execute(&mut store).is_ok());
+        assert_eq!(store.len(), 1);
+        store.change_task_status(3, TaskStatus::Completed).unwrap();
+        assert!(Commands::parse_from(archive_now).execute(&mut store).is_ok());
+        assert!(store.is_empty());
+
+        let archive: Storage<crate::tasks::Tasks> = Storage::new(filename.clone());
+        let archived: Vec<usize> = archive.download_data().unwrap().unwrap().iter().map(|task| task.id).collect();
+        assert_eq!(archived, [1, 2, 3]);
+
+        // Archiving can be undone like any other command
+        assert!(Commands::parse_from(["test-bin", "undo"]).execute(&mut store).is_ok());
+        assert_eq!(store.iter().map(|task| task.id).collect::<Vec<_>>(), [3]);
+        let _ = std::fs::remove_file(&filename);
+        let _ = std::fs::remove_file(format!("{}.bak", filename));
+        let _ = std::fs::remove_file(format!("{}.lock", filename));
+    });
+}
+
+#[test]
+// Test: list --format option
+fn test_execute_list_format() {
+    suppress_output(|| {
+        let mut store = MemoryStore::default();
+        store.add_task_with("X".into(), "".into(), TaskOptions::default()).unwrap();
+
+        for format in ["table", "json", "ndjson", "csv", "tsv", "markdown", "plain"] {
+            assert!(Commands::parse_from(["test-bin", "list", "--format", format]).execute(&mut store).is_ok());
+        }
+
+        assert!(Commands::try_parse_from(["test-bin", "list", "--format", "xml"]).is_err());
+    });
+}
+
+#[test]
+// Test: list with a query and saved queries
+fn test_execute_list_query() {
+    suppress_output(|| {
+        let filename = std::env::temp_dir().join(format!("command_queries_{}.toml", std::process::id()));
+        let filename = filename.to_string_lossy().into_owned();
+        let with_config = |args: &[&str]| {
+            let config = crate::config::Config::load(&filename).unwrap();
+            Commands::parse_from(args).with_config(&filename, &config)
+        };
+        let mut store = MemoryStore::default();
+        store.add_task_with("X".into(), "".into(), TaskOptions::default()).unwrap();
+
+        assert!(Commands::parse_from(["test-bin", "list", "status:pending and (tag:a or priority>=high)"]).execute(&mut store).is_ok());
+        assert!(matches!(Commands::parse_from(["test-bin", "list", "status:"]).execute(&mut store), Err(CommandError::InvalidArgs(_))));
+        assert!(matches!(Commands::parse_from(["test-bin", "list", "@abiertas"]).execute(&mut store), Err(CommandError::InvalidArgs(_))));
+
+        // Saved queries are validated before they are written
+        assert!(with_config(&["test-bin", "query", "save", "abiertas", "status!=completed"]).execute(&mut store).is_ok());
+        assert!(with_config(&["test-bin", "query", "save", "mala", "status~x"]).execute(&mut store).is_err());
+        assert!(with_config(&["test-bin", "query", "save", "con espacio", "id=1"]).execute(&mut store).is_err());
+        assert!(with_config(&["test-bin", "list", "@abiertas and id=1"]).execute(&mut store).is_ok());
+        assert!(with_config(&["test-bin", "query", "list"]).execute(&mut store).is_ok());
+        assert!(with_config(&["test-bin", "query", "rm", "abiertas"]).execute(&mut store).is_ok());
+        assert!(with_config(&["test-bin", "query", "rm", "abiertas"]).execute(&mut store).is_err());
+        assert!(with_config(&["test-bin", "list", "@abiertas"]).execute(&mut store).is_err());
+        let _ = std::fs::remove_file(&filename);
+    });
+}
+
+#[test]
+// Test: search command
+fn test_execute_search() {
+    suppress_output(|| {
+        let mut store = MemoryStore::default();
+        store.add_task_with("Pagar la factura".into(), "Luz de octubre".into(), TaskOptions::default()).unwrap();
+        store.add_task_with("Informe".into(), "".into(), TaskOptions::default()).unwrap();
+
+        assert!(Commands::parse_from(["test-bin", "search", "factura"]).execute(&mut store).is_ok());
+        assert!(Commands::parse_from(["test-bin", "search", "luz de octubre", "fact*", "--limit", "1"]).execute(&mut store).is_ok());
+        assert!(Commands::parse_from(["test-bin", "search", "nada"]).execute(&mut store).is_ok());
+        assert!(matches!(Commands::parse_from(["test-bin", "search", "\"luz"]).execute(&mut store), Err(CommandError::InvalidArgs(_))));
+        assert!(Commands::try_parse_from(["test-bin", "search"]).is_err());
+    });
+}
+
+#[test]
+// Test: list --sort, --columns and --wide
+fn test_execute_list_layout() {
+    suppress_output(|| {
+        let mut store = MemoryStore::default();
+        store.add_task_with("X".into(), "".into(), TaskOptions::default()).unwrap();
+
+        assert!(Commands::parse_from(["test-bin", "list", "--sort", "due,-priority,created", "--columns", "id,title,status,due"]).execute(&mut store).is_ok());
+        assert!(Commands::parse_from(["test-bin", "list", "--wide", "-c", "id,description"]).execute(&mut store).is_ok());
+        assert!(Commands::try_parse_from(["test-bin", "list", "--sort", "size"]).is_err());
+        assert!(Commands::try_parse_from(["test-bin", "list", "--columns", "id,size"]).is_err());
+    });
+}
+
+#[test]
+// Test: ID lists and ranges
+fn test_parse_ids() {
+    let parse = |ids: &str| match Commands::try_parse_from(["test-bin", "rm", ids]).unwrap().command {
+        Some(super::Command::Rm { ids: Some(ids), .. }) => ids.0,
+        _ => Vec::new(),
+    };
+    assert_eq!(parse("3"), [3]);
+    assert_eq!(parse("3,5,9-12"), [3, 5, 9, 10, 11, 12]);
+    assert_eq!(parse("2-3,1-3"), [2, 3, 1]);
+    for ids in ["", "3,", "a-b", "5-2", "1--2"] {
+        assert!(Commands::try_parse_from(["test-bin", "rm", ids]).is_err());
+    }
+    assert!(Commands::try_parse_from(["test-bin", "rm"]).is_err());
+    assert!(Commands::try_parse_from(["test-bin", "rm", "1", "--where", "id=1"]).is_err());
+    assert!(Commands::try_parse_from(["test-bin", "status", "completed", "--where", "tag:a"]).is_ok());
+    assert!(Commands::try_parse_from(["test-bin", "tag", "+x", "--where", "tag:a"]).is_ok());
+}
+
+#[test]
+// Test: commands on several tasks, by IDs or by query
+fn test_execute_bulk() {
+    suppress_output(|| {
+        let mut store = MemoryStore::default();
+
+        for title in ["A", "B", "C", "D", "E", "F", "G"] {
+            store.add_task_with(title.into(), "".into(), TaskOptions::default()).unwrap();
+        }
+
+        assert!(Commands::parse_from(["test-bin", "tag", "1-3", "+sprint-12"]).execute(&mut store).is_ok());
+        assert!(Commands::parse_from(["test-bin", "done", "--where", "tag:sprint-12"]).execute(&mut store).is_ok());
+        assert_eq!(store.iter().filter(|task| task.status == TaskStatus::Completed).count(), 3);
+        assert!(Commands::parse_from(["test-bin", "status", "1,3", "pending"]).execute(&mut store).is_ok());
+        assert_eq!(store[0].status, TaskStatus::Pending);
+        assert_eq!(store[1].status, TaskStatus::Completed);
+
+        // Nothing matching the query is not an error
+        assert!(Commands::parse_from(["test-bin", "rm", "--where", "tag:nada"]).execute(&mut store).is_ok());
+        assert!(matches!(Commands::parse_from(["test-bin", "rm", "--where", "tag:"]).execute(&mut store), Err(CommandError::InvalidArgs(_))));
+
+        // More tasks than the configured limit need --yes
+        let result = Commands::parse_from(["test-bin", "mv", "--where", "status:pending", "casa"]).execute(&mut store);
+        assert!(matches!(result, Err(CommandError::InvalidArgs(_))));
+        assert!(store.iter().all(|task| task.project == "general"));
+        assert!(Commands::parse_from(["test-bin", "mv", "--where", "status:pending", "casa", "--yes"]).execute(&mut store).is_ok());
+        assert_eq!(store.iter().filter(|task| task.project == "casa").count(), 6);
+
+        // Deleted tasks are restored with a query too, and the whole command is undone at once
+        assert!(Commands::parse_from(["test-bin", "rm", "1-3"]).execute(&mut store).is_ok());
+        assert!(Commands::parse_from(["test-bin", "restore", "--where", "id<=2"]).execute(&mut store).is_ok());
+        assert!(store[0].visible && store[1].visible && !store[2].visible);
+        assert!(Commands::parse_from(["test-bin", "undo"]).execute(&mut store).is_ok());
+        assert!(Commands::parse_from(["test-bin", "undo"]).execute(&mut store).is_ok());
+        assert!(store[0].visible && store[1].visible && store[2].visible);
+    });
+}
+
+#[test]
+// Test: failures on some tasks are reported and the other tasks are still changed
+fn test_execute_bulk_partial_failure() {
+    suppress_output(|| {
+        let mut store = MemoryStore::default();
+        store.add_task_with("X".into(), "".into(), TaskOptions::default()).unwrap();
+        store.add_task_with("Y".into(), "".into(), TaskOptions::default()).unwrap();
+
+        let result = Commands::parse_from(["test-bin", "rm", "1,7,2"]).execute(&mut store);
+        assert!(matches!(result, Err(CommandError::Partial { failed: 1, total: 3 })));
+        assert!(!store[0].visible && !store[1].visible);
+
+        // A single task keeps the usual error
+        let result = Commands::parse_from(["test-bin", "rm", "7"]).execute(&mut store);
+        assert!(matches!(result, Err(CommandError::TaskError(_))));
+
+        // Legacy flags take ID lists too
+        assert!(Commands::parse_from(["test-bin", "restore", "1,2"]).execute(&mut store).is_ok());
+        assert!(Commands::parse_from(["test-bin", "--completed", "1,2"]).execute(&mut store).is_ok());
+        assert!(store.iter().all(|task| task.status == TaskStatus::Completed));
+    });
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::str::FromStr;
+
+use crate::render::{Columns, SortOrder};
+use crate::storage::StoreKind;
+
+// Configuration file, read from the working directory when present
+pub const CONFIG_FILE: &str = "todo.toml";
+
+// Errors shown to the user when the configuration cannot be read
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Edit(toml_edit::TomlError),
+}
+
+// Implement fmt::Display for ConfigError
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "{}", e),
+            ConfigError::Parse(e) => write!(f, "Configuración inválida en {}: {}", CONFIG_FILE, e.message()),
+            ConfigError::Edit(e) => write!(f, "Configuración inválida en {}: {}", CONFIG_FILE, e.message()),
+        }
+    }
+}
+
+// Application configuration (every section is optional)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // Editor for edit <ID> (instead of $VISUAL or $EDITOR)
+    pub editor: Option<String>,
+    pub storage: StorageConfig,
+    pub list: ListConfig,
+    pub bulk: BulkConfig,
+    // Saved queries by name (used as @name)
+    pub queries: BTreeMap<String, String>,
+}
+
+// Storage section: backend and data file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub backend: StoreKind,
+    pub file: Option<String>,
+}
+
+// List section: default layout of the list command (overridden by its options)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ListConfig {
+    #[serde(deserialize_with = "parse_option")]
+    pub columns: Option<Columns>,
+    #[serde(deserialize_with = "parse_option")]
+    pub sort: Option<SortOrder>,
+    pub wide: bool,
+}
+
+// Bulk section: commands applied to several tasks at once
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BulkConfig {
+    // Number of tasks a command can change without asking for confirmation
+    pub confirm_above: usize,
+}
+
+// Implement Default for BulkConfig
+impl Default for BulkConfig {
+    fn default() -> Self {
+        Self { confirm_above: 5 }
+    }
+}
+
+// Read a value written as text with the same syntax as the command line (e.g. "due,-priority")
+fn parse_option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    let value = String::deserialize(deserializer)?;
+    value.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+// Config implementation
+impl Config {
+    // Read the configuration file (defaults when it does not exist)
+    pub fn load(file_name: &str) -> Result<Self, ConfigError> {
+        match fs::read_to_string(file_name) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(ConfigError::Io(e)),
+        }
+    }
+
+    // Parse the configuration from TOML
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        toml::from_str(content).map_err(ConfigError::Parse)
+    }
+}
+
+// Read the configuration file as an editable document (comments and layout are kept)
+fn read_document(file_name: &str) -> Result<toml_edit::DocumentMut, ConfigError> {
+    let content = match fs::read_to_string(file_name) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(ConfigError::Io(e)),
+    };
+
+    content.parse().map_err(ConfigError::Edit)
+}
+
+// Save a query in the [queries] section (replacing the one with the same name)
+pub fn save_query(file_name: &str, name: &str, query: &str) -> Result<(), ConfigError> {
+    let mut document = read_document(file_name)?;
+    let queries = document.entry("queries").or_insert(toml_edit::table());
+
+    let Some(queries) = queries.as_table_like_mut() else {
+        return Err(ConfigError::Io(io::Error::new(io::ErrorKind::InvalidData, "[queries] no es una tabla")));
+    };
+
+    queries.insert(name, toml_edit::value(query));
+    fs::write(file_name, document.to_string()).map_err(ConfigError::Io)
+}
+
+// Remove a saved query (false when there was none with that name)
+pub fn remove_query(file_name: &str, name: &str) -> Result<bool, ConfigError> {
+    let mut document = read_document(file_name)?;
+    let removed = document
+        .get_mut("queries")
+        .and_then(|queries| queries.as_table_like_mut())
+        .and_then(|queries| queries.remove(name))
+        .is_some();
+
+    if removed {
+        fs::write(file_name, document.to_string()).map_err(ConfigError::Io)?;
+    }
+
+    Ok(removed)
+}
+
+// Test configuration
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,65 @@
+use super::{remove_query, save_query, Config, ConfigError};
+use crate::storage::StoreKind;
+
+#[test]
+// Test: missing configuration file uses the defaults
+fn test_load_missing_config() {
+    let config = Config::load("missing_config_for_testing.toml").unwrap();
+    assert_eq!(config.storage.backend, StoreKind::Json);
+    assert!(config.storage.file.is_none());
+}
+
+#[test]
+// Test: parse the storage section (unknown sections are ignored)
+fn test_parse_storage_config() {
+    let config = Config::parse("[storage]\nbackend = \"sqlite\"\nfile = \"other.json\"\n\n[other]\nkey = 1\n").unwrap();
+    assert_eq!(config.storage.backend, StoreKind::Sqlite);
+    assert_eq!(config.storage.file.as_deref(), Some("other.json"));
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.storage.backend, StoreKind::Json);
+}
+
+#[test]
+// Test: parse an invalid configuration
+fn test_parse_invalid_config() {
+    let err = Config::parse("[storage]\nbackend = \"paper\"\n").unwrap_err();
+    assert!(matches!(err, ConfigError::Parse(_)));
+    assert!(err.to_string().starts_with("Configuración inválida en todo.toml"));
+}
+
+#[test]
+// Test: save and remove queries, keeping the rest of the file
+fn test_save_remove_query() {
+    let filename = std::env::temp_dir().join(format!("config_queries_{}.toml", std::process::id()));
+    let filename = filename.to_string_lossy().into_owned();
+    std::fs::write(&filename, "# Almacenamiento\n[storage]\nbackend = \"log\"\n").unwrap();
+
+    save_query(&filename, "trabajo", "tag:trabajo and status:pending").unwrap();
+    save_query(&filename, "urgentes", "priority:urgent").unwrap();
+    save_query(&filename, "trabajo", "tag:trabajo").unwrap();
+
+    let config = Config::load(&filename).unwrap();
+    assert_eq!(config.storage.backend, StoreKind::Log);
+    assert_eq!(config.queries.len(), 2);
+    assert_eq!(config.queries["trabajo"], "tag:trabajo");
+    assert!(std::fs::read_to_string(&filename).unwrap().starts_with("# Almacenamiento\n"));
+
+    assert!(remove_query(&filename, "urgentes").unwrap());
+    assert!(!remove_query(&filename, "urgentes").unwrap());
+    assert_eq!(Config::load(&filename).unwrap().queries.len(), 1);
+    let _ = std::fs::remove_file(&filename);
+}
+
+#[test]
+// Test: parse the list section (columns and sort use the command line syntax)
+fn test_parse_list_config() {
+    let config = Config::parse("[list]\ncolumns = \"id,title,due\"\nsort = \"due,-priority\"\nwide = true\n").unwrap();
+    assert_eq!(config.list.columns.unwrap().0.len(), 3);
+    assert_eq!(config.list.sort.unwrap().0.len(), 2);
+    assert!(config.list.wide);
+    assert_eq!(Config::default().bulk.confirm_above, 5);
+    assert_eq!(Config::parse("[bulk]\nconfirm_above = 20\n").unwrap().bulk.confirm_above, 20);
+
+    let err = Config::parse("[list]\ncolumns = \"id,size\"\n").unwrap_err();
+    assert!(err.to_string().contains("Columna desconocida: \"size\""));
+}
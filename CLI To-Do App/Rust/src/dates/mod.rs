@@ -0,0 +1,313 @@
+use chrono::{DateTime, Datelike, Days, Duration, FixedOffset, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use chrono::Offset;
+use std::fmt;
+
+// Errors shown to the user when a date argument cannot be understood
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateError {
+    Empty,
+    Unrecognized(String),
+    InvalidTime(String),
+    OutOfRange,
+}
+
+// Implement fmt::Display for DateError
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateError::Empty => write!(f, "La fecha no puede estar vacía"),
+            DateError::Unrecognized(s) => write!(f, "Fecha no reconocida: \"{}\" (ejemplos: hoy, mañana, next friday, in 3 days, 2025-11-02 17:00)", s),
+            DateError::InvalidTime(s) => write!(f, "Hora inválida: \"{}\" (formato esperado: HH:MM)", s),
+            DateError::OutOfRange => write!(f, "La fecha está fuera del rango permitido"),
+        }
+    }
+}
+
+// Get local datetime with timezone
+pub(crate) fn now_fixed() -> DateTime<FixedOffset> {
+    let local_now = Local::now();
+    let offset = local_now.offset().fix();
+    local_now.with_timezone(&offset)
+}
+
+// Fixed reference moment for tests: Wednesday 5 November 2025
+#[cfg(test)]
+pub(crate) fn reference_now() -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339("2025-11-05T12:00:00-04:00").unwrap()
+}
+
+// Parse a date argument relative to the current local time
+pub fn parse_date_now(input: &str) -> Result<DateTime<FixedOffset>, DateError> {
+    parse_date(input, now_fixed())
+}
+
+// Parse a date argument relative to `now` (dates without a time resolve to the end of the day)
+pub fn parse_date(input: &str, now: DateTime<FixedOffset>) -> Result<DateTime<FixedOffset>, DateError> {
+    let trimmed = input.trim();
+
+    if trimmed.is_empty() {
+        return Err(DateError::Empty);
+    }
+
+    // Full RFC 3339 timestamps keep their own offset
+    if let Ok(date) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(date);
+    }
+
+    let offset = *now.offset();
+
+    // Absolute dates and datetimes (ISO and day-first formats)
+    if let Some(naive) = parse_absolute(trimmed) {
+        return resolve(offset, naive);
+    }
+
+    let normalized = normalize(trimmed);
+    let mut words: Vec<&str> = normalized.split_whitespace().collect();
+
+    // Optional time at the end: "mañana 17:00", "next friday at 9:30", "lunes a las 8:00"
+    let time = match words.last() {
+        Some(last) if looks_like_time(last) => {
+            let time = parse_time(last).ok_or_else(|| DateError::InvalidTime(last.to_string()))?;
+            words.pop();
+            strip_time_connector(&mut words);
+            Some(time)
+        }
+        _ => None,
+    };
+
+    let today = now.date_naive();
+
+    let target = match parse_relative(&words, now)? {
+        Relative::Day(date) => date.and_time(time.unwrap_or_else(end_of_day)),
+        Relative::Moment(moment) => match time {
+            Some(time) => moment.date().and_time(time),
+            None => moment,
+        },
+        Relative::TimeOnly => match time {
+            Some(time) => today.and_time(time),
+            None => return Err(DateError::Unrecognized(trimmed.to_string())),
+        },
+        Relative::Unknown => return Err(DateError::Unrecognized(trimmed.to_string())),
+    };
+
+    resolve(offset, target)
+}
+
+// Result of interpreting the words of a relative date
+enum Relative {
+    Day(NaiveDate),
+    Moment(NaiveDateTime),
+    TimeOnly,
+    Unknown,
+}
+
+// Interpret the date part of the input (time already removed)
+fn parse_relative(words: &[&str], now: DateTime<FixedOffset>) -> Result<Relative, DateError> {
+    let today = now.date_naive();
+
+    let relative = match words {
+        [] => Relative::TimeOnly,
+        ["now"] | ["ahora"] => Relative::Moment(now.naive_local()),
+        ["today"] | ["hoy"] => Relative::Day(today),
+        ["tomorrow"] | ["manana"] | ["tmr"] => Relative::Day(add_days(today, 1)?),
+        ["yesterday"] | ["ayer"] => Relative::Day(today.pred_opt().ok_or(DateError::OutOfRange)?),
+        ["pasado", "manana"] | ["day", "after", "tomorrow"] => Relative::Day(add_days(today, 2)?),
+        ["eow"] | ["end", "of", "week"] | ["fin", "de", "semana"] | ["fin", "de", "la", "semana"] => {
+            let days_left = 6 - today.weekday().num_days_from_monday() as u64;
+            Relative::Day(add_days(today, days_left)?)
+        }
+        ["eom"] | ["end", "of", "month"] | ["fin", "de", "mes"] | ["fin", "del", "mes"] => {
+            let first = today.with_day(1).ok_or(DateError::OutOfRange)?;
+            let next_month = first.checked_add_months(Months::new(1)).ok_or(DateError::OutOfRange)?;
+            Relative::Day(next_month.pred_opt().ok_or(DateError::OutOfRange)?)
+        }
+        ["next", "week"] | ["proxima", "semana"] | ["la", "proxima", "semana"] | ["semana", "que", "viene"] => {
+            Relative::Day(add_days(today, 7)?)
+        }
+        [word] => match (parse_weekday(word), parse_compact(word)) {
+            (Some(weekday), _) => Relative::Day(add_days(today, days_until(today, weekday, false))?),
+            (None, Some((amount, unit))) => shift(now, amount, unit)?,
+            (None, None) => Relative::Unknown,
+        },
+        ["this", day] | ["este", day] | ["el", day] => match parse_weekday(day) {
+            Some(weekday) => Relative::Day(add_days(today, days_until(today, weekday, false))?),
+            None => Relative::Unknown,
+        },
+        ["next", day] | ["proximo", day] | ["proxima", day] | ["el", "proximo", day] | [day, "que", "viene"] => {
+            match parse_weekday(day) {
+                Some(weekday) => Relative::Day(add_days(today, days_until(today, weekday, true))?),
+                None => Relative::Unknown,
+            }
+        }
+        ["in", amount, unit] | ["en", amount, unit] | ["dentro", "de", amount, unit] => {
+            match (amount.parse::<u32>(), parse_unit(unit)) {
+                (Ok(amount), Some(unit)) => shift(now, amount, unit)?,
+                _ => Relative::Unknown,
+            }
+        }
+        _ => Relative::Unknown,
+    };
+
+    Ok(relative)
+}
+
+// Units accepted in relative offsets ("in 3 days", "en 2 semanas", "+4h")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+    Months,
+}
+
+// Move `now` forward by the given amount (day-based units resolve to the end of the day)
+fn shift(now: DateTime<FixedOffset>, amount: u32, unit: Unit) -> Result<Relative, DateError> {
+    let naive = now.naive_local();
+    let today = now.date_naive();
+
+    let relative = match unit {
+        Unit::Minutes => Relative::Moment(
+            naive.checked_add_signed(Duration::minutes(amount as i64)).ok_or(DateError::OutOfRange)?,
+        ),
+        Unit::Hours => Relative::Moment(
+            naive.checked_add_signed(Duration::hours(amount as i64)).ok_or(DateError::OutOfRange)?,
+        ),
+        Unit::Days => Relative::Day(add_days(today, amount as u64)?),
+        Unit::Weeks => Relative::Day(add_days(today, amount as u64 * 7)?),
+        Unit::Months => Relative::Day(today.checked_add_months(Months::new(amount)).ok_or(DateError::OutOfRange)?),
+    };
+
+    Ok(relative)
+}
+
+// Parse unit names in English and Spanish (singular and plural)
+fn parse_unit(unit: &str) -> Option<Unit> {
+    match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" | "minuto" | "minutos" => Some(Unit::Minutes),
+        "h" | "hour" | "hours" | "hora" | "horas" => Some(Unit::Hours),
+        "d" | "day" | "days" | "dia" | "dias" => Some(Unit::Days),
+        "w" | "week" | "weeks" | "semana" | "semanas" => Some(Unit::Weeks),
+        "month" | "months" | "mes" | "meses" => Some(Unit::Months),
+        _ => None,
+    }
+}
+
+// Parse compact offsets such as "+3d", "2w" or "+4h"
+fn parse_compact(value: &str) -> Option<(u32, Unit)> {
+    let value = value.strip_prefix('+').unwrap_or(value);
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(split);
+
+    if amount.is_empty() || !matches!(unit, "m" | "h" | "d" | "w") {
+        return None;
+    }
+
+    Some((amount.parse().ok()?, parse_unit(unit)?))
+}
+
+// Parse weekday names in English and Spanish (also used by recurrence rules)
+pub fn parse_weekday(day: &str) -> Option<Weekday> {
+    match day {
+        "monday" | "mon" | "lunes" | "lun" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "martes" | "mar" => Some(Weekday::Tue),
+        "wednesday" | "wed" | "miercoles" | "mie" => Some(Weekday::Wed),
+        "thursday" | "thu" | "jueves" | "jue" => Some(Weekday::Thu),
+        "friday" | "fri" | "viernes" | "vie" => Some(Weekday::Fri),
+        "saturday" | "sat" | "sabado" | "sab" => Some(Weekday::Sat),
+        "sunday" | "sun" | "domingo" | "dom" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+// Days from `today` until the given weekday (`strict` skips today)
+fn days_until(today: NaiveDate, weekday: Weekday, strict: bool) -> u64 {
+    let current = today.weekday().num_days_from_monday() as i64;
+    let target = weekday.num_days_from_monday() as i64;
+    let days = (target - current).rem_euclid(7) as u64;
+
+    if strict && days == 0 { 7 } else { days }
+}
+
+// Add days to a date, failing when the result is out of range
+fn add_days(date: NaiveDate, days: u64) -> Result<NaiveDate, DateError> {
+    date.checked_add_days(Days::new(days)).ok_or(DateError::OutOfRange)
+}
+
+// Absolute formats: "2025-11-02", "2025-11-02 17:00", "2025-11-02T17:00:00", "02-11-2025", "02/11/2025 17:00"
+fn parse_absolute(value: &str) -> Option<NaiveDateTime> {
+    const DATETIME_FORMATS: [&str; 8] = [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%d-%m-%Y %H:%M",
+        "%d-%m-%Y %H:%M:%S",
+        "%d/%m/%Y %H:%M",
+        "%d/%m/%Y %H:%M:%S",
+    ];
+
+    const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%d-%m-%Y", "%d/%m/%Y"];
+
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            DATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+                .map(|date| date.and_time(end_of_day()))
+        })
+}
+
+// Check whether a word has the shape of a time ("17:00", "9:30:15")
+fn looks_like_time(word: &str) -> bool {
+    word.contains(':') && word.chars().all(|c| c.is_ascii_digit() || c == ':')
+}
+
+// Parse "HH:MM" or "HH:MM:SS"
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(word, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(word, "%H:%M:%S"))
+        .ok()
+}
+
+// Remove connectors placed before the time ("at", "a las", "a la")
+fn strip_time_connector(words: &mut Vec<&str>) {
+    match words.as_slice() {
+        [.., "a", "las"] | [.., "a", "la"] => words.truncate(words.len() - 2),
+        [.., "at"] | [.., "@"] => words.truncate(words.len() - 1),
+        _ => {}
+    }
+}
+
+// Time used for dates given without an hour
+pub fn end_of_day() -> NaiveTime {
+    NaiveTime::from_hms_opt(23, 59, 59).unwrap()
+}
+
+// Lowercase and remove Spanish accents, so "Mañana" and "manana" are equivalent
+pub fn normalize(value: &str) -> String {
+    value
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' => 'a',
+            'é' => 'e',
+            'í' => 'i',
+            'ó' => 'o',
+            'ú' | 'ü' => 'u',
+            'ñ' => 'n',
+            _ => c,
+        })
+        .collect()
+}
+
+// Attach the offset to a naive datetime
+fn resolve(offset: FixedOffset, naive: NaiveDateTime) -> Result<DateTime<FixedOffset>, DateError> {
+    offset.from_local_datetime(&naive).single().ok_or(DateError::OutOfRange)
+}
+
+// Test configuration
+#[cfg(test)]
+mod tests;
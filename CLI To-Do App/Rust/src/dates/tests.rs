@@ -0,0 +1,107 @@
+use super::{parse_date, DateError};
+use chrono::{DateTime, FixedOffset};
+
+// Fixed reference moment: Wednesday 05-11-2025 10:30 at -04:00
+fn now() -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339("2025-11-05T10:30:00-04:00").unwrap()
+}
+
+// Parse relative to the reference moment and format as RFC 3339
+fn parse(input: &str) -> String {
+    parse_date(input, now()).unwrap().to_rfc3339()
+}
+
+#[test]
+// Test: RFC 3339 keeps its own offset
+fn test_parse_rfc3339() {
+    assert_eq!(parse("2025-11-02T17:00:00+02:00"), "2025-11-02T17:00:00+02:00");
+}
+
+#[test]
+// Test: absolute dates and datetimes use the local offset
+fn test_parse_absolute() {
+    assert_eq!(parse("2025-11-02 17:00"), "2025-11-02T17:00:00-04:00");
+    assert_eq!(parse("2025-11-02T17:00"), "2025-11-02T17:00:00-04:00");
+    assert_eq!(parse("2025-11-02"), "2025-11-02T23:59:59-04:00");
+    assert_eq!(parse("02-11-2025 08:15"), "2025-11-02T08:15:00-04:00");
+    assert_eq!(parse("02/11/2025"), "2025-11-02T23:59:59-04:00");
+}
+
+#[test]
+// Test: today, tomorrow and yesterday (English and Spanish)
+fn test_parse_day_keywords() {
+    assert_eq!(parse("today"), "2025-11-05T23:59:59-04:00");
+    assert_eq!(parse("hoy"), "2025-11-05T23:59:59-04:00");
+    assert_eq!(parse("tomorrow"), "2025-11-06T23:59:59-04:00");
+    assert_eq!(parse("mañana"), "2025-11-06T23:59:59-04:00");
+    assert_eq!(parse("Manana"), "2025-11-06T23:59:59-04:00");
+    assert_eq!(parse("pasado mañana"), "2025-11-07T23:59:59-04:00");
+    assert_eq!(parse("ayer"), "2025-11-04T23:59:59-04:00");
+    assert_eq!(parse("now"), "2025-11-05T10:30:00-04:00");
+}
+
+#[test]
+// Test: keywords followed by a time
+fn test_parse_day_keywords_with_time() {
+    assert_eq!(parse("tomorrow 17:00"), "2025-11-06T17:00:00-04:00");
+    assert_eq!(parse("mañana a las 9:30"), "2025-11-06T09:30:00-04:00");
+    assert_eq!(parse("next friday at 08:00"), "2025-11-07T08:00:00-04:00");
+    assert_eq!(parse("18:45"), "2025-11-05T18:45:00-04:00");
+}
+
+#[test]
+// Test: weekdays (plain, "next" and Spanish equivalents)
+fn test_parse_weekdays() {
+    assert_eq!(parse("friday"), "2025-11-07T23:59:59-04:00");
+    assert_eq!(parse("wednesday"), "2025-11-05T23:59:59-04:00");
+    assert_eq!(parse("next wednesday"), "2025-11-12T23:59:59-04:00");
+    assert_eq!(parse("next friday"), "2025-11-07T23:59:59-04:00");
+    assert_eq!(parse("próximo lunes"), "2025-11-10T23:59:59-04:00");
+    assert_eq!(parse("el próximo miércoles"), "2025-11-12T23:59:59-04:00");
+    assert_eq!(parse("sábado"), "2025-11-08T23:59:59-04:00");
+    assert_eq!(parse("viernes que viene"), "2025-11-07T23:59:59-04:00");
+}
+
+#[test]
+// Test: relative offsets
+fn test_parse_offsets() {
+    assert_eq!(parse("in 3 days"), "2025-11-08T23:59:59-04:00");
+    assert_eq!(parse("en 3 días"), "2025-11-08T23:59:59-04:00");
+    assert_eq!(parse("dentro de 2 semanas"), "2025-11-19T23:59:59-04:00");
+    assert_eq!(parse("in 1 month"), "2025-12-05T23:59:59-04:00");
+    assert_eq!(parse("in 2 hours"), "2025-11-05T12:30:00-04:00");
+    assert_eq!(parse("en 45 minutos"), "2025-11-05T11:15:00-04:00");
+    assert_eq!(parse("+3d"), "2025-11-08T23:59:59-04:00");
+    assert_eq!(parse("2w"), "2025-11-19T23:59:59-04:00");
+    assert_eq!(parse("+4h"), "2025-11-05T14:30:00-04:00");
+}
+
+#[test]
+// Test: end of week, end of month and next week
+fn test_parse_period_ends() {
+    assert_eq!(parse("eow"), "2025-11-09T23:59:59-04:00");
+    assert_eq!(parse("fin de semana"), "2025-11-09T23:59:59-04:00");
+    assert_eq!(parse("eom"), "2025-11-30T23:59:59-04:00");
+    assert_eq!(parse("fin de mes"), "2025-11-30T23:59:59-04:00");
+    assert_eq!(parse("next week"), "2025-11-12T23:59:59-04:00");
+    assert_eq!(parse("la próxima semana"), "2025-11-12T23:59:59-04:00");
+}
+
+#[test]
+// Test: end of week on a Sunday is the same day
+fn test_parse_eow_on_sunday() {
+    let sunday = DateTime::parse_from_rfc3339("2025-11-09T10:00:00-04:00").unwrap();
+    assert_eq!(parse_date("eow", sunday).unwrap().to_rfc3339(), "2025-11-09T23:59:59-04:00");
+}
+
+#[test]
+// Test: invalid inputs
+fn test_parse_invalid() {
+    assert_eq!(parse_date("", now()), Err(DateError::Empty));
+    assert_eq!(parse_date("   ", now()), Err(DateError::Empty));
+    assert!(matches!(parse_date("someday", now()), Err(DateError::Unrecognized(_))));
+    assert!(matches!(parse_date("next month please", now()), Err(DateError::Unrecognized(_))));
+    assert!(matches!(parse_date("in x days", now()), Err(DateError::Unrecognized(_))));
+    assert!(matches!(parse_date("2025-13-40", now()), Err(DateError::Unrecognized(_))));
+    assert!(matches!(parse_date("tomorrow 25:00", now()), Err(DateError::InvalidTime(_))));
+}
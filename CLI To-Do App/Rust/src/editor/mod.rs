@@ -0,0 +1,293 @@
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crate::dates::{normalize, now_fixed, parse_date};
+use crate::tasks::{Recurrence, Task, TaskError, TaskFields, TaskPriority, TaskStatus, Tasks};
+
+// Start of the lines added above the document to explain why it was re-opened
+const ERROR_MARK: &str = "# Error:";
+
+// Errors shown to the user when a task cannot be edited
+#[derive(Debug)]
+pub enum EditorError {
+    Io(io::Error),
+    Failed(String),
+    Task(TaskError),
+}
+
+// Implement fmt::Display for EditorError
+impl fmt::Display for EditorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditorError::Io(e) => write!(f, "{}", e),
+            EditorError::Failed(editor) => write!(f, "El editor \"{}\" terminó con un error; la tarea no se modificó", editor),
+            EditorError::Task(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+// Editor chosen by the user in the environment ($VISUAL, then $EDITOR, or vi), when running in a
+// terminal
+#[cfg(not(test))]
+pub fn terminal_editor() -> Option<String> {
+    use std::io::IsTerminal;
+
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty());
+
+    Some(editor.unwrap_or_else(|| "vi".to_string()))
+}
+
+// Alternative function for testing (there is never a terminal)
+#[cfg(test)]
+pub fn terminal_editor() -> Option<String> {
+    None
+}
+
+// Task as read back from the editor
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Document {
+    title: String,
+    #[serde(default)]
+    description: String,
+    status: String,
+    priority: String,
+    project: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    due: String,
+    #[serde(default)]
+    repeat: String,
+    #[serde(default)]
+    depends_on: Vec<usize>,
+}
+
+// Quote a text as a TOML string (descriptions with several lines as a multi-line string)
+fn quote(text: &str) -> String {
+    if text.contains('\n') && !text.contains("'''") {
+        return format!("'''\n{}'''", text);
+    }
+
+    toml::Value::String(text.to_string()).to_string()
+}
+
+// Key of a status in the document
+fn status_key(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "pending",
+        TaskStatus::InProgress => "in-progress",
+        TaskStatus::Completed => "completed",
+    }
+}
+
+// Key of a priority in the document
+fn priority_key(priority: TaskPriority) -> &'static str {
+    match priority {
+        TaskPriority::Low => "low",
+        TaskPriority::Medium => "medium",
+        TaskPriority::High => "high",
+        TaskPriority::Urgent => "urgent",
+    }
+}
+
+// Document with the editable fields of a task, as shown in the editor
+pub fn task_document(task: &Task) -> String {
+    let tags: Vec<String> = task.tags.iter().map(|tag| quote(tag)).collect();
+    let depends_on: Vec<String> = task.depends_on.iter().map(|id| id.to_string()).collect();
+    let due = task.due_at.map(|due| due.to_rfc3339_opts(SecondsFormat::Secs, false)).unwrap_or_default();
+    let repeat = task.recurrence.as_ref().map(Recurrence::to_string).unwrap_or_default();
+
+    let mut document = String::new();
+    document.push_str(&format!("# Tarea {}. Guarde y cierre el editor para aplicar los cambios; si lo cierra sin\n", task.id));
+    document.push_str("# cambios, la tarea queda como está. Las líneas que empiezan con # se ignoran.\n\n");
+    document.push_str(&format!("title = {}\n", quote(&task.title)));
+    document.push_str(&format!("description = {}\n", quote(&task.description)));
+    document.push_str(&format!("status = \"{}\"  # pending, in-progress o completed\n", status_key(task.status)));
+    document.push_str(&format!("priority = \"{}\"  # low, medium, high o urgent\n", priority_key(task.priority)));
+    document.push_str(&format!("project = {}  # p. ej. trabajo.backend\n", quote(&task.project)));
+    document.push_str(&format!("tags = [{}]\n", tags.join(", ")));
+    document.push_str(&format!("due = {}  # p. ej. mañana 17:00 o 2025-11-02; \"\" sin fecha\n", quote(&due)));
+    document.push_str(&format!("repeat = {}  # daily, weekly:mon,fri, monthly:15, every:3; \"\" no se repite\n", quote(&repeat)));
+    document.push_str(&format!("depends_on = [{}]  # IDs de las tareas que deben completarse antes\n", depends_on.join(", ")));
+    document
+}
+
+// Read the fields and the status of a task from an edited document
+pub fn parse_document(text: &str, now: DateTime<FixedOffset>) -> Result<(TaskFields, TaskStatus), String> {
+    let document: Document = toml::from_str(text).map_err(|e| e.to_string().trim_end().to_string())?;
+
+    let status = match normalize(document.status.trim()).as_str() {
+        "pending" | "pendiente" => TaskStatus::Pending,
+        "in-progress" | "in_progress" | "en-progreso" => TaskStatus::InProgress,
+        "completed" | "done" | "completada" => TaskStatus::Completed,
+        _ => return Err(format!("Estado inválido: \"{}\" (use pending, in-progress o completed)", document.status)),
+    };
+
+    let priority = match normalize(document.priority.trim()).as_str() {
+        "low" | "baja" => TaskPriority::Low,
+        "medium" | "media" => TaskPriority::Medium,
+        "high" | "alta" => TaskPriority::High,
+        "urgent" | "urgente" => TaskPriority::Urgent,
+        _ => return Err(format!("Prioridad inválida: \"{}\" (use low, medium, high o urgent)", document.priority)),
+    };
+
+    let due_at = match document.due.trim() {
+        "" => None,
+        due => Some(parse_date(due, now).map_err(|e| e.to_string())?),
+    };
+
+    let recurrence = match document.repeat.trim() {
+        "" => None,
+        repeat => Some(repeat.parse::<Recurrence>().map_err(|e| e.to_string())?),
+    };
+
+    let fields = TaskFields {
+        title: document.title.trim().to_string(),
+        // The closing quotes of a multi-line description usually go on their own line
+        description: document.description.trim_end().to_string(),
+        priority,
+        due_at,
+        tags: document.tags.into_iter().collect(),
+        project: document.project,
+        depends_on: document.depends_on.into_iter().collect(),
+        recurrence,
+    };
+
+    Ok((fields, status))
+}
+
+// Apply an edited document to a copy of the tasks (the tasks are not changed if it is not valid)
+fn apply_document(tasks: &Tasks, id: usize, text: &str) -> Result<Tasks, String> {
+    let (fields, status) = parse_document(text, now_fixed())?;
+    let mut edited = tasks.clone();
+
+    edited.replace_task_fields(id, fields).map_err(|e| e.to_string())?;
+
+    if edited.get_task(id).is_some_and(|task| task.status != status) {
+        edited.change_task_status(id, status).map_err(|e| e.to_string())?;
+    }
+
+    Ok(edited)
+}
+
+// The document with the error on top (replacing the previous one)
+fn with_error(text: &str, error: &str) -> String {
+    let mut lines: Vec<&str> = text.lines().collect();
+
+    while lines.first().is_some_and(|line| line.starts_with(ERROR_MARK) || line.starts_with("#   ")) {
+        lines.remove(0);
+    }
+
+    let mut document = String::new();
+
+    for (i, line) in error.lines().enumerate() {
+        document.push_str(if i == 0 { ERROR_MARK } else { "#  " });
+        document.push_str(&format!(" {}\n", line));
+    }
+
+    document.push_str(&lines.join("\n"));
+    document.push('\n');
+    document
+}
+
+// Run the editor on a file and wait for it to close (the command may include arguments)
+fn open_editor(editor: &str, path: &Path) -> Result<(), EditorError> {
+    let status = process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(path)
+        .status()
+        .map_err(EditorError::Io)?;
+
+    if !status.success() {
+        return Err(EditorError::Failed(editor.to_string()));
+    }
+
+    Ok(())
+}
+
+// Open the document until it is valid or closed without changes (returns the edited tasks, or
+// None when the task was left as it was)
+fn edit_loop(tasks: &Tasks, id: usize, editor: &str, path: &Path) -> Result<Option<Tasks>, EditorError> {
+    let task = tasks.get_task(id).ok_or(EditorError::Task(TaskError::TaskNotFound))?;
+    let mut text = task_document(task);
+
+    loop {
+        fs::write(path, &text).map_err(EditorError::Io)?;
+        open_editor(editor, path)?;
+        let edited = fs::read_to_string(path).map_err(EditorError::Io)?;
+
+        if edited == text {
+            return Ok(None);
+        }
+
+        match apply_document(tasks, id, &edited) {
+            Ok(result) if result == *tasks => return Ok(None),
+            Ok(result) => return Ok(Some(result)),
+            Err(e) => {
+                eprintln!("Error en la tarea editada: {}", e);
+                text = with_error(&edited, &e);
+            }
+        }
+    }
+}
+
+// Create a new directory in the temporary directory that only the user can open, for the document
+// being edited (nobody else can create the file before it is written, e.g. as a link to another
+// file)
+fn private_dir() -> io::Result<PathBuf> {
+    let mut builder = fs::DirBuilder::new();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+
+    let mut attempt = 0;
+
+    loop {
+        let dir = std::env::temp_dir().join(format!("tarea-{}-{}", process::id(), attempt));
+
+        match builder.create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Edit a task in the editor. Returns whether the task changed
+pub fn edit_task(tasks: &mut Tasks, id: usize, editor: &str) -> Result<bool, EditorError> {
+    tasks.validate_task_id(id).map_err(EditorError::Task)?;
+
+    let dir = private_dir().map_err(EditorError::Io)?;
+    let result = edit_loop(tasks, id, editor, &dir.join(format!("tarea-{}.toml", id)));
+    let _ = fs::remove_dir_all(&dir);
+
+    match result? {
+        Some(edited) => {
+            *tasks = edited;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+// Test configuration
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,107 @@
+use super::{edit_task, parse_document, task_document, EditorError};
+use crate::dates::reference_now;
+use crate::tasks::{Recurrence, TaskFields, TaskOptions, TaskPriority, TaskStatus, Tasks};
+use chrono::DateTime;
+
+// A task using every editable field, and the task it depends on
+fn sample_tasks() -> Tasks {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Preparar \"informe\"".into(), "".into(), TaskOptions::default()).unwrap();
+
+    let options = TaskOptions {
+        priority: TaskPriority::High,
+        due_at: Some(DateTime::parse_from_rfc3339("2025-11-06T17:00:00-04:00").unwrap()),
+        tags: vec!["trabajo".into(), "mensual".into()],
+        project: Some("trabajo.informes".into()),
+        depends_on: vec![1],
+        recurrence: Some(Recurrence::Monthly(15)),
+        ..Default::default()
+    };
+    tasks.add_task_with("Enviar informe".into(), "Primera línea\nSegunda línea".into(), options).unwrap();
+    tasks
+}
+
+#[test]
+// Test: the document of a task reads back as the same fields
+fn test_task_document_round_trip() {
+    let tasks = sample_tasks();
+
+    for task in tasks.iter() {
+        let (fields, status) = parse_document(&task_document(task), reference_now()).unwrap();
+        assert_eq!(fields, TaskFields::from(task));
+        assert_eq!(status, task.status);
+    }
+}
+
+#[test]
+// Test: values in the document are validated
+fn test_parse_document_errors() {
+    let tasks = sample_tasks();
+    let document = task_document(&tasks[1]);
+    let error = |from: &str, to: &str| parse_document(&document.replacen(from, to, 1), reference_now()).unwrap_err();
+
+    assert!(error("priority = \"high\"", "priority = \"altísima\"").starts_with("Prioridad inválida"));
+    assert!(error("status = \"pending\"", "status = \"done?\"").starts_with("Estado inválido"));
+    assert!(error("repeat = \"monthly:15\"", "repeat = \"hourly\"").contains("Regla de repetición inválida"));
+    assert!(error("due = \"2025-11-06T17:00:00-04:00\"", "due = \"someday\"").contains("Fecha no reconocida"));
+    assert!(error("depends_on = [1]", "depends_on = [1]\ncolor = \"red\"").contains("color"));
+    assert!(error("title = ", "titulo = ").contains("titulo"));
+
+    // Natural dates, Spanish names and empty values are accepted
+    let edited = document
+        .replacen("due = \"2025-11-06T17:00:00-04:00\"", "due = \"mañana 17:00\"", 1)
+        .replacen("priority = \"high\"", "priority = \"baja\"", 1)
+        .replacen("repeat = \"monthly:15\"", "repeat = \"\"", 1);
+    let (fields, _) = parse_document(&edited, reference_now()).unwrap();
+    assert_eq!(fields.due_at, Some(DateTime::parse_from_rfc3339("2025-11-06T17:00:00-04:00").unwrap()));
+    assert_eq!(fields.priority, TaskPriority::Low);
+    assert_eq!(fields.recurrence, None);
+}
+
+#[test]
+// Test: edit a task with an editor command (closing without changes leaves the task as it was)
+fn test_edit_task() {
+    let mut tasks = sample_tasks();
+    let before = tasks.clone();
+
+    assert!(!edit_task(&mut tasks, 2, "true").unwrap());
+    assert_eq!(tasks, before);
+    assert!(matches!(edit_task(&mut tasks, 2, "false"), Err(EditorError::Failed(_))));
+    assert!(matches!(edit_task(&mut tasks, 9, "true"), Err(EditorError::Task(_))));
+    assert_eq!(tasks, before);
+
+    // The description can be cleared and the status changed (completing it repeats the task)
+    tasks.change_task_status(1, TaskStatus::Completed).unwrap();
+    let editor = r#"sed -i -e 's/^title = .*/title = "Enviar"/' -e '/^description/,/'"'''"'$/d' -e 's/^status = .*/status = "completed"/'"#;
+    assert!(edit_task(&mut tasks, 2, editor).unwrap());
+    assert_eq!(tasks[1].title, "Enviar");
+    assert_eq!(tasks[1].description, "");
+    assert_eq!(tasks[1].status, TaskStatus::Completed);
+    assert_eq!(tasks[1].tags, before[1].tags);
+    assert_eq!(tasks.len(), 3);
+}
+
+#[test]
+// Test: an invalid document is opened again with the error on top
+fn test_edit_task_reopens_on_error() {
+    let mut tasks = sample_tasks();
+
+    // The first time the priority is invalid; once the error is shown, it is fixed
+    let editor = r#"f() { if grep -q '^# Error' "$1"; then sed -i 's/^priority = .*/priority = "urgent"/' "$1"; else sed -i 's/^priority = .*/priority = "x"/' "$1"; fi; }; f"#;
+    assert!(edit_task(&mut tasks, 2, editor).unwrap());
+    assert_eq!(tasks[1].priority, TaskPriority::Urgent);
+
+    // Closing the re-opened document without changes gives up
+    let before = tasks.clone();
+    let editor = r#"f() { grep -q '^# Error' "$1" || sed -i 's/^title = .*/title = ""/' "$1"; }; f"#;
+    assert!(!edit_task(&mut tasks, 2, editor).unwrap());
+    assert_eq!(tasks, before);
+}
+
+#[test]
+// Test: the document is written in a new directory only the user can open
+fn test_edit_task_private_dir() {
+    let mut tasks = sample_tasks();
+    let editor = r#"f() { [ "$(stat -c %a "$(dirname "$1")")" = 700 ]; }; f"#;
+    assert!(!edit_task(&mut tasks, 2, editor).unwrap());
+}
@@ -0,0 +1,135 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::dates::now_fixed;
+use crate::tasks::{Task, Tasks};
+
+// Number of commands that can be undone
+pub const JOURNAL_LIMIT: usize = 20;
+
+// Errors shown to the user when undoing or redoing a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JournalError {
+    Conflict(usize),
+}
+
+// Implement fmt::Display for JournalError
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalError::Conflict(id) => write!(f, "La tarea {} cambió después de ese comando", id),
+        }
+    }
+}
+
+// A task before and after a command (None when it did not exist)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskChange {
+    pub id: usize,
+    pub before: Option<Task>,
+    pub after: Option<Task>,
+}
+
+// A mutating command with the changes it made, so it can be reverted and applied again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub command: String,
+    pub at: DateTime<FixedOffset>,
+    pub changes: Vec<TaskChange>,
+}
+
+// Undo/redo journal: the last commands that can be undone, and the undone commands that can be
+// applied again (until a new command is run)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Journal {
+    undo: Vec<JournalEntry>,
+    redo: Vec<JournalEntry>,
+}
+
+// Apply the changes to the tasks (backwards when reverting). Every task must still be in the
+// version the changes start from
+fn apply(tasks: &mut Tasks, changes: &[TaskChange], reverse: bool) -> Result<(), JournalError> {
+    let versions = |change: &TaskChange| if reverse { (change.after.clone(), change.before.clone()) } else { (change.before.clone(), change.after.clone()) };
+
+    // Check every task first, so a conflict leaves the tasks untouched
+    for change in changes {
+        if tasks.get_task(change.id) != versions(change).0.as_ref() {
+            return Err(JournalError::Conflict(change.id));
+        }
+    }
+
+    for change in changes {
+        match versions(change).1 {
+            Some(task) => tasks.put_task(task),
+            None => {
+                tasks.remove_task(change.id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Journal implementation
+impl Journal {
+    // Record the changes made by a command (the oldest entry is dropped beyond JOURNAL_LIMIT)
+    pub fn record(&mut self, command: String, before: &Tasks, after: &Tasks) {
+        let mut changes: Vec<TaskChange> = after
+            .iter()
+            .filter(|task| before.get_task(task.id) != Some(task))
+            .map(|task| TaskChange { id: task.id, before: before.get_task(task.id).cloned(), after: Some(task.clone()) })
+            .collect();
+
+        changes.extend(
+            before
+                .iter()
+                .filter(|task| after.get_task(task.id).is_none())
+                .map(|task| TaskChange { id: task.id, before: Some(task.clone()), after: None }),
+        );
+
+        if changes.is_empty() {
+            return;
+        }
+
+        self.undo.push(JournalEntry { command, at: now_fixed(), changes });
+        self.redo.clear();
+
+        if self.undo.len() > JOURNAL_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    // Revert the last command (None when there is nothing to undo)
+    pub fn undo(&mut self, tasks: &mut Tasks) -> Result<Option<JournalEntry>, JournalError> {
+        let Some(entry) = self.undo.last() else {
+            return Ok(None);
+        };
+
+        apply(tasks, &entry.changes, true)?;
+
+        let entry = self.undo.pop().expect("entry was just read");
+        self.redo.push(entry.clone());
+
+        Ok(Some(entry))
+    }
+
+    // Apply again the last undone command (None when there is nothing to redo)
+    pub fn redo(&mut self, tasks: &mut Tasks) -> Result<Option<JournalEntry>, JournalError> {
+        let Some(entry) = self.redo.last() else {
+            return Ok(None);
+        };
+
+        apply(tasks, &entry.changes, false)?;
+
+        let entry = self.redo.pop().expect("entry was just read");
+        self.undo.push(entry.clone());
+
+        Ok(Some(entry))
+    }
+}
+
+// Test configuration
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,92 @@
+use super::{Journal, JournalError, JOURNAL_LIMIT};
+use crate::tasks::{TaskOptions, TaskStatus, Tasks};
+
+// Run a change on the tasks and record it in the journal
+fn record(journal: &mut Journal, tasks: &mut Tasks, command: &str, f: impl FnOnce(&mut Tasks)) {
+    let before = tasks.clone();
+    f(tasks);
+    journal.record(command.to_string(), &before, tasks);
+}
+
+#[test]
+// Test: undo and redo a sequence of commands
+fn test_undo_redo() {
+    let mut journal = Journal::default();
+    let mut tasks = Tasks::default();
+    record(&mut journal, &mut tasks, "add", |tasks| {
+        tasks.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+    });
+    let added = tasks.clone();
+    record(&mut journal, &mut tasks, "status", |tasks| {
+        tasks.change_task_status(1, TaskStatus::Completed).unwrap();
+    });
+    let completed = tasks.clone();
+
+    let entry = journal.undo(&mut tasks).unwrap().unwrap();
+    assert_eq!(entry.command, "status");
+    assert_eq!(tasks, added);
+    assert_eq!(journal.undo(&mut tasks).unwrap().unwrap().command, "add");
+    assert!(tasks.is_empty());
+    assert_eq!(journal.undo(&mut tasks).unwrap(), None);
+
+    assert_eq!(journal.redo(&mut tasks).unwrap().unwrap().command, "add");
+    assert_eq!(journal.redo(&mut tasks).unwrap().unwrap().command, "status");
+    assert_eq!(tasks, completed);
+    assert_eq!(journal.redo(&mut tasks).unwrap(), None);
+}
+
+#[test]
+// Test: commands without changes are not recorded, new commands discard the redo history
+fn test_record() {
+    let mut journal = Journal::default();
+    let mut tasks = Tasks::default();
+    record(&mut journal, &mut tasks, "list", |_| {});
+    assert_eq!(journal.undo(&mut tasks).unwrap(), None);
+
+    record(&mut journal, &mut tasks, "add", |tasks| {
+        tasks.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+    });
+    journal.undo(&mut tasks).unwrap();
+    record(&mut journal, &mut tasks, "add", |tasks| {
+        tasks.add_task_with("Second".into(), "".into(), TaskOptions::default()).unwrap();
+    });
+    assert_eq!(journal.redo(&mut tasks).unwrap(), None);
+    assert_eq!(tasks[0].id, 2);
+}
+
+#[test]
+// Test: only the last JOURNAL_LIMIT commands can be undone
+fn test_journal_limit() {
+    let mut journal = Journal::default();
+    let mut tasks = Tasks::default();
+
+    for n in 0..JOURNAL_LIMIT + 5 {
+        record(&mut journal, &mut tasks, &n.to_string(), |tasks| {
+            tasks.add_task_with(format!("Task {}", n), "".into(), TaskOptions::default()).unwrap();
+        });
+    }
+
+    let mut undone = 0;
+    while journal.undo(&mut tasks).unwrap().is_some() {
+        undone += 1;
+    }
+
+    assert_eq!(undone, JOURNAL_LIMIT);
+    assert_eq!(tasks.len(), 5);
+}
+
+#[test]
+// Test: a task changed outside the journal cannot be reverted
+fn test_undo_conflict() {
+    let mut journal = Journal::default();
+    let mut tasks = Tasks::default();
+    record(&mut journal, &mut tasks, "add", |tasks| {
+        tasks.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+        tasks.add_task_with("Second".into(), "".into(), TaskOptions::default()).unwrap();
+    });
+    tasks.update_task(2, "Renamed".into(), "".into()).unwrap();
+    let changed = tasks.clone();
+
+    assert_eq!(journal.undo(&mut tasks), Err(JournalError::Conflict(2)));
+    assert_eq!(tasks, changed);
+}
@@ -1,38 +1,49 @@
 mod command;
+mod config;
+mod dates;
+mod editor;
+mod journal;
+mod query;
+mod render;
+mod search;
 mod storage;
 mod tasks;
+mod tui;
 
 use crate::command::Commands;
-use crate::storage::Storage;
-use crate::tasks::Tasks;
+use crate::config::{Config, CONFIG_FILE};
+use crate::storage::open_store;
 use std::io::{self, Write};
+use std::process;
 
 fn main() {
-    // Initialize tasks list
-    let mut tasks_list: Tasks = Tasks::default();
-
-    // Initialize storage using JSON file
-    let storage: Storage<Tasks> = Storage::new("tasks.json".to_string());
+    // Parse commands
+    let commands = Commands::parse_from_env();
 
-    // Load existing tasks
-    match storage.download_data() {
-        Ok(Some(data)) => tasks_list = data,
-        Ok(None) => {}
+    // Read configuration (saved queries are passed to the commands)
+    let config = match Config::load(CONFIG_FILE) {
+        Ok(config) => config,
         Err(e) => {
-            let _ = writeln!(io::stderr(), "Error al cargar las tareas: {}", e);
+            let _ = writeln!(io::stderr(), "Error al leer la configuración: {}", e);
+            process::exit(1);
         }
-    }
+    };
+    let commands = commands.with_config(CONFIG_FILE, &config);
 
-    // Parse commands
-    let commands = Commands::parse_from_env();
+    // Initialize storage (--store overrides the configured backend)
+    let kind = commands.store().unwrap_or(config.storage.backend);
+    let file_name = config.storage.file.as_deref().unwrap_or(kind.default_file());
+    let mut store = match open_store(kind, file_name, commands.lock_timeout()) {
+        Ok(store) => store,
+        Err(e) => {
+            let _ = writeln!(io::stderr(), "Error al abrir el almacenamiento: {}", e);
+            process::exit(1);
+        }
+    };
 
-    // Execute commands
-    if let Err(e) = commands.execute(&mut tasks_list) {
+    // Execute commands (tasks are loaded from and saved to the store)
+    if let Err(e) = commands.execute(store.as_mut()) {
         let _ = writeln!(io::stderr(), "Error al ejecutar los comandos: {}", e);
-    }
-
-    // Save tasks to storage
-    if let Err(e) = storage.upload_data(&tasks_list) {
-        let _ = writeln!(io::stderr(), "Error al guardar las tareas: {}", e);
+        process::exit(1);
     }
 }
@@ -0,0 +1,552 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::dates::{normalize, parse_date};
+use crate::tasks::{Task, TaskPriority, TaskStatus, Tasks};
+
+// Errors shown to the user when a query cannot be parsed (position is the 1-based character
+// where the problem was found)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    pub position: usize,
+    pub query: String,
+}
+
+// Implement fmt::Display for QueryError (points at the position in the query)
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (posición {})\n  {}\n  {}^", self.message, self.position, self.query, " ".repeat(self.position.saturating_sub(1)))
+    }
+}
+
+// Kind of value held by a field, which decides the operators and values it accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Text,
+    Project,
+    Tags,
+    Status,
+    Priority,
+    Date,
+    Flag,
+    Ids,
+}
+
+// Task fields that can be queried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldId {
+    Id,
+    Title,
+    Description,
+    Project,
+    Tag,
+    Status,
+    Priority,
+    Due,
+    Created,
+    Updated,
+    Completed,
+    Parent,
+    DependsOn,
+    Blocked,
+    Overdue,
+    Recurring,
+}
+
+// Field names (and aliases) accepted in queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Field {
+    id: FieldId,
+    names: &'static [&'static str],
+    kind: Kind,
+}
+
+// Queryable fields. Adding a field takes an entry here and an arm in `value`
+const FIELDS: [Field; 16] = [
+    Field { id: FieldId::Id, names: &["id"], kind: Kind::Number },
+    Field { id: FieldId::Title, names: &["title", "titulo"], kind: Kind::Text },
+    Field { id: FieldId::Description, names: &["description", "desc", "descripcion"], kind: Kind::Text },
+    Field { id: FieldId::Project, names: &["project", "proyecto"], kind: Kind::Project },
+    Field { id: FieldId::Tag, names: &["tag", "tags", "etiqueta"], kind: Kind::Tags },
+    Field { id: FieldId::Status, names: &["status", "estado"], kind: Kind::Status },
+    Field { id: FieldId::Priority, names: &["priority", "prioridad"], kind: Kind::Priority },
+    Field { id: FieldId::Due, names: &["due", "vence"], kind: Kind::Date },
+    Field { id: FieldId::Created, names: &["created", "creada"], kind: Kind::Date },
+    Field { id: FieldId::Updated, names: &["updated", "actualizada"], kind: Kind::Date },
+    Field { id: FieldId::Completed, names: &["completed", "completada"], kind: Kind::Date },
+    Field { id: FieldId::Parent, names: &["parent", "padre"], kind: Kind::Number },
+    Field { id: FieldId::DependsOn, names: &["depends", "depende"], kind: Kind::Ids },
+    Field { id: FieldId::Blocked, names: &["blocked", "bloqueada"], kind: Kind::Flag },
+    Field { id: FieldId::Overdue, names: &["overdue", "vencida"], kind: Kind::Flag },
+    Field { id: FieldId::Recurring, names: &["recurring", "repite"], kind: Kind::Flag },
+];
+
+// Comparison operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    // ":" (for projects, also matches subprojects)
+    Is,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    // "~": contains, ignoring case and accents
+    Contains,
+}
+
+// Op implementation
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Is => ":",
+            Op::Eq => "=",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Contains => "~",
+        }
+    }
+
+    // Whether the operator can be used with a kind of field
+    fn accepts(self, kind: Kind) -> bool {
+        match self {
+            Op::Is | Op::Eq | Op::Ne => true,
+            Op::Lt | Op::Le | Op::Gt | Op::Ge => matches!(kind, Kind::Number | Kind::Priority | Kind::Date),
+            Op::Contains => matches!(kind, Kind::Text | Kind::Project | Kind::Tags),
+        }
+    }
+
+    // Result of an ordered comparison
+    fn compare<T: PartialOrd>(self, left: T, right: T) -> bool {
+        match self {
+            Op::Is | Op::Eq => left == right,
+            Op::Ne => left != right,
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+            Op::Contains => false,
+        }
+    }
+}
+
+// Values compared against fields (texts are normalized, dates compare by day)
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    None,
+    Number(usize),
+    Text(String),
+    Status(TaskStatus),
+    Priority(TaskPriority),
+    Date(NaiveDate),
+    Flag(bool),
+}
+
+// Expression tree of a query
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare { field: Field, op: Op, value: Value },
+}
+
+// Parsed query, ready to be matched against tasks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query(Expr);
+
+// Tokens of the query language
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+    Not,
+    Open,
+    Close,
+    Saved(String),
+}
+
+// Token along with its 1-based position
+type Spanned = (Token, usize);
+
+// Characters that end an unquoted word
+fn is_special(c: char) -> bool {
+    c.is_whitespace() || "()\":=!<>~@".contains(c)
+}
+
+// Split a query into tokens
+fn tokenize(query: &str) -> Result<Vec<Spanned>, (String, usize)> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let position = i + 1;
+        let next = chars.get(i + 1).copied();
+
+        let (token, length) = match c {
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => (Token::Open, 1),
+            ')' => (Token::Close, 1),
+            ':' => (Token::Op(Op::Is), 1),
+            '=' => (Token::Op(Op::Eq), 1),
+            '~' => (Token::Op(Op::Contains), 1),
+            '!' if next == Some('=') => (Token::Op(Op::Ne), 2),
+            '!' => (Token::Not, 1),
+            '<' if next == Some('=') => (Token::Op(Op::Le), 2),
+            '<' => (Token::Op(Op::Lt), 1),
+            '>' if next == Some('=') => (Token::Op(Op::Ge), 2),
+            '>' => (Token::Op(Op::Gt), 1),
+            '"' => {
+                let Some(end) = chars[i + 1..].iter().position(|&c| c == '"') else {
+                    return Err(("Comillas sin cerrar".to_string(), position));
+                };
+
+                (Token::Quoted(chars[i + 1..i + 1 + end].iter().collect()), end + 2)
+            }
+            '@' => {
+                let length = chars[i + 1..].iter().take_while(|&&c| !is_special(c)).count();
+
+                if length == 0 {
+                    return Err(("Se esperaba el nombre de una consulta guardada después de \"@\"".to_string(), position));
+                }
+
+                (Token::Saved(chars[i + 1..i + 1 + length].iter().collect()), length + 1)
+            }
+            _ => {
+                let length = chars[i..].iter().take_while(|&&c| !is_special(c)).count();
+                (Token::Word(chars[i..i + length].iter().collect()), length)
+            }
+        };
+
+        tokens.push((token, position));
+        i += length;
+    }
+
+    Ok(tokens)
+}
+
+// Recursive descent parser:
+//   or         := and ("or" and)*
+//   and        := not (["and"] not)*
+//   not        := ("not" | "!") not | primary
+//   primary    := "(" or ")" | "@" name | field operator value
+struct Parser<'a> {
+    tokens: Vec<Spanned>,
+    position: usize,
+    end: usize,
+    saved: &'a BTreeMap<String, String>,
+    // Saved queries being expanded (to reject recursive definitions)
+    expanding: Vec<String>,
+    now: DateTime<FixedOffset>,
+}
+
+// Check whether a token is a keyword
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    matches!(token, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+}
+
+// Parser implementation
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    // Position of the current token (or the end of the query)
+    fn here(&self) -> usize {
+        self.tokens.get(self.position).map_or(self.end, |&(_, position)| position)
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, (String, usize)> {
+        Err((message, self.here()))
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, (String, usize)> {
+        let mut query = self.parse_and()?;
+
+        while self.peek().is_some_and(|token| is_keyword(token, "or")) {
+            self.position += 1;
+            query = Expr::Or(Box::new(query), Box::new(self.parse_and()?));
+        }
+
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, (String, usize)> {
+        let mut query = self.parse_not()?;
+
+        loop {
+            match self.peek() {
+                Some(token) if is_keyword(token, "and") => self.position += 1,
+                // Conditions written one after the other must all match
+                Some(token) if !is_keyword(token, "or") && *token != Token::Close => {}
+                _ => return Ok(query),
+            }
+
+            query = Expr::And(Box::new(query), Box::new(self.parse_not()?));
+        }
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, (String, usize)> {
+        match self.peek() {
+            Some(token) if *token == Token::Not || is_keyword(token, "not") => {
+                self.position += 1;
+                Ok(Expr::Not(Box::new(self.parse_not()?)))
+            }
+            _ => self.parse_primary(),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, (String, usize)> {
+        let start = self.here();
+
+        match self.peek().cloned() {
+            Some(Token::Open) => {
+                self.position += 1;
+                let query = self.parse_or()?;
+
+                if self.peek() != Some(&Token::Close) {
+                    return Err(("Falta cerrar el paréntesis abierto aquí".to_string(), start));
+                }
+
+                self.position += 1;
+                Ok(query)
+            }
+            Some(Token::Saved(name)) => {
+                self.position += 1;
+                self.expand(&name, start)
+            }
+            Some(Token::Word(name)) if !name.eq_ignore_ascii_case("and") && !name.eq_ignore_ascii_case("or") => {
+                self.position += 1;
+                self.parse_comparison(&name, start)
+            }
+            Some(Token::Close) => self.error("Paréntesis de cierre sin abrir".to_string()),
+            _ => self.error("Se esperaba una condición (p. ej. status:pending)".to_string()),
+        }
+    }
+
+    // Parse a saved query in place of its name
+    fn expand(&mut self, name: &str, start: usize) -> Result<Expr, (String, usize)> {
+        let Some(text) = self.saved.get(name) else {
+            return Err((format!("Consulta guardada desconocida: @{}", name), start));
+        };
+
+        if self.expanding.iter().any(|expanding| expanding == name) {
+            return Err((format!("La consulta guardada @{} se usa a sí misma", name), start));
+        }
+
+        let mut expanding = self.expanding.clone();
+        expanding.push(name.to_string());
+
+        parse_tokens(text, self.saved, expanding, self.now)
+            .map_err(|e| (format!("Error en la consulta guardada @{}: {} (posición {})", name, e.0, e.1), start))
+    }
+
+    fn parse_comparison(&mut self, name: &str, start: usize) -> Result<Expr, (String, usize)> {
+        let normalized = normalize(name);
+        let Some(&field) = FIELDS.iter().find(|field| field.names.contains(&normalized.as_str())) else {
+            let names: Vec<&str> = FIELDS.iter().map(|field| field.names[0]).collect();
+            return Err((format!("Campo desconocido \"{}\" (campos: {})", name, names.join(", ")), start));
+        };
+
+        let Some(Token::Op(op)) = self.peek().cloned() else {
+            return self.error(format!("Se esperaba un operador (:, =, !=, <, <=, >, >=, ~) después de \"{}\"", name));
+        };
+
+        if !op.accepts(field.kind) {
+            return self.error(format!("El operador \"{}\" no se puede usar con {}", op.symbol(), field.names[0]));
+        }
+
+        self.position += 1;
+        let value_position = self.here();
+
+        let text = match self.peek().cloned() {
+            Some(Token::Word(text) | Token::Quoted(text)) => text,
+            _ => return self.error(format!("Se esperaba un valor después de \"{}{}\"", name, op.symbol())),
+        };
+
+        self.position += 1;
+
+        let value = parse_value(field, op, &text, self.now).map_err(|message| (message, value_position))?;
+
+        Ok(Expr::Compare { field, op, value })
+    }
+}
+
+// Interpret the value of a comparison according to the field
+fn parse_value(field: Field, op: Op, text: &str, now: DateTime<FixedOffset>) -> Result<Value, String> {
+    let normalized = normalize(text.trim());
+    let optional = matches!(field.id, FieldId::Due | FieldId::Completed | FieldId::Parent | FieldId::DependsOn);
+
+    if optional && matches!(normalized.as_str(), "none" | "ninguno" | "ninguna") {
+        if !matches!(op, Op::Is | Op::Eq | Op::Ne) {
+            return Err(format!("\"{}\" solo se puede comparar con :, = o !=", text));
+        }
+
+        return Ok(Value::None);
+    }
+
+    let invalid = |expected: &str| format!("Valor inválido para {}: \"{}\" ({})", field.names[0], text, expected);
+
+    match field.kind {
+        Kind::Number | Kind::Ids => normalized.parse().map(Value::Number).map_err(|_| invalid("se esperaba un número")),
+        // Projects are stored in lowercase (and may contain accents)
+        Kind::Project => Ok(Value::Text(text.trim().to_lowercase())),
+        Kind::Text | Kind::Tags => Ok(Value::Text(normalized)),
+        Kind::Status => match normalized.as_str() {
+            "pending" | "pendiente" => Ok(Value::Status(TaskStatus::Pending)),
+            "in-progress" | "in_progress" | "en-progreso" => Ok(Value::Status(TaskStatus::InProgress)),
+            "completed" | "done" | "completada" => Ok(Value::Status(TaskStatus::Completed)),
+            _ => Err(invalid("use pending, in-progress o completed")),
+        },
+        Kind::Priority => match normalized.as_str() {
+            "low" | "baja" => Ok(Value::Priority(TaskPriority::Low)),
+            "medium" | "media" => Ok(Value::Priority(TaskPriority::Medium)),
+            "high" | "alta" => Ok(Value::Priority(TaskPriority::High)),
+            "urgent" | "urgente" => Ok(Value::Priority(TaskPriority::Urgent)),
+            _ => Err(invalid("use low, medium, high o urgent")),
+        },
+        Kind::Date => parse_date(text, now).map(|date| Value::Date(date.date_naive())).map_err(|e| invalid(&e.to_string())),
+        Kind::Flag => match normalized.as_str() {
+            "true" | "yes" | "si" => Ok(Value::Flag(true)),
+            "false" | "no" => Ok(Value::Flag(false)),
+            _ => Err(invalid("use true o false")),
+        },
+    }
+}
+
+// Tokenize and parse a whole query
+fn parse_tokens(query: &str, saved: &BTreeMap<String, String>, expanding: Vec<String>, now: DateTime<FixedOffset>) -> Result<Expr, (String, usize)> {
+    let tokens = tokenize(query)?;
+    let end = query.chars().count() + 1;
+    let mut parser = Parser { tokens, position: 0, end, saved, expanding, now };
+    let parsed = parser.parse_or()?;
+
+    match parser.peek() {
+        None => Ok(parsed),
+        Some(Token::Close) => parser.error("Paréntesis de cierre sin abrir".to_string()),
+        Some(_) => parser.error("Se esperaba \"and\" u \"or\"".to_string()),
+    }
+}
+
+// Value of a field of a task, as compared by queries
+enum FieldValue<'a> {
+    Number(Option<usize>),
+    Text(&'a str),
+    Tags(Vec<&'a str>),
+    Status(TaskStatus),
+    Priority(TaskPriority),
+    Date(Option<DateTime<FixedOffset>>),
+    Flag(bool),
+    Ids(Vec<usize>),
+}
+
+// Read a field of a task
+fn value<'a>(id: FieldId, tasks: &Tasks, task: &'a Task, now: DateTime<FixedOffset>) -> FieldValue<'a> {
+    match id {
+        FieldId::Id => FieldValue::Number(Some(task.id)),
+        FieldId::Title => FieldValue::Text(&task.title),
+        FieldId::Description => FieldValue::Text(&task.description),
+        FieldId::Project => FieldValue::Text(&task.project),
+        FieldId::Tag => FieldValue::Tags(task.tags.iter().map(String::as_str).collect()),
+        FieldId::Status => FieldValue::Status(task.status),
+        FieldId::Priority => FieldValue::Priority(task.priority),
+        FieldId::Due => FieldValue::Date(task.due_at),
+        FieldId::Created => FieldValue::Date(Some(task.created_at)),
+        FieldId::Updated => FieldValue::Date(Some(task.updated_at)),
+        FieldId::Completed => FieldValue::Date((task.status == TaskStatus::Completed).then_some(task.completed_at)),
+        FieldId::Parent => FieldValue::Number(task.parent),
+        FieldId::DependsOn => FieldValue::Ids(task.depends_on.iter().copied().collect()),
+        FieldId::Blocked => FieldValue::Flag(tasks.is_blocked(task.id)),
+        FieldId::Overdue => FieldValue::Flag(task.is_overdue(now)),
+        FieldId::Recurring => FieldValue::Flag(task.recurrence.is_some()),
+    }
+}
+
+// Query implementation
+impl Query {
+    // Parse a query; saved queries are referenced as @name and dates are resolved relative to `now`
+    pub fn parse(query: &str, saved: &BTreeMap<String, String>, now: DateTime<FixedOffset>) -> Result<Self, QueryError> {
+        parse_tokens(query, saved, Vec::new(), now)
+            .map(Query)
+            .map_err(|(message, position)| QueryError { message, position, query: query.to_string() })
+    }
+
+    // Check whether a task matches the query
+    pub fn matches(&self, tasks: &Tasks, task: &Task, now: DateTime<FixedOffset>) -> bool {
+        self.0.matches(tasks, task, now)
+    }
+}
+
+// Expr implementation
+impl Expr {
+    fn matches(&self, tasks: &Tasks, task: &Task, now: DateTime<FixedOffset>) -> bool {
+        match self {
+            Expr::And(left, right) => left.matches(tasks, task, now) && right.matches(tasks, task, now),
+            Expr::Or(left, right) => left.matches(tasks, task, now) || right.matches(tasks, task, now),
+            Expr::Not(expr) => !expr.matches(tasks, task, now),
+            Expr::Compare { field, op, value: expected } => compare(field, *op, expected, value(field.id, tasks, task, now)),
+        }
+    }
+}
+
+// Compare a field of a task with a value of the query
+fn compare(field: &Field, op: Op, expected: &Value, actual: FieldValue) -> bool {
+    // Missing values only match "none" (and "!= value")
+    let missing = match (&actual, expected) {
+        (FieldValue::Number(None) | FieldValue::Date(None), Value::None) => Some(op != Op::Ne),
+        (FieldValue::Number(Some(_)) | FieldValue::Date(Some(_)), Value::None) => Some(op == Op::Ne),
+        (FieldValue::Ids(ids), Value::None) => Some(ids.is_empty() != (op == Op::Ne)),
+        (FieldValue::Number(None) | FieldValue::Date(None), _) => Some(op == Op::Ne),
+        _ => None,
+    };
+
+    if let Some(result) = missing {
+        return result;
+    }
+
+    match (actual, expected) {
+        (FieldValue::Number(Some(number)), Value::Number(expected)) => op.compare(number, *expected),
+        (FieldValue::Text(text), Value::Text(expected)) => {
+            let text = if field.kind == Kind::Project { text.to_string() } else { normalize(text) };
+
+            match op {
+                Op::Contains => text.contains(expected.as_str()),
+                // Projects include their subprojects
+                Op::Is if field.kind == Kind::Project => text == *expected || text.starts_with(&format!("{}.", expected)),
+                _ => op.compare(text.as_str(), expected.as_str()),
+            }
+        }
+        (FieldValue::Tags(tags), Value::Text(expected)) => {
+            let tags: Vec<String> = tags.iter().map(|tag| normalize(tag)).collect();
+
+            match op {
+                Op::Contains => tags.iter().any(|tag| tag.contains(expected.as_str())),
+                Op::Ne => !tags.contains(expected),
+                _ => tags.contains(expected),
+            }
+        }
+        (FieldValue::Ids(ids), Value::Number(expected)) => ids.contains(expected) != (op == Op::Ne),
+        (FieldValue::Status(status), Value::Status(expected)) => op.compare(status as u8, *expected as u8),
+        (FieldValue::Priority(priority), Value::Priority(expected)) => op.compare(priority, *expected),
+        (FieldValue::Date(Some(date)), Value::Date(expected)) => op.compare(date.date_naive(), *expected),
+        (FieldValue::Flag(flag), Value::Flag(expected)) => op.compare(flag, *expected),
+        _ => false,
+    }
+}
+
+// Test configuration
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,117 @@
+use super::Query;
+use crate::dates::reference_now;
+use crate::tasks::{TaskOptions, TaskPriority, TaskStatus, Tasks};
+use chrono::DateTime;
+use std::collections::BTreeMap;
+
+// Tasks with different statuses, priorities, tags, projects and due dates
+fn sample_tasks() -> Tasks {
+    let mut tasks = Tasks::default();
+    let due = |date: &str| Some(DateTime::parse_from_rfc3339(date).unwrap());
+
+    let options = TaskOptions { tags: vec!["trabajo".into()], due_at: due("2025-11-06T17:00:00-04:00"), project: Some("trabajo.informes".into()), ..Default::default() };
+    tasks.add_task_with("Informe mensual".into(), "".into(), options).unwrap();
+    let options = TaskOptions { priority: TaskPriority::Urgent, due_at: due("2025-11-03T09:00:00-04:00"), ..Default::default() };
+    tasks.add_task_with("Pagar la luz".into(), "Factura de octubre".into(), options).unwrap();
+    let options = TaskOptions { tags: vec!["casa".into()], depends_on: vec![2], ..Default::default() };
+    tasks.add_task_with("Revisar el REPORT".into(), "".into(), options).unwrap();
+    tasks.add_task_with("Terminada".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.change_task_status(4, TaskStatus::Completed).unwrap();
+    tasks
+}
+
+// IDs of the tasks matching a query
+fn matching(tasks: &Tasks, query: &str, saved: &BTreeMap<String, String>) -> Vec<usize> {
+    let now = reference_now();
+    let query = Query::parse(query, saved, now).unwrap();
+    tasks.iter().filter(|task| query.matches(tasks, task, now)).map(|task| task.id).collect()
+}
+
+#[test]
+// Test: comparisons on each kind of field
+fn test_query_fields() {
+    let tasks = sample_tasks();
+    let none = BTreeMap::new();
+    let ids = |query: &str| matching(&tasks, query, &none);
+
+    assert_eq!(ids("status:pending"), [1, 2, 3]);
+    assert_eq!(ids("estado=completada"), [4]);
+    assert_eq!(ids("priority>=high"), [2]);
+    assert_eq!(ids("priority<medium"), Vec::<usize>::new());
+    assert_eq!(ids("tag:trabajo"), [1]);
+    assert_eq!(ids("tag!=trabajo"), [2, 3, 4]);
+    assert_eq!(ids("project:trabajo"), [1]);
+    assert_eq!(ids("project=trabajo"), Vec::<usize>::new());
+    assert_eq!(ids("title~report"), [3]);
+    assert_eq!(ids("description~\"factura de\""), [2]);
+    assert_eq!(ids("id>2"), [3, 4]);
+    assert_eq!(ids("due<friday"), [1, 2]);
+    assert_eq!(ids("due:none"), [3, 4]);
+    assert_eq!(ids("due!=none"), [1, 2]);
+    assert_eq!(ids("due=2025-11-06"), [1]);
+    assert_eq!(ids("overdue:true"), [2]);
+    assert_eq!(ids("blocked:true"), [3]);
+    assert_eq!(ids("depends:2"), [3]);
+}
+
+#[test]
+// Test: and, or, not, implicit and and parentheses
+fn test_query_operators() {
+    let tasks = sample_tasks();
+    let none = BTreeMap::new();
+    let ids = |query: &str| matching(&tasks, query, &none);
+
+    assert_eq!(ids("status:pending and (tag:trabajo or priority>=high) and due<friday"), [1, 2]);
+    assert_eq!(ids("status:pending tag:casa"), [3]);
+    assert_eq!(ids("tag:casa or tag:trabajo and priority:urgent"), [3]);
+    assert_eq!(ids("(tag:casa or tag:trabajo) and priority:urgent"), Vec::<usize>::new());
+    assert_eq!(ids("not status:completed and !blocked:true"), [1, 2]);
+    assert_eq!(ids("NOT (id=1 OR id=2)"), [3, 4]);
+}
+
+#[test]
+// Test: syntax errors point at the position of the problem
+fn test_query_errors() {
+    let none = BTreeMap::new();
+    let error = |query: &str| Query::parse(query, &none, reference_now()).unwrap_err();
+
+    let err = error("status:pending and foo:1");
+    assert_eq!(err.position, 20);
+    assert!(err.message.starts_with("Campo desconocido \"foo\""));
+    assert!(err.to_string().ends_with("status:pending and foo:1\n                     ^"));
+
+    assert_eq!(error("status").position, 7);
+    assert_eq!(error("status:").position, 8);
+    assert_eq!(error("status:later").position, 8);
+    assert_eq!(error("(status:pending").position, 1);
+    assert_eq!(error("status:pending)").position, 15);
+    assert_eq!(error("title~\"abc").position, 7);
+    assert_eq!(error("priority~high").position, 9);
+    assert_eq!(error("due<someday").position, 5);
+    assert_eq!(error("tag:a or").position, 9);
+    assert!(error("@").message.contains("consulta guardada"));
+}
+
+#[test]
+// Test: saved queries can be referenced by name (unknown and recursive ones are rejected)
+fn test_query_saved() {
+    let tasks = sample_tasks();
+    let saved: BTreeMap<String, String> = [
+        ("abiertas".to_string(), "status!=completed".to_string()),
+        ("urgentes".to_string(), "@abiertas priority>=high".to_string()),
+        ("ciclo".to_string(), "@otra".to_string()),
+        ("otra".to_string(), "tag:x or @ciclo".to_string()),
+    ]
+    .into();
+
+    assert_eq!(matching(&tasks, "@abiertas", &saved), [1, 2, 3]);
+    assert_eq!(matching(&tasks, "@urgentes or tag:casa", &saved), [2, 3]);
+    assert_eq!(matching(&tasks, "not @abiertas", &saved), [4]);
+
+    let err = Query::parse("tag:x @nada", &saved, reference_now()).unwrap_err();
+    assert_eq!(err.position, 7);
+    assert_eq!(err.message, "Consulta guardada desconocida: @nada");
+
+    let err = Query::parse("@ciclo", &saved, reference_now()).unwrap_err();
+    assert!(err.message.contains("se usa a sí misma"));
+}
@@ -0,0 +1,519 @@
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::dates::normalize;
+use crate::query::Query;
+use crate::tasks::{Task, TaskFilter, TaskPriority, TaskStatus, Tasks};
+
+// Formats in which task lists can be written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+    Markdown,
+    Plain,
+}
+
+// Task as written in machine-readable formats. Field names and values are part of the output
+// contract (independent of the storage format): snake_case names, English status and priority
+// keys and RFC 3339 dates
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskRecord<'a> {
+    pub id: usize,
+    pub uuid: Option<Uuid>,
+    pub title: &'a str,
+    pub description: &'a str,
+    pub project: &'a str,
+    pub status: &'static str,
+    pub priority: &'static str,
+    pub tags: Vec<&'a str>,
+    pub due_at: Option<String>,
+    pub overdue: bool,
+    pub recurrence: Option<String>,
+    pub parent_id: Option<usize>,
+    pub depends_on: Vec<usize>,
+    pub blocked_by: Vec<usize>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub completed_at: Option<String>,
+}
+
+// Columns of the CSV and TSV formats, in the order of TaskRecord
+const RECORD_FIELDS: [&str; 17] = [
+    "id", "uuid", "title", "description", "project", "status", "priority", "tags", "due_at", "overdue", "recurrence",
+    "parent_id", "depends_on", "blocked_by", "created_at", "updated_at", "completed_at",
+];
+
+// Columns of the table and Markdown formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Id,
+    Title,
+    Description,
+    Project,
+    Priority,
+    Tags,
+    Due,
+    Recurrence,
+    Created,
+    Updated,
+    Status,
+    Completed,
+    BlockedBy,
+}
+
+// Name used to select each column and its header, in the default order
+const COLUMNS: [(Column, &str, &str); 13] = [
+    (Column::Id, "id", "ID"),
+    (Column::Title, "title", "Título"),
+    (Column::Description, "description", "Descripción"),
+    (Column::Project, "project", "Proyecto"),
+    (Column::Priority, "priority", "Prioridad"),
+    (Column::Tags, "tags", "Etiquetas"),
+    (Column::Due, "due", "Vence"),
+    (Column::Recurrence, "recurrence", "Repite"),
+    (Column::Created, "created", "Creado en"),
+    (Column::Updated, "updated", "Actualizado en"),
+    (Column::Status, "status", "Estado"),
+    (Column::Completed, "completed", "Completado en"),
+    (Column::BlockedBy, "blocked", "Bloqueada por"),
+];
+
+// Column implementation
+impl Column {
+    fn header(self) -> &'static str {
+        COLUMNS.iter().find(|(column, _, _)| *column == self).map_or("", |(_, _, header)| header)
+    }
+}
+
+// Columns to show, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Columns(pub Vec<Column>);
+
+// Implement Default for Columns (every column)
+impl Default for Columns {
+    fn default() -> Self {
+        Self(COLUMNS.iter().map(|(column, _, _)| *column).collect())
+    }
+}
+
+// Implement FromStr for Columns: "id,title,status,due"
+impl FromStr for Columns {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let names: Vec<&str> = COLUMNS.iter().map(|(_, name, _)| *name).collect();
+
+        let columns = value
+            .split(',')
+            .map(|name| {
+                COLUMNS
+                    .iter()
+                    .find(|(_, key, _)| *key == name.trim().to_lowercase())
+                    .map(|(column, _, _)| *column)
+                    .ok_or_else(|| format!("Columna desconocida: \"{}\" (columnas: {})", name.trim(), names.join(", ")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self(columns))
+    }
+}
+
+// Fields tasks can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    Id,
+    Title,
+    Project,
+    Priority,
+    Status,
+    Due,
+    Created,
+    Updated,
+    Completed,
+}
+
+// Name used to select each sort field
+const SORT_FIELDS: [(SortField, &str); 9] = [
+    (SortField::Id, "id"),
+    (SortField::Title, "title"),
+    (SortField::Project, "project"),
+    (SortField::Priority, "priority"),
+    (SortField::Status, "status"),
+    (SortField::Due, "due"),
+    (SortField::Created, "created"),
+    (SortField::Updated, "updated"),
+    (SortField::Completed, "completed"),
+];
+
+// A sort field and its direction ("-" in front for descending)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+// SortKey implementation
+impl SortKey {
+    // Compare two tasks by this key. Tasks without the date go last in both directions
+    fn compare(self, a: &Task, b: &Task) -> Ordering {
+        let completed = |task: &Task| (task.status == TaskStatus::Completed).then_some(task.completed_at);
+
+        let dates = |a: Option<DateTime<FixedOffset>>, b: Option<DateTime<FixedOffset>>| match (a, b) {
+            (Some(a), Some(b)) if self.descending => b.cmp(&a),
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+
+        let order = match self.field {
+            SortField::Id => a.id.cmp(&b.id),
+            SortField::Title => normalize(&a.title).cmp(&normalize(&b.title)),
+            SortField::Project => a.project.cmp(&b.project),
+            SortField::Priority => a.priority.cmp(&b.priority),
+            SortField::Status => (a.status as u8).cmp(&(b.status as u8)),
+            SortField::Due => return dates(a.due_at, b.due_at),
+            SortField::Created => a.created_at.cmp(&b.created_at),
+            SortField::Updated => a.updated_at.cmp(&b.updated_at),
+            SortField::Completed => return dates(completed(a), completed(b)),
+        };
+
+        if self.descending { order.reverse() } else { order }
+    }
+}
+
+// Order in which tasks are listed (ties are broken by ID)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortOrder(pub Vec<SortKey>);
+
+// Implement Default for SortOrder (highest priority first)
+impl Default for SortOrder {
+    fn default() -> Self {
+        Self(vec![SortKey { field: SortField::Priority, descending: true }])
+    }
+}
+
+// Implement FromStr for SortOrder: "due,-priority,created"
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let names: Vec<&str> = SORT_FIELDS.iter().map(|(_, name)| *name).collect();
+
+        let keys = value
+            .split(',')
+            .map(|key| {
+                let key = key.trim().to_lowercase();
+                let (name, descending) = match key.strip_prefix('-') {
+                    Some(name) => (name, true),
+                    None => (key.strip_prefix('+').unwrap_or(&key), false),
+                };
+
+                SORT_FIELDS
+                    .iter()
+                    .find(|(_, field)| *field == name)
+                    .map(|(field, _)| SortKey { field: *field, descending })
+                    .ok_or_else(|| format!("No se puede ordenar por \"{}\" (campos: {}; use - para orden descendente)", name, names.join(", ")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self(keys))
+    }
+}
+
+// SortOrder implementation
+impl SortOrder {
+    fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        self.0.iter().map(|key| key.compare(a, b)).find(|order| order.is_ne()).unwrap_or(Ordering::Equal).then(a.id.cmp(&b.id))
+    }
+}
+
+// How the table and Markdown formats are laid out
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Layout {
+    pub columns: Columns,
+    pub sort: SortOrder,
+    // Show long texts in full instead of fitting the table to the terminal
+    pub wide: bool,
+    // Width to fit the table to (the terminal width when None)
+    pub width: Option<u16>,
+}
+
+// Stable key of a status in machine-readable formats
+fn status_key(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "pending",
+        TaskStatus::InProgress => "in_progress",
+        TaskStatus::Completed => "completed",
+    }
+}
+
+// Stable key of a priority in machine-readable formats
+fn priority_key(priority: TaskPriority) -> &'static str {
+    match priority {
+        TaskPriority::Low => "low",
+        TaskPriority::Medium => "medium",
+        TaskPriority::High => "high",
+        TaskPriority::Urgent => "urgent",
+    }
+}
+
+// Color used to display each priority
+fn priority_color(priority: TaskPriority) -> Color {
+    match priority {
+        TaskPriority::Low => Color::DarkGrey,
+        TaskPriority::Medium => Color::Blue,
+        TaskPriority::High => Color::Yellow,
+        TaskPriority::Urgent => Color::Red,
+    }
+}
+
+// Date in RFC 3339 with second precision
+fn iso_date(date: DateTime<FixedOffset>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+// Join IDs with commas
+fn join_ids(ids: &[usize]) -> String {
+    ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+// TaskRecord implementation
+impl<'a> TaskRecord<'a> {
+    pub fn new(tasks: &Tasks, task: &'a Task, now: DateTime<FixedOffset>) -> Self {
+        Self {
+            id: task.id,
+            uuid: task.uuid,
+            title: &task.title,
+            description: &task.description,
+            project: &task.project,
+            status: status_key(task.status),
+            priority: priority_key(task.priority),
+            tags: task.tags.iter().map(String::as_str).collect(),
+            due_at: task.due_at.map(iso_date),
+            overdue: task.is_overdue(now),
+            recurrence: task.recurrence.as_ref().map(|recurrence| recurrence.to_string()),
+            parent_id: task.parent,
+            depends_on: task.depends_on.iter().copied().collect(),
+            blocked_by: tasks.blockers(task.id),
+            created_at: iso_date(task.created_at),
+            updated_at: iso_date(task.updated_at),
+            completed_at: (task.status == TaskStatus::Completed).then(|| iso_date(task.completed_at)),
+        }
+    }
+
+    // Values of the record as text, in the order of RECORD_FIELDS (lists separated by commas)
+    fn fields(&self) -> [String; 17] {
+        let ids = |ids: &[usize]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+
+        [
+            self.id.to_string(),
+            self.uuid.map(|uuid| uuid.to_string()).unwrap_or_default(),
+            self.title.to_string(),
+            self.description.to_string(),
+            self.project.to_string(),
+            self.status.to_string(),
+            self.priority.to_string(),
+            self.tags.join(","),
+            self.due_at.clone().unwrap_or_default(),
+            self.overdue.to_string(),
+            self.recurrence.clone().unwrap_or_default(),
+            self.parent_id.map(|id| id.to_string()).unwrap_or_default(),
+            ids(&self.depends_on),
+            ids(&self.blocked_by),
+            self.created_at.clone(),
+            self.updated_at.clone(),
+            self.completed_at.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+// Quote a CSV field when needed (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// TSV fields cannot contain tabs or line breaks, they are replaced by spaces
+fn tsv_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+// Markdown table cells cannot contain pipes or line breaks
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+// Shorten a text to its first line (the table wraps it to the width of the column)
+fn first_line(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+
+    if line.len() == text.trim_end().len() {
+        return line.to_string();
+    }
+
+    format!("{}…", line.trim_end())
+}
+
+// Cell shown for a task in a column of the table and Markdown formats (subtasks are indented under
+// their parent, parents show their progress; texts show their first line unless `wide`)
+fn cell(tasks: &Tasks, task: &Task, depth: usize, column: Column, wide: bool) -> String {
+    let text = |text: &str| if wide { text.to_string() } else { first_line(text) };
+
+    match column {
+        Column::Id => task.id.to_string(),
+        Column::Title => {
+            let indent = match depth {
+                0 => String::new(),
+                _ => format!("{}└─ ", "   ".repeat(depth - 1)),
+            };
+
+            let mut title = format!("{}{}", indent, text(&task.title));
+
+            if let Some((completed, total)) = tasks.subtask_progress(task.id) {
+                title.push_str(&format!(" ({}/{})", completed, total));
+            }
+
+            title
+        }
+        Column::Description => text(&task.description),
+        Column::Project => task.project.clone(),
+        Column::Priority => task.priority.stringify().to_string(),
+        Column::Tags => task.tags.iter().map(|tag| format!("+{}", tag)).collect::<Vec<_>>().join(" "),
+        Column::Due => task.due_at.map(|due| due.format("%d-%m-%Y %H:%M").to_string()).unwrap_or_default(),
+        Column::Recurrence => task.recurrence.as_ref().map(|recurrence| recurrence.stringify()).unwrap_or_default(),
+        Column::Created => task.created_at.format("%d-%m-%Y %H:%M:%S").to_string(),
+        Column::Updated => task.updated_at.format("%d-%m-%Y %H:%M:%S").to_string(),
+        Column::Status => task.status.stringify().to_string(),
+        Column::Completed if task.status == TaskStatus::Completed => task.completed_at.format("%d-%m-%Y %H:%M:%S").to_string(),
+        Column::Completed => String::new(),
+        Column::BlockedBy => join_ids(&tasks.blockers(task.id)),
+    }
+}
+
+// Table for the terminal (overdue tasks are highlighted). Unless `wide`, the table is fitted to the
+// terminal width, wrapping the cells that do not fit
+fn table(tasks: &Tasks, rows: &[(&Task, usize)], layout: &Layout, now: DateTime<FixedOffset>) -> String {
+    let mut table = Table::new();
+    table.set_header(layout.columns.0.iter().map(|column| Cell::new(column.header())).collect::<Vec<_>>());
+
+    if !layout.wide {
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+
+        if let Some(width) = layout.width {
+            table.set_width(width);
+        }
+    }
+
+    for &(task, depth) in rows {
+        let mut row: Vec<Cell> = layout
+            .columns
+            .0
+            .iter()
+            .map(|&column| match column {
+                Column::Priority => Cell::new(task.priority.stringify()).fg(priority_color(task.priority)),
+                _ => Cell::new(cell(tasks, task, depth, column, layout.wide)),
+            })
+            .collect();
+
+        if task.is_overdue(now) {
+            row = row.into_iter().map(|cell| cell.fg(Color::Red).add_attribute(Attribute::Bold)).collect();
+        }
+
+        table.add_row(row);
+    }
+
+    table.to_string()
+}
+
+// Markdown table (texts are always complete)
+fn markdown(tasks: &Tasks, rows: &[(&Task, usize)], columns: &Columns) -> String {
+    let headers: Vec<&str> = columns.0.iter().map(|column| column.header()).collect();
+    let mut lines = vec![format!("| {} |", headers.join(" | ")), format!("|{}", "---|".repeat(headers.len()))];
+
+    for &(task, depth) in rows {
+        let cells: Vec<String> = columns.0.iter().map(|&column| markdown_cell(&cell(tasks, task, depth, column, true))).collect();
+        lines.push(format!("| {} |", cells.join(" | ")));
+    }
+
+    lines.join("\n")
+}
+
+// One line per task, with only the attributes that are set
+fn plain(tasks: &Tasks, rows: &[(&Task, usize)]) -> String {
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|&(task, depth)| {
+            let mut line = format!("{}{} {} [{}, {}]", "  ".repeat(depth), task.id, task.title, task.status.stringify(), task.priority.stringify());
+
+            if let Some(due) = task.due_at {
+                line.push_str(&format!(" vence {}", due.format("%d-%m-%Y %H:%M")));
+            }
+
+            for tag in &task.tags {
+                line.push_str(&format!(" +{}", tag));
+            }
+
+            let blockers = tasks.blockers(task.id);
+
+            if !blockers.is_empty() {
+                line.push_str(&format!(" (bloqueada por {})", join_ids(&blockers)));
+            }
+
+            line
+        })
+        .collect();
+
+    lines.join("\n")
+}
+
+// Delimited text with a header row
+fn delimited(records: &[TaskRecord], separator: &str, field: fn(&str) -> String) -> String {
+    let mut lines = vec![RECORD_FIELDS.join(separator)];
+
+    for record in records {
+        lines.push(record.fields().iter().map(|value| field(value)).collect::<Vec<_>>().join(separator));
+    }
+
+    lines.join("\n")
+}
+
+// Write the tasks that pass the filter and the query in the given format, in the order of the
+// layout (with subtasks under their parent). Columns and widths only apply to the table and
+// Markdown formats
+pub fn render_tasks(tasks: &Tasks, filter: &TaskFilter, query: Option<&Query>, format: OutputFormat, layout: &Layout, now: DateTime<FixedOffset>) -> Result<String, serde_json::Error> {
+    let mut filtered = tasks.filter_tasks_at(filter, now);
+    filtered.retain(|task| query.is_none_or(|query| query.matches(tasks, task, now)));
+    filtered.sort_by(|a, b| layout.sort.compare(a, b));
+
+    let rows = tasks.tree_order(filtered);
+    let records = || rows.iter().map(|&(task, _)| TaskRecord::new(tasks, task, now)).collect::<Vec<_>>();
+
+    let output = match format {
+        OutputFormat::Table => table(tasks, &rows, layout, now),
+        OutputFormat::Markdown => markdown(tasks, &rows, &layout.columns),
+        OutputFormat::Plain => plain(tasks, &rows),
+        OutputFormat::Json => serde_json::to_string_pretty(&records())?,
+        OutputFormat::Ndjson => records().iter().map(serde_json::to_string).collect::<Result<Vec<_>, _>>()?.join("\n"),
+        OutputFormat::Csv => delimited(&records(), ",", csv_field),
+        OutputFormat::Tsv => delimited(&records(), "\t", tsv_field),
+    };
+
+    Ok(output)
+}
+
+// Test configuration
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,172 @@
+use super::{render_tasks, Columns, Layout, OutputFormat, SortOrder};
+use crate::dates::reference_now;
+use crate::query::Query;
+use crate::tasks::{Recurrence, TaskFilter, TaskOptions, TaskPriority, TaskStatus, Tasks};
+use chrono::DateTime;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+// Tasks covering every field: a parent with a subtask, tags, due date, recurrence and a dependency
+fn sample_tasks() -> Tasks {
+    let mut tasks = Tasks::default();
+    let options = TaskOptions {
+        priority: TaskPriority::High,
+        due_at: Some(DateTime::parse_from_rfc3339("2025-11-02T17:00:00-04:00").unwrap()),
+        tags: vec!["trabajo".into(), "urgente".into()],
+        recurrence: Some("weekly:mon,fri".parse::<Recurrence>().unwrap()),
+        ..Default::default()
+    };
+    tasks.add_task_with("Informe, \"semanal\"".into(), "Línea 1\nLínea 2\t| fin".into(), options).unwrap();
+    tasks.add_task_with("Revisar".into(), "".into(), TaskOptions { parent: Some(1), depends_on: vec![1], ..Default::default() }).unwrap();
+    tasks.add_task_with("Hecha".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.change_task_status(3, TaskStatus::Completed).unwrap();
+    tasks
+}
+
+// Render all visible tasks
+fn render(tasks: &Tasks, format: OutputFormat) -> String {
+    render_tasks(tasks, &TaskFilter::default(), None, format, &Layout::default(), reference_now()).unwrap()
+}
+
+#[test]
+// Test: JSON output uses stable field names and RFC 3339 dates
+fn test_render_json() {
+    let tasks = sample_tasks();
+    let value: Value = serde_json::from_str(&render(&tasks, OutputFormat::Json)).unwrap();
+    let records = value.as_array().unwrap();
+    assert_eq!(records.len(), 3);
+
+    let first = &records[0];
+    assert_eq!(first["id"], 1);
+    assert_eq!(first["title"], "Informe, \"semanal\"");
+    assert_eq!(first["status"], "pending");
+    assert_eq!(first["priority"], "high");
+    assert_eq!(first["tags"], serde_json::json!(["trabajo", "urgente"]));
+    assert_eq!(first["due_at"], "2025-11-02T17:00:00-04:00");
+    assert_eq!(first["overdue"], true);
+    assert_eq!(first["recurrence"], "weekly:mon,fri");
+    assert_eq!(first["completed_at"], Value::Null);
+    assert!(first["uuid"].is_string());
+
+    // Subtasks follow their parent
+    assert_eq!(records[1]["id"], 2);
+    assert_eq!(records[1]["parent_id"], 1);
+    assert_eq!(records[1]["blocked_by"], serde_json::json!([1]));
+    assert_eq!(records[2]["status"], "completed");
+    assert!(records[2]["completed_at"].is_string());
+}
+
+#[test]
+// Test: NDJSON output has one JSON object per line
+fn test_render_ndjson() {
+    let tasks = sample_tasks();
+    let output = render(&tasks, OutputFormat::Ndjson);
+    let ids: Vec<u64> = output.lines().map(|line| serde_json::from_str::<Value>(line).unwrap()["id"].as_u64().unwrap()).collect();
+    assert_eq!(ids, [1, 2, 3]);
+    assert_eq!(render(&Tasks::default(), OutputFormat::Ndjson), "");
+    assert_eq!(render(&Tasks::default(), OutputFormat::Json), "[]");
+}
+
+#[test]
+// Test: CSV and TSV output (quoting and separators)
+fn test_render_csv_tsv() {
+    let tasks = sample_tasks();
+    let csv = render(&tasks, OutputFormat::Csv);
+    assert!(csv.starts_with("id,uuid,title,description,project,status,priority,tags,"));
+    assert!(csv.contains(",\"Informe, \"\"semanal\"\"\",\"Línea 1\nLínea 2\t| fin\",general,pending,high,\"trabajo,urgente\",2025-11-02T17:00:00-04:00,true,"));
+
+    let tsv = render(&tasks, OutputFormat::Tsv);
+    let lines: Vec<&str> = tsv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines.iter().all(|line| line.split('\t').count() == 17));
+    assert!(lines[1].contains("\tInforme, \"semanal\"\tLínea 1 Línea 2 | fin\t"));
+}
+
+#[test]
+// Test: Markdown, plain and table output
+fn test_render_text_formats() {
+    let tasks = sample_tasks();
+    let markdown = render(&tasks, OutputFormat::Markdown);
+    let lines: Vec<&str> = markdown.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].starts_with("| ID | Título |"));
+    assert!(lines[2].contains("Línea 1 Línea 2\t\\| fin"));
+    assert!(lines[3].contains("└─ Revisar"));
+
+    let plain = render(&tasks, OutputFormat::Plain);
+    assert_eq!(
+        plain.lines().collect::<Vec<_>>(),
+        [
+            "1 Informe, \"semanal\" [Pendiente, Alta] vence 02-11-2025 17:00 +trabajo +urgente",
+            "  2 Revisar [Pendiente, Media] (bloqueada por 1)",
+            "3 Hecha [Completada, Media]",
+        ]
+    );
+
+    assert!(render(&tasks, OutputFormat::Table).contains("Revisar"));
+}
+
+#[test]
+// Test: sort keys, directions and tasks without a due date
+fn test_render_sort() {
+    let mut tasks = Tasks::default();
+    let due = |date: &str| TaskOptions { due_at: Some(DateTime::parse_from_rfc3339(date).unwrap()), ..Default::default() };
+    tasks.add_task_with("b".into(), "".into(), due("2025-11-07T10:00:00-04:00")).unwrap();
+    tasks.add_task_with("A".into(), "".into(), TaskOptions { priority: TaskPriority::Urgent, ..Default::default() }).unwrap();
+    tasks.add_task_with("c".into(), "".into(), due("2025-11-06T10:00:00-04:00")).unwrap();
+
+    let ids = |sort: &str| {
+        let layout = Layout { sort: sort.parse().unwrap(), ..Default::default() };
+        let output = render_tasks(&tasks, &TaskFilter::default(), None, OutputFormat::Ndjson, &layout, reference_now()).unwrap();
+        output.lines().map(|line| serde_json::from_str::<Value>(line).unwrap()["id"].as_u64().unwrap()).collect::<Vec<_>>()
+    };
+
+    assert_eq!(ids("due"), [3, 1, 2]);
+    assert_eq!(ids("-due"), [1, 3, 2]);
+    assert_eq!(ids("-priority,-id"), [2, 3, 1]);
+    assert_eq!(ids("title"), [2, 1, 3]);
+    assert_eq!(ids("+id"), [1, 2, 3]);
+    assert_eq!(ids("due,-priority,created"), [3, 1, 2]);
+    assert!("due,size".parse::<SortOrder>().unwrap_err().contains("\"size\""));
+}
+
+#[test]
+// Test: selected columns, truncated texts and --wide
+fn test_render_columns_and_width() {
+    let mut tasks = Tasks::default();
+    let description = "Una descripción muy larga que no cabe en la columna de la tabla\nSegunda línea";
+    tasks.add_task_with("Informe".into(), description.into(), TaskOptions::default()).unwrap();
+
+    let columns: Columns = "id, title,STATUS,description".parse().unwrap();
+    assert!("id,size".parse::<Columns>().unwrap_err().starts_with("Columna desconocida: \"size\""));
+
+    let layout = Layout { columns: columns.clone(), ..Default::default() };
+    let markdown = render_tasks(&tasks, &TaskFilter::default(), None, OutputFormat::Markdown, &layout, reference_now()).unwrap();
+    assert!(markdown.starts_with("| ID | Título | Estado | Descripción |\n|---|---|---|---|\n"));
+    assert!(markdown.contains("Segunda línea"));
+
+    let table = render_tasks(&tasks, &TaskFilter::default(), None, OutputFormat::Table, &layout, reference_now()).unwrap();
+    assert!(table.contains("| Una descripción muy larga que no cabe en la columna de la tabla… |"));
+    assert!(!table.contains("Segunda"));
+
+    let narrow = Layout { width: Some(40), ..layout.clone() };
+    let table = render_tasks(&tasks, &TaskFilter::default(), None, OutputFormat::Table, &narrow, reference_now()).unwrap();
+    assert!(table.lines().all(|line| line.chars().count() <= 40));
+    assert!(table.contains("tabla…"));
+
+    let wide = Layout { wide: true, width: Some(40), ..layout };
+    let table = render_tasks(&tasks, &TaskFilter::default(), None, OutputFormat::Table, &wide, reference_now()).unwrap();
+    assert!(table.contains("la columna de la tabla"));
+    assert!(table.contains("Segunda línea"));
+}
+
+#[test]
+// Test: the query is applied together with the filter
+fn test_render_with_query() {
+    let tasks = sample_tasks();
+    let query = Query::parse("tag:trabajo or title~hecha", &BTreeMap::new(), reference_now()).unwrap();
+    let filter = TaskFilter { status: Some(TaskStatus::Pending), ..Default::default() };
+    let output = render_tasks(&tasks, &filter, Some(&query), OutputFormat::Ndjson, &Layout::default(), reference_now()).unwrap();
+    let ids: Vec<u64> = output.lines().map(|line| serde_json::from_str::<Value>(line).unwrap()["id"].as_u64().unwrap()).collect();
+    assert_eq!(ids, [1]);
+}
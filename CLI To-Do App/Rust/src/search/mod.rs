@@ -0,0 +1,379 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+
+use crate::dates::normalize;
+use crate::tasks::{Task, Tasks};
+
+// Format of the stored index (an index in another format is rebuilt)
+const INDEX_VERSION: u32 = 1;
+
+// Weight of a match in the title compared to one in the description
+const TITLE_WEIGHT: f64 = 3.0;
+
+// Weight of a prefix match compared to a whole word
+const PREFIX_WEIGHT: f64 = 0.7;
+
+// Extra weight of a phrase compared to its words
+const PHRASE_WEIGHT: f64 = 1.5;
+
+// Marks placed around the matches when highlighting
+const HIGHLIGHT: (&str, &str) = ("[", "]");
+
+// Errors shown to the user when the search terms are not valid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchError {
+    Empty,
+    UnclosedQuote,
+}
+
+// Implement fmt::Display for SearchError
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::Empty => write!(f, "No hay nada que buscar"),
+            SearchError::UnclosedQuote => write!(f, "Comillas sin cerrar en la búsqueda"),
+        }
+    }
+}
+
+// Split a text into words, lowercased and without accents, with their byte range in the text
+fn words(text: &str) -> Vec<(String, Range<usize>)> {
+    let mut words = Vec::new();
+    let mut current: Option<(String, usize)> = None;
+
+    for (i, c) in text.char_indices() {
+        if c.is_alphanumeric() {
+            let (word, _) = current.get_or_insert_with(|| (String::new(), i));
+            word.push_str(&normalize(c.encode_utf8(&mut [0; 4])));
+        } else if let Some((word, start)) = current.take() {
+            words.push((word, start..i));
+        }
+    }
+
+    if let Some((word, start)) = current {
+        words.push((word, start..text.len()));
+    }
+
+    words
+}
+
+// A search term: a word (prefix when it ends with *) or a phrase between quotes
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    Word { word: String, prefix: bool },
+    Phrase(Vec<String>),
+}
+
+// Search terms; a task matches when it contains all of them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    terms: Vec<Term>,
+}
+
+// SearchQuery implementation
+impl SearchQuery {
+    // Parse the search terms: informe "pago de la luz" fact*
+    pub fn parse(input: &str) -> Result<Self, SearchError> {
+        let mut terms = Vec::new();
+
+        for (i, part) in input.split('"').enumerate() {
+            // Odd parts are between quotes
+            if i % 2 == 1 {
+                let phrase: Vec<String> = words(part).into_iter().map(|(word, _)| word).collect();
+
+                match phrase.len() {
+                    0 => {}
+                    1 => terms.push(Term::Word { word: phrase[0].clone(), prefix: false }),
+                    _ => terms.push(Term::Phrase(phrase)),
+                }
+
+                continue;
+            }
+
+            for chunk in part.split_whitespace() {
+                let prefix = chunk.ends_with('*');
+
+                for (word, range) in words(chunk) {
+                    // Only the last word of the chunk ("e-mail*" -> "e", "mail*") is a prefix
+                    let prefix = prefix && range.end == chunk.trim_end_matches('*').len();
+                    terms.push(Term::Word { word, prefix });
+                }
+            }
+        }
+
+        if input.matches('"').count() % 2 == 1 {
+            return Err(SearchError::UnclosedQuote);
+        }
+
+        if terms.is_empty() {
+            return Err(SearchError::Empty);
+        }
+
+        Ok(Self { terms })
+    }
+
+    // Whether a word of a text matches a word of the query
+    fn matches_word(&self, word: &str) -> bool {
+        self.terms.iter().any(|term| match term {
+            Term::Word { word: term, prefix: true } => word.starts_with(term.as_str()),
+            Term::Word { word: term, prefix: false } => word == term,
+            Term::Phrase(_) => false,
+        })
+    }
+
+    // Mark the parts of a text matched by the query (None when nothing matches)
+    pub fn highlight(&self, text: &str) -> Option<String> {
+        let words = words(text);
+        let mut marked = vec![false; words.len()];
+
+        for (i, (word, _)) in words.iter().enumerate() {
+            marked[i] |= self.matches_word(word);
+        }
+
+        for phrase in self.terms.iter().filter_map(|term| match term {
+            Term::Phrase(phrase) => Some(phrase),
+            Term::Word { .. } => None,
+        }) {
+            for start in 0..words.len().saturating_sub(phrase.len() - 1) {
+                if phrase.iter().enumerate().all(|(offset, word)| words[start + offset].0 == *word) {
+                    marked[start..start + phrase.len()].fill(true);
+                }
+            }
+        }
+
+        if !marked.contains(&true) {
+            return None;
+        }
+
+        // Consecutive marked words (separated only by spaces) share a single mark
+        let mut output = String::new();
+        let mut last = 0;
+        let mut i = 0;
+
+        while i < words.len() {
+            if !marked[i] {
+                i += 1;
+                continue;
+            }
+
+            let start = words[i].1.start;
+
+            while i + 1 < words.len() && marked[i + 1] && text[words[i].1.end..words[i + 1].1.start].trim().is_empty() {
+                i += 1;
+            }
+
+            let end = words[i].1.end;
+            output.push_str(&text[last..start]);
+            output.push_str(HIGHLIGHT.0);
+            output.push_str(&text[start..end]);
+            output.push_str(HIGHLIGHT.1);
+            last = end;
+            i += 1;
+        }
+
+        output.push_str(&text[last..]);
+        Some(output)
+    }
+}
+
+// Positions of a word in the title and the description of a task
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Postings {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    title: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    description: Vec<usize>,
+}
+
+// Version of a task in the index, with its words so it can be removed without a full scan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexedTask {
+    updated_at: DateTime<FixedOffset>,
+    words: Vec<String>,
+}
+
+// A task found by a search, with its relevance
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub id: usize,
+    pub score: f64,
+}
+
+// Inverted index of the words in the titles and descriptions of the visible tasks. It is a cache:
+// tasks changed since the last search (by their update time) are indexed again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    tasks: BTreeMap<usize, IndexedTask>,
+    words: BTreeMap<String, BTreeMap<usize, Postings>>,
+}
+
+// Implement Default for SearchIndex (an empty index in the current format)
+impl Default for SearchIndex {
+    fn default() -> Self {
+        Self { version: INDEX_VERSION, tasks: BTreeMap::new(), words: BTreeMap::new() }
+    }
+}
+
+// SearchIndex implementation
+impl SearchIndex {
+    // Whether the index was written in the current format
+    pub fn is_current(&self) -> bool {
+        self.version == INDEX_VERSION
+    }
+
+    fn remove(&mut self, id: usize) {
+        let Some(indexed) = self.tasks.remove(&id) else {
+            return;
+        };
+
+        for word in indexed.words {
+            if let Some(postings) = self.words.get_mut(&word) {
+                postings.remove(&id);
+
+                if postings.is_empty() {
+                    self.words.remove(&word);
+                }
+            }
+        }
+    }
+
+    fn insert(&mut self, task: &Task) {
+        let mut postings: BTreeMap<String, Postings> = BTreeMap::new();
+
+        for (position, (word, _)) in words(&task.title).into_iter().enumerate() {
+            postings.entry(word).or_default().title.push(position);
+        }
+
+        for (position, (word, _)) in words(&task.description).into_iter().enumerate() {
+            postings.entry(word).or_default().description.push(position);
+        }
+
+        let indexed = IndexedTask { updated_at: task.updated_at, words: postings.keys().cloned().collect() };
+
+        for (word, positions) in postings {
+            self.words.entry(word).or_default().insert(task.id, positions);
+        }
+
+        self.tasks.insert(task.id, indexed);
+    }
+
+    // Bring the index up to date with the visible tasks (returns whether it changed)
+    pub fn update(&mut self, tasks: &Tasks) -> bool {
+        let stale: Vec<usize> = self
+            .tasks
+            .iter()
+            .filter(|(id, indexed)| tasks.get_task(**id).is_none_or(|task| !task.visible || task.updated_at != indexed.updated_at))
+            .map(|(id, _)| *id)
+            .collect();
+
+        let missing: Vec<&Task> = tasks
+            .iter()
+            .filter(|task| task.visible && self.tasks.get(&task.id).is_none_or(|indexed| indexed.updated_at != task.updated_at))
+            .collect();
+
+        for &id in &stale {
+            self.remove(id);
+        }
+
+        for task in &missing {
+            self.insert(task);
+        }
+
+        !stale.is_empty() || !missing.is_empty()
+    }
+
+    // Postings of a word, or of every word starting with it
+    fn postings(&self, word: &str, prefix: bool) -> Vec<(&String, &BTreeMap<usize, Postings>)> {
+        if prefix {
+            self.words.range(word.to_string()..).take_while(|(indexed, _)| indexed.starts_with(word)).collect()
+        } else {
+            self.words.get_key_value(word).into_iter().collect()
+        }
+    }
+
+    // Number of matches of a term in the title and the description of each task
+    fn term_matches(&self, term: &Term) -> BTreeMap<usize, (f64, f64)> {
+        let mut matches: BTreeMap<usize, (f64, f64)> = BTreeMap::new();
+
+        match term {
+            Term::Word { word, prefix } => {
+                for (indexed, postings) in self.postings(word, *prefix) {
+                    let weight = if indexed == word { 1.0 } else { PREFIX_WEIGHT };
+
+                    for (id, positions) in postings {
+                        let entry = matches.entry(*id).or_default();
+                        entry.0 += weight * positions.title.len() as f64;
+                        entry.1 += weight * positions.description.len() as f64;
+                    }
+                }
+            }
+            Term::Phrase(phrase) => {
+                let Some(first) = self.words.get(&phrase[0]) else {
+                    return matches;
+                };
+
+                // A phrase matches where each word follows the previous one in the same field
+                let count = |id: usize, start: usize, field: fn(&Postings) -> &Vec<usize>| {
+                    phrase[1..].iter().enumerate().all(|(offset, word)| {
+                        self.words
+                            .get(word)
+                            .and_then(|postings| postings.get(&id))
+                            .is_some_and(|positions| field(positions).contains(&(start + offset + 1)))
+                    })
+                };
+
+                for (id, positions) in first {
+                    let title = positions.title.iter().filter(|&&start| count(*id, start, |p| &p.title)).count();
+                    let description = positions.description.iter().filter(|&&start| count(*id, start, |p| &p.description)).count();
+
+                    if title + description > 0 {
+                        matches.insert(*id, (PHRASE_WEIGHT * title as f64, PHRASE_WEIGHT * description as f64));
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+
+    // Tasks containing every term, most relevant first. Rarer terms and matches in the title weigh
+    // more; repeated matches add less and less
+    pub fn search(&self, query: &SearchQuery) -> Vec<SearchHit> {
+        let total = self.tasks.len() as f64;
+        let mut scores: Option<BTreeMap<usize, f64>> = None;
+
+        for term in &query.terms {
+            let matches = self.term_matches(term);
+            let found = matches.len() as f64;
+            let rarity = (1.0 + (total - found + 0.5) / (found + 0.5)).ln();
+
+            let term_scores: BTreeMap<usize, f64> = matches
+                .into_iter()
+                .map(|(id, (title, description))| {
+                    let frequency = TITLE_WEIGHT * title + description;
+                    (id, rarity * frequency / (frequency + 1.0))
+                })
+                .collect();
+
+            scores = Some(match scores {
+                None => term_scores,
+                Some(scores) => scores
+                    .into_iter()
+                    .filter_map(|(id, score)| term_scores.get(&id).map(|term_score| (id, score + term_score)))
+                    .collect(),
+            });
+        }
+
+        let mut hits: Vec<SearchHit> = scores.unwrap_or_default().into_iter().map(|(id, score)| SearchHit { id, score }).collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+        hits
+    }
+}
+
+// Test configuration
+#[cfg(test)]
+mod tests;
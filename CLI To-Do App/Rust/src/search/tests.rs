@@ -0,0 +1,82 @@
+use super::{SearchError, SearchIndex, SearchQuery};
+use crate::tasks::{TaskOptions, Tasks};
+
+// Tasks with Spanish titles and descriptions
+fn sample_tasks() -> Tasks {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Pagar la factura de la luz".into(), "Vence el jueves".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Informe de facturación".into(), "Revisar las facturas de octubre".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Llamar a Íñigo".into(), "Sobre el informe anual".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Comprar pan".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks
+}
+
+// IDs found by a search, most relevant first
+fn search(index: &SearchIndex, terms: &str) -> Vec<usize> {
+    index.search(&SearchQuery::parse(terms).unwrap()).into_iter().map(|hit| hit.id).collect()
+}
+
+#[test]
+// Test: words, phrases and prefixes, ignoring case and accents
+fn test_search_terms() {
+    let mut index = SearchIndex::default();
+    assert!(index.update(&sample_tasks()));
+
+    assert_eq!(search(&index, "INFORME"), [2, 3]);
+    assert_eq!(search(&index, "inigo"), [3]);
+    assert_eq!(search(&index, "facturacion"), [2]);
+    assert_eq!(search(&index, "factura"), [1]);
+    assert_eq!(search(&index, "factur*"), [2, 1]);
+    assert_eq!(search(&index, "\"factura de la luz\""), [1]);
+    assert_eq!(search(&index, "\"luz de la factura\""), Vec::<usize>::new());
+    assert_eq!(search(&index, "informe anual"), [3]);
+    assert_eq!(search(&index, "informe xyz"), Vec::<usize>::new());
+    assert_eq!(SearchQuery::parse("  "), Err(SearchError::Empty));
+    assert_eq!(SearchQuery::parse("\"pago"), Err(SearchError::UnclosedQuote));
+}
+
+#[test]
+// Test: matches in the title rank above matches in the description
+fn test_search_ranking() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Preparar reunión".into(), "Agenda del informe".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Informe trimestral".into(), "".into(), TaskOptions::default()).unwrap();
+    let mut index = SearchIndex::default();
+    index.update(&tasks);
+
+    let hits = index.search(&SearchQuery::parse("informe").unwrap());
+    assert_eq!(hits.iter().map(|hit| hit.id).collect::<Vec<_>>(), [2, 1]);
+    assert!(hits[0].score > hits[1].score);
+}
+
+#[test]
+// Test: the index follows changed, deleted and new tasks
+fn test_search_index_update() {
+    let mut tasks = sample_tasks();
+    let mut index = SearchIndex::default();
+    index.update(&tasks);
+    assert!(!index.update(&tasks));
+
+    tasks.update_task(4, "Comprar leche".into(), "".into()).unwrap();
+    tasks.delete_task(1).unwrap();
+    tasks.add_task_with("Otra factura".into(), "".into(), TaskOptions::default()).unwrap();
+    assert!(index.update(&tasks));
+
+    assert_eq!(search(&index, "pan"), Vec::<usize>::new());
+    assert_eq!(search(&index, "leche"), [4]);
+    assert_eq!(search(&index, "factura"), [5]);
+
+    // The updated index is the same as one built from scratch
+    let mut rebuilt = SearchIndex::default();
+    rebuilt.update(&tasks);
+    assert_eq!(index, rebuilt);
+}
+
+#[test]
+// Test: highlight the matched words (consecutive words share a mark)
+fn test_search_highlight() {
+    let query = SearchQuery::parse("\"factura de la\" ÍÑIGO fact*").unwrap();
+    assert_eq!(query.highlight("Pagar la factura de la luz").as_deref(), Some("Pagar la [factura de la] luz"));
+    assert_eq!(query.highlight("Llamar a Íñigo, facturas").as_deref(), Some("Llamar a [Íñigo], [facturas]"));
+    assert_eq!(query.highlight("Comprar pan"), None);
+}
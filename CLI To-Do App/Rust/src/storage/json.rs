@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use crate::journal::Journal;
+use crate::search::SearchIndex;
+use crate::tasks::Tasks;
+
+use super::store::TaskStore;
+use super::{read_index, read_journal, write_index, write_journal, MigrationReport, Storage, StorageError, StorageLock};
+
+// Tasks stored in a JSON file. The file is locked from the first access until the store is
+// released or dropped, so a whole load-modify-save cycle is protected from concurrent invocations
+#[derive(Debug)]
+pub struct JsonStore {
+    storage: Storage<Tasks>,
+    lock: Option<StorageLock>,
+}
+
+// JsonStore implementation
+impl JsonStore {
+    pub fn new(storage: Storage<Tasks>) -> Self {
+        Self { storage, lock: None }
+    }
+
+    // Take the lock on the file unless it is already held
+    fn lock(&mut self) -> Result<(), StorageError> {
+        if self.lock.is_none() {
+            self.lock = Some(self.storage.lock()?);
+        }
+
+        Ok(())
+    }
+}
+
+// Implement TaskStore for JsonStore
+impl TaskStore for JsonStore {
+    fn load(&mut self) -> Result<Tasks, StorageError> {
+        self.lock()?;
+        Ok(self.storage.download_data()?.unwrap_or_default())
+    }
+
+    fn save(&mut self, tasks: &Tasks) -> Result<(), StorageError> {
+        self.lock()?;
+        self.storage.upload_data(tasks)
+    }
+
+    fn load_journal(&mut self) -> Result<Journal, StorageError> {
+        self.lock()?;
+        read_journal(Path::new(&self.storage.file_name))
+    }
+
+    fn save_journal(&mut self, journal: &Journal) -> Result<(), StorageError> {
+        self.lock()?;
+        write_journal(Path::new(&self.storage.file_name), journal)
+    }
+
+    fn release(&mut self) {
+        self.lock = None;
+    }
+
+    fn load_index(&mut self) -> Result<SearchIndex, StorageError> {
+        read_index(Path::new(&self.storage.file_name))
+    }
+
+    fn save_index(&mut self, index: &SearchIndex) -> Result<(), StorageError> {
+        write_index(Path::new(&self.storage.file_name), index)
+    }
+
+    fn migrate(&mut self, dry_run: bool) -> Result<Option<MigrationReport>, StorageError> {
+        self.lock()?;
+        self.storage.migrate(dry_run)
+    }
+}
@@ -0,0 +1,379 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::dates::now_fixed;
+use crate::journal::Journal;
+use crate::search::SearchIndex;
+use crate::tasks::{Task, TaskStatus, Tasks};
+
+use super::store::TaskStore;
+use super::{read_index, read_journal, write_atomic, write_index, write_journal, Storage, StorageError, StorageLock};
+
+// Events appended since the last snapshot before a new one is taken
+const SNAPSHOT_INTERVAL: u64 = 100;
+
+// Skip the purged flag when it is not set
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+// Change recorded in the event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event")]
+pub enum Event {
+    // A new task (with all its fields)
+    TaskAdded { task: Task },
+    // Any other change to a task (with all its fields after the change)
+    TaskUpdated { task: Task },
+    // Only the status (and completion date) of a task changed
+    StatusChanged { id: usize, from: TaskStatus, to: TaskStatus, completed_at: DateTime<FixedOffset> },
+    // A task was deleted (hidden like the CLI does, or removed from the list when purged)
+    TaskDeleted {
+        id: usize,
+        #[serde(default, skip_serializing_if = "is_false")]
+        purged: bool,
+    },
+}
+
+// Event with its position in the log and the moment it happened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub seq: u64,
+    pub at: DateTime<FixedOffset>,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+// Event implementation
+impl Event {
+    // ID of the task the event is about
+    pub fn task_id(&self) -> usize {
+        match self {
+            Event::TaskAdded { task } | Event::TaskUpdated { task } => task.id,
+            Event::StatusChanged { id, .. } | Event::TaskDeleted { id, .. } => *id,
+        }
+    }
+
+    // Event stringify method
+    pub fn stringify(&self) -> String {
+        match self {
+            Event::TaskAdded { task } => format!("Tarea creada: \"{}\"", task.title),
+            Event::TaskUpdated { task } => format!("Tarea actualizada: \"{}\"", task.title),
+            Event::StatusChanged { from, to, .. } => format!("Estado: {} -> {}", from.stringify(), to.stringify()),
+            Event::TaskDeleted { purged: false, .. } => "Tarea eliminada".to_string(),
+            Event::TaskDeleted { purged: true, .. } => "Tarea eliminada definitivamente".to_string(),
+        }
+    }
+
+    // Describe the change from `previous` to `task` with the smallest event that reproduces it
+    fn between(previous: &Task, task: &Task) -> Event {
+        let mut candidates = Vec::new();
+
+        if previous.status != task.status {
+            candidates.push(Event::StatusChanged { id: task.id, from: previous.status, to: task.status, completed_at: task.completed_at });
+        }
+
+        if previous.visible && !task.visible {
+            candidates.push(Event::TaskDeleted { id: task.id, purged: false });
+        }
+
+        for event in candidates {
+            let mut candidate = previous.clone();
+            event.apply_to(&mut candidate, task.updated_at);
+
+            if candidate == *task {
+                return event;
+            }
+        }
+
+        Event::TaskUpdated { task: task.clone() }
+    }
+
+    // Apply a status change or a deletion to a single task
+    fn apply_to(&self, task: &mut Task, at: DateTime<FixedOffset>) {
+        match self {
+            Event::StatusChanged { to, completed_at, .. } => {
+                task.status = *to;
+                task.completed_at = *completed_at;
+            }
+            Event::TaskDeleted { .. } => task.visible = false,
+            Event::TaskAdded { .. } | Event::TaskUpdated { .. } => return,
+        }
+
+        task.updated_at = at;
+    }
+}
+
+// LogEntry implementation
+impl LogEntry {
+    // Replay the event on the task list (false when it does not fit the current state)
+    fn apply(&self, tasks: &mut Tasks) -> bool {
+        let id = self.event.task_id();
+
+        match &self.event {
+            Event::TaskAdded { task } if tasks.get_task(id).is_none() => tasks.put_task(task.clone()),
+            Event::TaskUpdated { task } if tasks.get_task(id).is_some() => tasks.put_task(task.clone()),
+            Event::TaskDeleted { purged: true, .. } => return tasks.remove_task(id).is_some(),
+            Event::StatusChanged { .. } | Event::TaskDeleted { .. } => {
+                let Some(mut task) = tasks.get_task(id).cloned() else {
+                    return false;
+                };
+
+                self.event.apply_to(&mut task, self.at);
+                tasks.put_task(task);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+// Events that turn `previous` into `tasks` (with seq 0, numbered when they are appended)
+fn diff(previous: &Tasks, tasks: &Tasks) -> Vec<LogEntry> {
+    let mut entries = Vec::new();
+
+    for task in tasks.iter() {
+        let event = match previous.get_task(task.id) {
+            None => Event::TaskAdded { task: task.clone() },
+            Some(old) if old != task => Event::between(old, task),
+            Some(_) => continue,
+        };
+
+        entries.push(LogEntry { seq: 0, at: task.updated_at, event });
+    }
+
+    for task in previous.iter().filter(|task| tasks.get_task(task.id).is_none()) {
+        entries.push(LogEntry { seq: 0, at: now_fixed(), event: Event::TaskDeleted { id: task.id, purged: true } });
+    }
+
+    entries
+}
+
+// State of the task list after the first `seq` events, so loading does not replay the whole log
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Snapshot {
+    seq: u64,
+    tasks: Tasks,
+}
+
+// Path of the snapshot of an event log
+pub fn snapshot_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".snapshot");
+    PathBuf::from(name)
+}
+
+// Events read from the log, along with the length of the valid part of the file
+fn read_entries(path: &Path) -> Result<(Vec<LogEntry>, u64), StorageError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(StorageError::Io(e)),
+    };
+
+    let mut entries = Vec::new();
+    let mut valid = 0;
+
+    for (number, line) in content.split_inclusive('\n').enumerate() {
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            // A last line without newline is an append interrupted by a crash: it is ignored
+            // (and overwritten by the next append)
+            Err(_) if !line.ends_with('\n') => break,
+            Err(_) => return Err(StorageError::InvalidEvent(number + 1)),
+        }
+
+        valid += line.len() as u64;
+    }
+
+    Ok((entries, valid))
+}
+
+// Current state rebuilt from the snapshot and the log
+#[derive(Debug)]
+struct State {
+    tasks: Tasks,
+    seq: u64,
+    snapshot_seq: u64,
+    log_len: u64,
+}
+
+// Tasks stored as an append-only log of events (one JSON object per line). The current state is
+// rebuilt by replaying the log on top of the latest snapshot (<file>.snapshot), which is taken
+// every SNAPSHOT_INTERVAL events. Like JsonStore, the log is locked from the first access until
+// the store is released or dropped
+#[derive(Debug)]
+pub struct LogStore {
+    storage: Storage<Tasks>,
+    lock: Option<StorageLock>,
+    state: Option<State>,
+    snapshot_interval: u64,
+}
+
+// LogStore implementation
+impl LogStore {
+    pub fn new(storage: Storage<Tasks>) -> Self {
+        Self { storage, lock: None, state: None, snapshot_interval: SNAPSHOT_INTERVAL }
+    }
+
+    fn path(&self) -> &Path {
+        Path::new(&self.storage.file_name)
+    }
+
+    // Take the lock and rebuild the current state unless it was already done
+    fn read(&mut self) -> Result<&mut State, StorageError> {
+        if self.lock.is_none() {
+            self.lock = Some(self.storage.lock()?);
+        }
+
+        if self.state.is_none() {
+            let snapshot = match fs::read_to_string(snapshot_path(self.path())) {
+                Ok(content) => serde_json::from_str::<Snapshot>(&content).map_err(StorageError::Serde)?,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Snapshot::default(),
+                Err(e) => return Err(StorageError::Io(e)),
+            };
+
+            let (entries, log_len) = read_entries(self.path())?;
+            let mut state = State { tasks: snapshot.tasks, seq: snapshot.seq, snapshot_seq: snapshot.seq, log_len };
+
+            // Events already included in the snapshot are skipped
+            for (number, entry) in entries.iter().enumerate().filter(|(_, entry)| entry.seq > snapshot.seq) {
+                if !entry.apply(&mut state.tasks) {
+                    return Err(StorageError::InvalidEvent(number + 1));
+                }
+
+                state.seq = entry.seq;
+            }
+
+            self.state = Some(state);
+        }
+
+        Ok(self.state.as_mut().expect("state was just loaded"))
+    }
+
+    // Append events to the log and apply them to the current state
+    fn append(&mut self, mut entries: Vec<LogEntry>) -> Result<(), StorageError> {
+        let path = self.path().to_path_buf();
+        let state = self.read()?;
+        let mut content = String::new();
+
+        for entry in &mut entries {
+            state.seq += 1;
+            entry.seq = state.seq;
+            content.push_str(&serde_json::to_string(entry).map_err(StorageError::Serde)?);
+            content.push('\n');
+        }
+
+        let mut file = fs::OpenOptions::new().create(true).write(true).truncate(false).open(&path).map_err(StorageError::Io)?;
+
+        // Drop the remains of an interrupted append before writing after them
+        file.set_len(state.log_len).map_err(StorageError::Io)?;
+        file.seek(SeekFrom::End(0)).map_err(StorageError::Io)?;
+        file.write_all(content.as_bytes()).map_err(StorageError::Io)?;
+        file.sync_data().map_err(StorageError::Io)?;
+        state.log_len += content.len() as u64;
+
+        for entry in &entries {
+            entry.apply(&mut state.tasks);
+        }
+
+        Ok(())
+    }
+
+    // Write the current state as the latest snapshot
+    fn snapshot(&mut self) -> Result<(), StorageError> {
+        let path = snapshot_path(self.path());
+        let state = self.read()?;
+        let snapshot = Snapshot { seq: state.seq, tasks: state.tasks.clone() };
+        let content = serde_json::to_string(&snapshot).map_err(StorageError::Serde)?;
+
+        write_atomic(&path, content.as_bytes(), false, |file, content| file.write_all(content)).map_err(StorageError::Io)?;
+        state.snapshot_seq = state.seq;
+
+        Ok(())
+    }
+}
+
+// Implement TaskStore for LogStore
+impl TaskStore for LogStore {
+    fn load(&mut self) -> Result<Tasks, StorageError> {
+        Ok(self.read()?.tasks.clone())
+    }
+
+    // Only the differences with the current state are appended
+    fn save(&mut self, tasks: &Tasks) -> Result<(), StorageError> {
+        let entries = diff(&self.read()?.tasks, tasks);
+        self.append(entries)?;
+
+        let interval = self.snapshot_interval;
+        let state = self.read()?;
+
+        // The ID counter is not part of any event: when it is ahead of the replayed tasks (e.g.
+        // tasks copied from another backend after some were removed) a snapshot keeps it
+        if state.tasks.next_id() != tasks.next_id() {
+            state.tasks = tasks.clone();
+            return self.snapshot();
+        }
+
+        if state.seq - state.snapshot_seq >= interval {
+            self.snapshot()?;
+        }
+
+        Ok(())
+    }
+
+    fn load_journal(&mut self) -> Result<Journal, StorageError> {
+        self.read()?;
+        read_journal(self.path())
+    }
+
+    fn save_journal(&mut self, journal: &Journal) -> Result<(), StorageError> {
+        self.read()?;
+        write_journal(self.path(), journal)
+    }
+
+    // The state is rebuilt after the next lock, as other invocations may append events meanwhile
+    fn release(&mut self) {
+        self.lock = None;
+        self.state = None;
+    }
+
+    fn load_index(&mut self) -> Result<SearchIndex, StorageError> {
+        read_index(self.path())
+    }
+
+    fn save_index(&mut self, index: &SearchIndex) -> Result<(), StorageError> {
+        write_index(self.path(), index)
+    }
+
+    fn events(&mut self) -> Result<Option<Vec<LogEntry>>, StorageError> {
+        self.read()?;
+        Ok(Some(read_entries(self.path())?.0))
+    }
+
+    // Take a snapshot and empty the log (the history before it is discarded)
+    fn compact(&mut self) -> Result<Option<usize>, StorageError> {
+        self.snapshot()?;
+
+        let path = self.path().to_path_buf();
+        let removed = read_entries(&path)?.0.len();
+
+        write_atomic(&path, b"", false, |file, content| file.write_all(content)).map_err(StorageError::Io)?;
+        self.read()?.log_len = 0;
+
+        Ok(Some(removed))
+    }
+}
+
+// Alternative function for testing: take snapshots more often
+#[cfg(test)]
+impl LogStore {
+    pub fn with_snapshot_interval(mut self, snapshot_interval: u64) -> Self {
+        self.snapshot_interval = snapshot_interval;
+        self
+    }
+}
@@ -0,0 +1,65 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::journal::Journal;
+use crate::tasks::{Task, Tasks};
+
+use super::store::TaskStore;
+use super::StorageError;
+
+// Tasks kept in memory only (nothing is persisted), used by tests (it is not a backend that can
+// be selected)
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    tasks: Tasks,
+    journal: Journal,
+}
+
+// Implement TaskStore for MemoryStore
+impl TaskStore for MemoryStore {
+    fn load(&mut self) -> Result<Tasks, StorageError> {
+        Ok(self.tasks.clone())
+    }
+
+    fn save(&mut self, tasks: &Tasks) -> Result<(), StorageError> {
+        self.tasks = tasks.clone();
+        Ok(())
+    }
+
+    fn load_journal(&mut self) -> Result<Journal, StorageError> {
+        Ok(self.journal.clone())
+    }
+
+    fn save_journal(&mut self, journal: &Journal) -> Result<(), StorageError> {
+        self.journal = journal.clone();
+        Ok(())
+    }
+
+    fn put(&mut self, task: Task) -> Result<(), StorageError> {
+        self.tasks.put_task(task);
+        Ok(())
+    }
+
+    fn delete(&mut self, id: usize) -> Result<bool, StorageError> {
+        Ok(self.tasks.remove_task(id).is_some())
+    }
+
+    fn partial_updates(&self) -> bool {
+        true
+    }
+}
+
+// Implement Deref for MemoryStore (direct access to the stored tasks)
+impl Deref for MemoryStore {
+    type Target = Tasks;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tasks
+    }
+}
+
+// Implement DerefMut for MemoryStore (allows preparing the stored tasks)
+impl DerefMut for MemoryStore {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tasks
+    }
+}
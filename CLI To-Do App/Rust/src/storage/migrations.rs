@@ -0,0 +1,77 @@
+use serde_json::{json, Map, Value};
+
+use super::StorageError;
+
+// Version of the format written by this application
+pub const CURRENT_VERSION: u64 = 2;
+
+// Migration from one version to the next one
+pub struct Migration {
+    pub description: &'static str,
+    pub apply: fn(Value) -> Result<Value, StorageError>,
+}
+
+// Migrations in order: MIGRATIONS[n] upgrades version n to version n + 1
+pub const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [
+    Migration { description: "v0 -> v1: lista de tareas a objeto con NextID y Tasks", apply: v0_to_v1 },
+    Migration { description: "v1 -> v2: objeto con versión (version, next_id, tasks)", apply: v1_to_v2 },
+];
+
+// Detect the version of stored data:
+//   v0: bare array of tasks (first versions and the Go app)
+//   v1: {"NextID": n, "Tasks": [...]}
+//   v2 and later: {"version": N, ...}
+pub fn detect_version(value: &Value) -> Result<u64, StorageError> {
+    match value {
+        Value::Array(_) => Ok(0),
+        Value::Object(map) => match map.get("version") {
+            Some(version) => version.as_u64().ok_or(StorageError::InvalidFormat),
+            None if map.contains_key("Tasks") => Ok(1),
+            None => Err(StorageError::InvalidFormat),
+        },
+        _ => Err(StorageError::InvalidFormat),
+    }
+}
+
+// Upgrade stored data to the current version, one step at a time (returns the original version)
+pub fn migrate(mut value: Value) -> Result<(Value, u64), StorageError> {
+    let version = detect_version(&value)?;
+
+    if version > CURRENT_VERSION {
+        return Err(StorageError::UnsupportedVersion(version));
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        value = (migration.apply)(value)?;
+    }
+
+    Ok((value, version))
+}
+
+// Take an object out of a value
+fn into_object(value: Value) -> Result<Map<String, Value>, StorageError> {
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => Err(StorageError::InvalidFormat),
+    }
+}
+
+// v0 -> v1: wrap the array, the counter continues after the highest ID
+fn v0_to_v1(value: Value) -> Result<Value, StorageError> {
+    let Value::Array(tasks) = value else {
+        return Err(StorageError::InvalidFormat);
+    };
+
+    let max_id = tasks.iter().filter_map(|task| task.get("ID").and_then(Value::as_u64)).max().unwrap_or(0);
+
+    Ok(json!({ "NextID": max_id + 1, "Tasks": tasks }))
+}
+
+// v1 -> v2: versioned envelope with lowercase keys (task fields are unchanged)
+fn v1_to_v2(value: Value) -> Result<Value, StorageError> {
+    let mut map = into_object(value)?;
+    let tasks = map.remove("Tasks").ok_or(StorageError::InvalidFormat)?;
+    let next_id = map.remove("NextID").unwrap_or(json!(1));
+
+    Ok(json!({ "version": 2, "next_id": next_id, "tasks": tasks }))
+}
@@ -2,12 +2,44 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::fmt;
 use std::fs;
-use std::io;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::journal::Journal;
+use crate::search::SearchIndex;
+use crate::tasks::TaskError;
+
+mod json;
+mod log;
+#[cfg(test)]
+mod memory;
+mod migrations;
+mod sqlite;
+mod store;
+
+pub use json::JsonStore;
+pub use log::{LogEntry, LogStore};
+#[cfg(test)]
+pub use memory::MemoryStore;
+pub use migrations::CURRENT_VERSION;
+pub use sqlite::SqliteStore;
+pub use store::{open_store, StoreKind, TaskStore};
+
+// Time to wait for another invocation to release the data file
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Pause between attempts to acquire the lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
 
 // Storage struct
 #[derive(Debug, Clone)]
 pub struct Storage<T> {
     pub file_name: String,
+    pub backup: bool,
+    pub lock_timeout: Duration,
     _marker: std::marker::PhantomData<T>,
 }
 
@@ -17,6 +49,12 @@ pub enum StorageError {
     EmptyFileName,
     Io(io::Error),
     Serde(serde_json::Error),
+    Locked(PathBuf, Duration),
+    InvalidFormat,
+    UnsupportedVersion(u64),
+    Sqlite(rusqlite::Error),
+    InvalidEvent(usize),
+    Tasks(TaskError),
 }
 
 // Implement fmt::Display for StorageError
@@ -26,6 +64,21 @@ impl fmt::Display for StorageError {
             StorageError::EmptyFileName => write!(f, "El nombre del archivo no puede estar vacío"),
             StorageError::Io(e) => write!(f, "{}", e),
             StorageError::Serde(e) => write!(f, "{}", e),
+            StorageError::Sqlite(e) => write!(f, "{}", e),
+            StorageError::InvalidFormat => write!(f, "Formato de archivo no reconocido"),
+            StorageError::InvalidEvent(line) => write!(f, "El historial de eventos no es válido (línea {})", line),
+            StorageError::Tasks(e) => write!(f, "Las tareas guardadas no son válidas: {}", e),
+            StorageError::UnsupportedVersion(version) => write!(
+                f,
+                "La versión {} del archivo es más nueva que la admitida ({}); actualice la aplicación",
+                version, CURRENT_VERSION
+            ),
+            StorageError::Locked(path, timeout) => write!(
+                f,
+                "El archivo de tareas está siendo usado por otra ejecución (no se pudo bloquear {} en {:.1} s)",
+                path.display(),
+                timeout.as_secs_f64()
+            ),
         }
     }
 }
@@ -33,10 +86,173 @@ impl fmt::Display for StorageError {
 // Storage implementation (T can be any type)
 impl<T> Storage<T> {
     pub fn new(file_name: String) -> Self {
-        Self { file_name, _marker: std::marker::PhantomData }
+        Self { file_name, backup: false, lock_timeout: DEFAULT_LOCK_TIMEOUT, _marker: std::marker::PhantomData }
+    }
+
+    // Keep a copy of the previous version of the file (<file>.bak) on every upload
+    pub fn with_backup(mut self, backup: bool) -> Self {
+        self.backup = backup;
+        self
+    }
+
+    // Maximum time to wait for the lock held by another invocation
+    pub fn with_lock_timeout(mut self, lock_timeout: Duration) -> Self {
+        self.lock_timeout = lock_timeout;
+        self
+    }
+
+    // Take an exclusive lock on the data file, waiting up to the lock timeout. The lock is held on
+    // a sidecar file (<file>.lock), since uploads replace the data file itself, and it is released
+    // when the returned guard is dropped
+    pub fn lock(&self) -> Result<StorageLock, StorageError> {
+        if self.file_name.is_empty() {
+            return Err(StorageError::EmptyFileName);
+        }
+
+        let path = lock_path(Path::new(&self.file_name));
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(StorageError::Io)?;
+        let started = Instant::now();
+
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(StorageLock { _file: file }),
+                Err(fs::TryLockError::WouldBlock) if started.elapsed() < self.lock_timeout => {
+                    thread::sleep(LOCK_RETRY_INTERVAL.min(self.lock_timeout.saturating_sub(started.elapsed())));
+                }
+                Err(fs::TryLockError::WouldBlock) => return Err(StorageError::Locked(path, self.lock_timeout)),
+                Err(fs::TryLockError::Error(e)) => return Err(StorageError::Io(e)),
+            }
+        }
+    }
+}
+
+// Exclusive lock on a data file (released when dropped)
+#[derive(Debug)]
+pub struct StorageLock {
+    _file: fs::File,
+}
+
+// Path of the backup copy of a data file
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+// Path of the lock file of a data file
+pub fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".lock");
+    PathBuf::from(name)
+}
+
+// Path of the undo/redo journal kept next to a data file
+pub fn journal_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".journal");
+    PathBuf::from(name)
+}
+
+// Read the journal kept next to a data file (empty when it does not exist)
+fn read_journal(path: &Path) -> Result<Journal, StorageError> {
+    match fs::read_to_string(journal_path(path)) {
+        Ok(content) => serde_json::from_str(&content).map_err(StorageError::Serde),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Journal::default()),
+        Err(e) => Err(StorageError::Io(e)),
     }
 }
 
+// Write the journal kept next to a data file
+fn write_journal(path: &Path, journal: &Journal) -> Result<(), StorageError> {
+    let content = serde_json::to_string(journal).map_err(StorageError::Serde)?;
+    write_atomic(&journal_path(path), content.as_bytes(), false, |file, content| file.write_all(content)).map_err(StorageError::Io)
+}
+
+// Path of the search index kept next to a data file
+pub fn index_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".index");
+    PathBuf::from(name)
+}
+
+// Read the search index kept next to a data file. It is only a cache, so an index that is missing,
+// damaged or in an older format is replaced by an empty one (and rebuilt)
+fn read_index(path: &Path) -> Result<SearchIndex, StorageError> {
+    match fs::read_to_string(index_path(path)) {
+        Ok(content) => Ok(serde_json::from_str(&content).ok().filter(SearchIndex::is_current).unwrap_or_default()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(SearchIndex::default()),
+        Err(e) => Err(StorageError::Io(e)),
+    }
+}
+
+// Write the search index kept next to a data file
+fn write_index(path: &Path, index: &SearchIndex) -> Result<(), StorageError> {
+    let content = serde_json::to_string(index).map_err(StorageError::Serde)?;
+    write_atomic(&index_path(path), content.as_bytes(), false, |file, content| file.write_all(content)).map_err(StorageError::Io)
+}
+
+// Path of the temporary file used while writing, in the same directory so the rename is atomic
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, process::id()))
+}
+
+// Write a file atomically: the content goes to a temporary file that is synced to disk and then
+// renamed over the original, so a crash or a full disk never leaves a truncated file behind
+fn write_atomic<W>(path: &Path, content: &[u8], backup: bool, write: W) -> io::Result<()>
+where
+    W: FnOnce(&mut fs::File, &[u8]) -> io::Result<()>,
+{
+    let temp = temp_path(path);
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp)?;
+        write(&mut file, content)?;
+        file.sync_all()?;
+
+        if backup && path.exists() {
+            fs::copy(path, backup_path(path))?;
+        }
+
+        fs::rename(&temp, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+        return result;
+    }
+
+    // Persist the rename itself (only possible on Unix, where directories can be opened)
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+        fs::File::open(dir)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+// Stored data with its format version
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    version: u64,
+    #[serde(flatten)]
+    data: &'a T,
+}
+
+// Result of migrating the data file to the current version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from: u64,
+    pub to: u64,
+    pub steps: Vec<&'static str>,
+}
+
 // Storage implementation (T can be serialized/deserialized)
 impl<T> Storage<T>
 where
@@ -48,13 +264,20 @@ where
             return Err(StorageError::EmptyFileName);
         }
 
-        let content = serde_json::to_string_pretty(data).map_err(StorageError::Serde)?;
-        fs::write(&self.file_name, content).map_err(StorageError::Io)?; 
+        let envelope = Envelope { version: CURRENT_VERSION, data };
+        let content = serde_json::to_string_pretty(&envelope).map_err(StorageError::Serde)?;
+        write_atomic(Path::new(&self.file_name), content.as_bytes(), self.backup, |file, content| file.write_all(content))
+            .map_err(StorageError::Io)?;
         Ok(())
     }
 
-    // Download tasks data from JSON file
+    // Download tasks data from JSON file (older versions are migrated in memory)
     pub fn download_data(&self) -> Result<Option<T>, StorageError> { 
+        Ok(self.download_versioned()?.map(|(data, _)| data))
+    }
+
+    // Download data along with the version the file was stored in
+    fn download_versioned(&self) -> Result<Option<(T, u64)>, StorageError> {
         if self.file_name.is_empty() {
             return Err(StorageError::EmptyFileName);
         }
@@ -65,8 +288,30 @@ where
             Err(e) => return Err(StorageError::Io(e)),
         };
 
-        let data: T = serde_json::from_str(&content).map_err(StorageError::Serde)?;
-        Ok(Some(data))
+        let value = serde_json::from_str(&content).map_err(StorageError::Serde)?;
+        let (mut value, version) = migrations::migrate(value)?;
+
+        if let Some(map) = value.as_object_mut() {
+            map.remove("version");
+        }
+
+        let data: T = serde_json::from_value(value).map_err(StorageError::Serde)?;
+        Ok(Some((data, version)))
+    }
+
+    // Upgrade the file to the current version (with `dry_run` only checks that it can be migrated)
+    pub fn migrate(&self, dry_run: bool) -> Result<Option<MigrationReport>, StorageError> {
+        let Some((data, from)) = self.download_versioned()? else {
+            return Ok(None);
+        };
+
+        if !dry_run && from < CURRENT_VERSION {
+            self.upload_data(&data)?;
+        }
+
+        let steps = migrations::MIGRATIONS[from as usize..].iter().map(|migration| migration.description).collect();
+
+        Ok(Some(MigrationReport { from, to: CURRENT_VERSION, steps }))
     }
 }
 
@@ -0,0 +1,382 @@
+use chrono::{DateTime, FixedOffset};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension, Row};
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::journal::Journal;
+use crate::search::SearchIndex;
+use crate::tasks::{Recurrence, Task, TaskPriority, TaskStatus, Tasks};
+
+use super::store::{put_changes, TaskStore};
+use super::{read_index, write_index, StorageError};
+
+// Tables and indexes (created on first use). Tags and dependencies get their own tables so they
+// can be indexed and queried
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value INTEGER NOT NULL
+    );
+
+    INSERT OR IGNORE INTO meta (key, value) VALUES ('schema_version', 1), ('next_id', 1);
+
+    CREATE TABLE IF NOT EXISTS tasks (
+        id INTEGER PRIMARY KEY,
+        uuid TEXT,
+        title TEXT NOT NULL,
+        description TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        completed_at TEXT NOT NULL,
+        due_at TEXT,
+        status INTEGER NOT NULL,
+        priority INTEGER NOT NULL,
+        project TEXT NOT NULL,
+        parent_id INTEGER,
+        recurrence TEXT,
+        visible INTEGER NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS task_tags (
+        task_id INTEGER NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (task_id, tag)
+    );
+
+    CREATE TABLE IF NOT EXISTS task_dependencies (
+        task_id INTEGER NOT NULL,
+        depends_on INTEGER NOT NULL,
+        PRIMARY KEY (task_id, depends_on)
+    );
+
+    CREATE TABLE IF NOT EXISTS journal (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        content TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks (status);
+    CREATE INDEX IF NOT EXISTS idx_tasks_due_at ON tasks (due_at);
+    CREATE INDEX IF NOT EXISTS idx_task_tags_tag ON task_tags (tag);
+";
+
+// Implement ToSql for TaskStatus (stored as an integer, like in the JSON file)
+impl ToSql for TaskStatus {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(*self as u8))
+    }
+}
+
+// Implement FromSql for TaskStatus
+impl FromSql for TaskStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_i64()? {
+            0 => Ok(TaskStatus::Pending),
+            1 => Ok(TaskStatus::InProgress),
+            2 => Ok(TaskStatus::Completed),
+            other => Err(FromSqlError::OutOfRange(other)),
+        }
+    }
+}
+
+// Implement ToSql for TaskPriority (stored as an integer, like in the JSON file)
+impl ToSql for TaskPriority {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(*self as u8))
+    }
+}
+
+// Implement FromSql for TaskPriority
+impl FromSql for TaskPriority {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_i64()? {
+            0 => Ok(TaskPriority::Low),
+            1 => Ok(TaskPriority::Medium),
+            2 => Ok(TaskPriority::High),
+            3 => Ok(TaskPriority::Urgent),
+            other => Err(FromSqlError::OutOfRange(other)),
+        }
+    }
+}
+
+// Implement FromSql for Recurrence (stored as text, like in the JSON file)
+impl FromSql for Recurrence {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_str()?.parse().map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+// Read a task from a row of the tasks table (tags and dependencies are read separately)
+fn task_from_row(row: &Row) -> rusqlite::Result<Task> {
+    let uuid: Option<String> = row.get("uuid")?;
+
+    Ok(Task {
+        id: row.get("id")?,
+        uuid: uuid.and_then(|uuid| Uuid::parse_str(&uuid).ok()),
+        title: row.get("title")?,
+        description: row.get("description")?,
+        created_at: row.get::<_, DateTime<FixedOffset>>("created_at")?,
+        updated_at: row.get::<_, DateTime<FixedOffset>>("updated_at")?,
+        completed_at: row.get::<_, DateTime<FixedOffset>>("completed_at")?,
+        due_at: row.get::<_, Option<DateTime<FixedOffset>>>("due_at")?,
+        status: row.get("status")?,
+        priority: row.get("priority")?,
+        tags: BTreeSet::new(),
+        project: row.get("project")?,
+        parent: row.get("parent_id")?,
+        depends_on: BTreeSet::new(),
+        recurrence: row.get("recurrence")?,
+        visible: row.get("visible")?,
+    })
+}
+
+// Write a task with its tags and dependencies, replacing the stored version
+fn write_task(connection: &Connection, task: &Task) -> rusqlite::Result<()> {
+    remove_task(connection, task.id)?;
+
+    connection.execute(
+        "INSERT INTO tasks (id, uuid, title, description, created_at, updated_at, completed_at, due_at,
+                            status, priority, project, parent_id, recurrence, visible)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            task.id,
+            task.uuid.map(|uuid| uuid.to_string()),
+            task.title,
+            task.description,
+            task.created_at,
+            task.updated_at,
+            task.completed_at,
+            task.due_at,
+            task.status,
+            task.priority,
+            task.project,
+            task.parent,
+            task.recurrence.as_ref().map(|recurrence| recurrence.to_string()),
+            task.visible,
+        ],
+    )?;
+
+    for tag in &task.tags {
+        connection.execute("INSERT INTO task_tags (task_id, tag) VALUES (?1, ?2)", params![task.id, tag])?;
+    }
+
+    for dependency in &task.depends_on {
+        connection.execute(
+            "INSERT INTO task_dependencies (task_id, depends_on) VALUES (?1, ?2)",
+            params![task.id, dependency],
+        )?;
+    }
+
+    // IDs are never reused, so the counter only moves forward
+    connection.execute("UPDATE meta SET value = max(value, ?1) WHERE key = 'next_id'", params![task.id + 1])?;
+
+    Ok(())
+}
+
+// Remove a task with its tags and dependencies (returns whether it existed)
+fn remove_task(connection: &Connection, id: usize) -> rusqlite::Result<bool> {
+    connection.execute("DELETE FROM task_tags WHERE task_id = ?1", params![id])?;
+    connection.execute("DELETE FROM task_dependencies WHERE task_id = ?1", params![id])?;
+    Ok(connection.execute("DELETE FROM tasks WHERE id = ?1", params![id])? > 0)
+}
+
+// Tasks stored in an SQLite database. Loading starts a write transaction that keeps other
+// invocations waiting until the changes are saved (or the store is released or dropped), and only
+// the tasks that changed are written
+pub struct SqliteStore {
+    connection: Connection,
+    path: PathBuf,
+    lock_timeout: Duration,
+}
+
+// SqliteStore implementation
+impl SqliteStore {
+    // Open (or create) the database
+    pub fn open(file_name: &str, lock_timeout: Duration) -> Result<Self, StorageError> {
+        if file_name.is_empty() {
+            return Err(StorageError::EmptyFileName);
+        }
+
+        let connection = Connection::open(file_name).map_err(StorageError::Sqlite)?;
+        let store = Self { connection, path: PathBuf::from(file_name), lock_timeout };
+
+        store.connection.busy_timeout(lock_timeout).map_err(StorageError::Sqlite)?;
+        store.connection.execute_batch(SCHEMA).map_err(|e| store.error(e))?;
+
+        Ok(store)
+    }
+
+    // Report a busy database as a lock held by another invocation
+    fn error(&self, e: rusqlite::Error) -> StorageError {
+        match e.sqlite_error_code() {
+            Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) => StorageError::Locked(self.path.clone(), self.lock_timeout),
+            _ => StorageError::Sqlite(e),
+        }
+    }
+
+    // Start a write transaction unless one is already open (returns whether it was started)
+    fn begin(&mut self) -> Result<bool, StorageError> {
+        if !self.connection.is_autocommit() {
+            return Ok(false);
+        }
+
+        self.connection.execute_batch("BEGIN IMMEDIATE").map_err(|e| self.error(e))?;
+        Ok(true)
+    }
+
+    // Commit the open transaction
+    fn commit(&mut self) -> Result<(), StorageError> {
+        if self.connection.is_autocommit() {
+            return Ok(());
+        }
+
+        self.connection.execute_batch("COMMIT").map_err(|e| self.error(e))
+    }
+
+    // Run writes in the open transaction, or in their own transaction when none is open
+    fn write<T>(&mut self, f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, StorageError> {
+        let started = self.begin()?;
+        let result = f(&self.connection).map_err(|e| self.error(e));
+
+        if started {
+            match result {
+                Ok(_) => self.commit()?,
+                Err(_) => {
+                    let _ = self.connection.execute_batch("ROLLBACK");
+                }
+            }
+        }
+
+        result
+    }
+
+    // Read the stored tasks and the next ID counter
+    fn read_rows(&self) -> rusqlite::Result<(Vec<Task>, usize)> {
+        let mut tags: HashMap<usize, BTreeSet<String>> = HashMap::new();
+        let mut statement = self.connection.prepare("SELECT task_id, tag FROM task_tags")?;
+        let rows = statement.query_map([], |row| Ok((row.get::<_, usize>(0)?, row.get::<_, String>(1)?)))?;
+
+        for row in rows {
+            let (id, tag) = row?;
+            tags.entry(id).or_default().insert(tag);
+        }
+
+        let mut dependencies: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+        let mut statement = self.connection.prepare("SELECT task_id, depends_on FROM task_dependencies")?;
+        let rows = statement.query_map([], |row| Ok((row.get::<_, usize>(0)?, row.get::<_, usize>(1)?)))?;
+
+        for row in rows {
+            let (id, dependency) = row?;
+            dependencies.entry(id).or_default().insert(dependency);
+        }
+
+        let mut statement = self.connection.prepare("SELECT * FROM tasks ORDER BY id")?;
+        let tasks = statement
+            .query_map([], task_from_row)?
+            .map(|task| {
+                task.map(|mut task| {
+                    task.tags = tags.remove(&task.id).unwrap_or_default();
+                    task.depends_on = dependencies.remove(&task.id).unwrap_or_default();
+                    task
+                })
+            })
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let next_id = self.connection.query_row("SELECT value FROM meta WHERE key = 'next_id'", [], |row| row.get(0))?;
+
+        Ok((tasks, next_id))
+    }
+
+    // Read all tasks
+    fn read_tasks(&self) -> Result<Tasks, StorageError> {
+        let (tasks, next_id) = self.read_rows().map_err(|e| self.error(e))?;
+        Tasks::from_parts(tasks, next_id).map_err(StorageError::Tasks)
+    }
+}
+
+// Implement TaskStore for SqliteStore
+impl TaskStore for SqliteStore {
+    fn load(&mut self) -> Result<Tasks, StorageError> {
+        self.begin()?;
+        self.read_tasks()
+    }
+
+    fn save(&mut self, tasks: &Tasks) -> Result<(), StorageError> {
+        self.write(|connection| {
+            connection.execute_batch("DELETE FROM task_tags; DELETE FROM task_dependencies; DELETE FROM tasks;")?;
+
+            for task in tasks.iter() {
+                write_task(connection, task)?;
+            }
+
+            connection.execute("UPDATE meta SET value = ?1 WHERE key = 'next_id'", params![tasks.next_id()])?;
+            Ok(())
+        })?;
+
+        self.commit()
+    }
+
+    // The journal is kept as JSON in a single row, written in the same transaction as the tasks
+    fn load_journal(&mut self) -> Result<Journal, StorageError> {
+        let content: Option<String> = self
+            .connection
+            .query_row("SELECT content FROM journal WHERE id = 1", [], |row| row.get(0))
+            .optional()
+            .map_err(|e| self.error(e))?;
+
+        match content {
+            Some(content) => serde_json::from_str(&content).map_err(StorageError::Serde),
+            None => Ok(Journal::default()),
+        }
+    }
+
+    fn save_journal(&mut self, journal: &Journal) -> Result<(), StorageError> {
+        let content = serde_json::to_string(journal).map_err(StorageError::Serde)?;
+        self.write(|connection| connection.execute("INSERT OR REPLACE INTO journal (id, content) VALUES (1, ?1)", params![content]))?;
+        Ok(())
+    }
+
+    // The search index is a cache, kept in a file next to the database (outside the transaction)
+    fn load_index(&mut self) -> Result<SearchIndex, StorageError> {
+        read_index(&self.path)
+    }
+
+    fn save_index(&mut self, index: &SearchIndex) -> Result<(), StorageError> {
+        write_index(&self.path, index)
+    }
+
+    fn put(&mut self, task: Task) -> Result<(), StorageError> {
+        self.write(|connection| write_task(connection, &task))
+    }
+
+    fn delete(&mut self, id: usize) -> Result<bool, StorageError> {
+        self.write(|connection| remove_task(connection, id))
+    }
+
+    fn release(&mut self) {
+        if !self.connection.is_autocommit() {
+            let _ = self.connection.execute_batch("ROLLBACK");
+        }
+    }
+
+    fn partial_updates(&self) -> bool {
+        true
+    }
+
+    fn save_changes(&mut self, loaded: &Tasks, tasks: &Tasks) -> Result<(), StorageError> {
+        if loaded != tasks {
+            put_changes(self, loaded, tasks)?;
+        }
+
+        self.commit()
+    }
+}
+
+// Implement Drop for SqliteStore (discard the changes of a transaction that was not saved)
+impl Drop for SqliteStore {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
@@ -0,0 +1,145 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::journal::Journal;
+use crate::search::SearchIndex;
+use crate::tasks::{Task, Tasks};
+
+use super::{JsonStore, LogEntry, LogStore, MigrationReport, SqliteStore, Storage, StorageError};
+
+// Backend where tasks are kept. Per-task operations default to a full load and save, backends
+// that can do better (e.g. databases) override them and report it with `partial_updates`
+pub trait TaskStore {
+    // Load all tasks (an empty list when nothing has been stored yet)
+    fn load(&mut self) -> Result<Tasks, StorageError>;
+
+    // Save all tasks
+    fn save(&mut self, tasks: &Tasks) -> Result<(), StorageError>;
+
+    // Load the undo/redo journal (empty when nothing has been recorded yet)
+    fn load_journal(&mut self) -> Result<Journal, StorageError>;
+
+    // Save the undo/redo journal
+    fn save_journal(&mut self, journal: &Journal) -> Result<(), StorageError>;
+
+    // Insert or replace a single task
+    fn put(&mut self, task: Task) -> Result<(), StorageError> {
+        let mut tasks = self.load()?;
+        tasks.put_task(task);
+        self.save(&tasks)
+    }
+
+    // Remove a single task permanently (returns whether it existed)
+    fn delete(&mut self, id: usize) -> Result<bool, StorageError> {
+        let mut tasks = self.load()?;
+
+        if tasks.remove_task(id).is_none() {
+            return Ok(false);
+        }
+
+        self.save(&tasks)?;
+        Ok(true)
+    }
+
+    // Release what loading took to protect the load-modify-save cycle (the lock on the file, or
+    // the open transaction, whose unsaved changes are discarded), so other invocations can use the
+    // stored tasks until the next load
+    fn release(&mut self) {}
+
+    // Whether put and delete are cheaper than saving all tasks
+    fn partial_updates(&self) -> bool {
+        false
+    }
+
+    // Save the changes made to the loaded tasks: nothing when they are unchanged, only the changed
+    // tasks when the backend supports partial updates, and all tasks otherwise
+    fn save_changes(&mut self, loaded: &Tasks, tasks: &Tasks) -> Result<(), StorageError> {
+        if loaded == tasks {
+            return Ok(());
+        }
+
+        if !self.partial_updates() {
+            return self.save(tasks);
+        }
+
+        put_changes(self, loaded, tasks)
+    }
+
+    // Load the search index kept beside the stored tasks (empty when the backend keeps none, so it
+    // is built on every search)
+    fn load_index(&mut self) -> Result<SearchIndex, StorageError> {
+        Ok(SearchIndex::default())
+    }
+
+    // Save the search index
+    fn save_index(&mut self, _index: &SearchIndex) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    // Upgrade stored data to the current format (None when there is nothing to migrate)
+    fn migrate(&mut self, _dry_run: bool) -> Result<Option<MigrationReport>, StorageError> {
+        Ok(None)
+    }
+
+    // Recorded history of changes, oldest first (None when the backend does not keep one)
+    fn events(&mut self) -> Result<Option<Vec<LogEntry>>, StorageError> {
+        Ok(None)
+    }
+
+    // Fold the history into the stored state, returning the number of discarded events (None
+    // when the backend does not keep a history)
+    fn compact(&mut self) -> Result<Option<usize>, StorageError> {
+        Ok(None)
+    }
+}
+
+// Write the tasks that changed since they were loaded, one by one
+pub(super) fn put_changes<S: TaskStore + ?Sized>(store: &mut S, loaded: &Tasks, tasks: &Tasks) -> Result<(), StorageError> {
+    for task in tasks.iter().filter(|task| loaded.get_task(task.id) != Some(task)) {
+        store.put(task.clone())?;
+    }
+
+    for task in loaded.iter().filter(|task| tasks.get_task(task.id).is_none()) {
+        store.delete(task.id)?;
+    }
+
+    Ok(())
+}
+
+// Available storage backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreKind {
+    #[default]
+    Json,
+    Sqlite,
+    Log,
+}
+
+// StoreKind implementation
+impl StoreKind {
+    // Data file used when none is configured
+    pub fn default_file(self) -> &'static str {
+        match self {
+            StoreKind::Json => "tasks.json",
+            StoreKind::Sqlite => "tasks.db",
+            StoreKind::Log => "tasks.log",
+        }
+    }
+}
+
+// Open the selected backend
+pub fn open_store(kind: StoreKind, file_name: &str, lock_timeout: Duration) -> Result<Box<dyn TaskStore>, StorageError> {
+    match kind {
+        StoreKind::Json => {
+            // Keep a backup of the previous version of the file
+            let storage = Storage::new(file_name.to_string()).with_backup(true).with_lock_timeout(lock_timeout);
+            Ok(Box::new(JsonStore::new(storage)))
+        }
+        StoreKind::Sqlite => Ok(Box::new(SqliteStore::open(file_name, lock_timeout)?)),
+        StoreKind::Log => {
+            let storage = Storage::new(file_name.to_string()).with_lock_timeout(lock_timeout);
+            Ok(Box::new(LogStore::new(storage)))
+        }
+    }
+}
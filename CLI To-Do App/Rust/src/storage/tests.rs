@@ -1,6 +1,15 @@
-use super::{Storage, StorageError};
-use crate::tasks::{Tasks, TaskStatus};
+use super::log::{snapshot_path, Event};
+use super::migrations::{detect_version, migrate, MIGRATIONS};
+use super::{backup_path, index_path, journal_path, lock_path, temp_path, write_atomic, JsonStore, LogStore, MemoryStore, MigrationReport, SqliteStore, Storage, StorageError, TaskStore, CURRENT_VERSION};
+use serde_json::{json, Value};
+use crate::journal::Journal;
+use crate::search::SearchIndex;
+use crate::tasks::{Recurrence, TaskOptions, TaskPriority, Tasks, TaskStatus};
 use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
 
 // Create a temporary file
 fn temp_file(name: &str) -> String {
@@ -39,7 +48,7 @@ fn test_upload_data_with_valid_data() {
     let filename = temp_file("upload_valid");
     let s: Storage<Tasks> = Storage::new(filename.clone());
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     s.upload_data(&tasks).unwrap();
     assert!(fs::metadata(&filename).is_ok());
     let _ = fs::remove_file(filename);
@@ -59,8 +68,8 @@ fn test_download_data_with_valid_file() {
     let filename = temp_file("download_valid");
     let s: Storage<Tasks> = Storage::new(filename.clone());
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
-    tasks.add_task("Title 2 for testing".into(), "Description 2 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 2 for testing".into(), "Description 2 for testing".into(), TaskOptions::default()).unwrap();
     s.upload_data(&tasks).unwrap();
     let downloaded = s.download_data().unwrap().unwrap();
     assert_eq!(downloaded.len(), 2);
@@ -75,7 +84,7 @@ fn test_upload_download_integration() {
     let filename = temp_file("integration");
     let s: Storage<Tasks> = Storage::new(filename.clone());
     let mut tasks = Tasks::default();
-    tasks.add_task("Task 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Task 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     tasks.change_task_status(1, TaskStatus::Completed).unwrap();
     s.upload_data(&tasks).unwrap();
     let downloaded = s.download_data().unwrap().unwrap(); 
@@ -93,10 +102,10 @@ fn test_upload_data_with_complex_tasks() {
     let filename = temp_file("complex");
     let s: Storage<Tasks> = Storage::new(filename.clone());
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
-    tasks.add_task("Title 2 for testing".into(), "Description 2 for testing".into()).unwrap();
-    tasks.add_task("Title 3 for testing".into(), "Description 3 for testing".into()).unwrap();
-    tasks.add_task("Title 4 for testing".into(), "Description 4 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 2 for testing".into(), "Description 2 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 3 for testing".into(), "Description 3 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 4 for testing".into(), "Description 4 for testing".into(), TaskOptions::default()).unwrap();
     tasks.change_task_status(2, TaskStatus::InProgress).unwrap();
     tasks.change_task_status(3, TaskStatus::Completed).unwrap();
     tasks.delete_task(4).unwrap();
@@ -106,7 +115,7 @@ fn test_upload_data_with_complex_tasks() {
     assert_eq!(downloaded[0].status, TaskStatus::Pending);
     assert_eq!(downloaded[1].status, TaskStatus::InProgress);
     assert_eq!(downloaded[2].status, TaskStatus::Completed);
-    assert_eq!(downloaded[3].visible, false);
+    assert!(!downloaded[3].visible);
     let _ = fs::remove_file(filename);
 }
 
@@ -140,3 +149,716 @@ fn test_download_data_with_invalid_status_value() {
     assert!(matches!(err, super::StorageError::Serde(_)));
     let _ = fs::remove_file(filename);
 }
+
+#[test]
+// Test: download data written without priorities and with unknown fields
+fn test_download_data_without_priority_and_unknown_fields() {
+    let filename = temp_file("legacy_priority");
+    let json = r#"[{
+        "ID": 1,
+        "Title": "X",
+        "Description": "Y",
+        "CreatedAt": "2024-01-01T00:00:00+00:00",
+        "UpdatedAt": "2024-01-01T00:00:00+00:00",
+        "CompletedAt": "0001-01-01T00:00:00Z",
+        "Status": 0,
+        "Visible": true,
+        "SomethingNew": [1, 2, 3]
+    }]"#;
+    fs::write(&filename, json).unwrap();
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let downloaded = s.download_data().unwrap().unwrap();
+    assert_eq!(downloaded[0].priority, TaskPriority::Medium);
+    assert_eq!(downloaded[0].project, "general");
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: upload and download task priority
+fn test_upload_download_priority() {
+    let filename = temp_file("priority");
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.change_task_priority(1, TaskPriority::Urgent).unwrap();
+    s.upload_data(&tasks).unwrap();
+    let content = fs::read_to_string(&filename).unwrap();
+    assert!(content.contains("\"Priority\": 3"));
+    let downloaded = s.download_data().unwrap().unwrap();
+    assert_eq!(downloaded[0].priority, TaskPriority::Urgent);
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: upload and download task due date (omitted when not set)
+fn test_upload_download_due_date() {
+    let filename = temp_file("due_date");
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 2 for testing".into(), "Description 2 for testing".into(), TaskOptions::default()).unwrap();
+    let due_at = chrono::DateTime::parse_from_rfc3339("2025-11-02T17:00:00-04:00").unwrap();
+    tasks.change_task_due_date(2, Some(due_at)).unwrap();
+    s.upload_data(&tasks).unwrap();
+    let content = fs::read_to_string(&filename).unwrap();
+    assert_eq!(content.matches("\"DueAt\"").count(), 1);
+    let downloaded = s.download_data().unwrap().unwrap();
+    assert!(downloaded[0].due_at.is_none());
+    assert_eq!(downloaded[1].due_at, Some(due_at));
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: upload and download task tags
+fn test_upload_download_tags() {
+    let filename = temp_file("tags");
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_tags(1, &["work".into(), "urgent".into()]).unwrap();
+    s.upload_data(&tasks).unwrap();
+    let downloaded = s.download_data().unwrap().unwrap();
+    assert_eq!(downloaded[0].tags, tasks[0].tags);
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: upload and download task project
+fn test_upload_download_project() {
+    let filename = temp_file("project");
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.move_task(1, "work.backend").unwrap();
+    s.upload_data(&tasks).unwrap();
+    let downloaded = s.download_data().unwrap().unwrap();
+    assert_eq!(downloaded[0].project, "work.backend");
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: upload data stores the next ID counter with the tasks
+fn test_upload_download_next_id() {
+    let filename = temp_file("next_id");
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    s.upload_data(&tasks).unwrap();
+    let content = fs::read_to_string(&filename).unwrap();
+    assert!(content.starts_with("{\n  \"version\": 2,\n  \"next_id\": 2,"));
+    assert!(content.contains("\"UUID\""));
+    let mut downloaded = s.download_data().unwrap().unwrap();
+    assert_eq!(downloaded[0].uuid, tasks[0].uuid);
+    assert_eq!(downloaded.add_task_with("Title 2 for testing".into(), "".into(), TaskOptions::default()).unwrap(), 2);
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: download data keeps the stored counter and IDs of reordered tasks
+fn test_download_data_with_counter_and_reordered_tasks() {
+    let filename = temp_file("reordered");
+    let json = r#"{"NextID": 7, "Tasks": [
+        {"ID": 4, "Title": "Four", "Description": "", "CreatedAt": "2024-01-01T00:00:00+00:00",
+         "UpdatedAt": "2024-01-01T00:00:00+00:00", "CompletedAt": "0001-01-01T00:00:00Z", "Status": 0, "Visible": true},
+        {"ID": 2, "Title": "Two", "Description": "", "CreatedAt": "2024-01-01T00:00:00+00:00",
+         "UpdatedAt": "2024-01-01T00:00:00+00:00", "CompletedAt": "0001-01-01T00:00:00Z", "Status": 0, "Visible": true}
+    ]}"#;
+    fs::write(&filename, json).unwrap();
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let mut downloaded = s.download_data().unwrap().unwrap();
+    downloaded.change_task_status(2, TaskStatus::Completed).unwrap();
+    assert_eq!(downloaded.get_task(2).unwrap().status, TaskStatus::Completed);
+    assert_eq!(downloaded.get_task(4).unwrap().status, TaskStatus::Pending);
+    assert_eq!(downloaded.add_task_with("Seven".into(), "".into(), TaskOptions::default()).unwrap(), 7);
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: download data with duplicate IDs
+fn test_download_data_with_duplicate_ids() {
+    let filename = temp_file("duplicate_ids");
+    let task = r#"{"ID": 1, "Title": "X", "Description": "", "CreatedAt": "2024-01-01T00:00:00+00:00",
+        "UpdatedAt": "2024-01-01T00:00:00+00:00", "CompletedAt": "0001-01-01T00:00:00Z", "Status": 0, "Visible": true}"#;
+    fs::write(&filename, format!("[{}, {}]", task, task)).unwrap();
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let err = s.download_data().unwrap_err();
+    assert!(matches!(err, StorageError::Serde(_)));
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: upload and download subtasks
+fn test_upload_download_subtasks() {
+    let filename = temp_file("subtasks");
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Parent".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Child".into(), "".into(), TaskOptions { parent: Some(1), ..Default::default() }).unwrap();
+    s.upload_data(&tasks).unwrap();
+    let content = fs::read_to_string(&filename).unwrap();
+    assert_eq!(content.matches("\"ParentID\"").count(), 1);
+    let downloaded = s.download_data().unwrap().unwrap();
+    assert_eq!(downloaded[1].parent, Some(1));
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: upload and download task dependencies
+fn test_upload_download_dependencies() {
+    let filename = temp_file("dependencies");
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Dependency".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Blocked".into(), "".into(), TaskOptions { depends_on: vec![1], ..Default::default() }).unwrap();
+    s.upload_data(&tasks).unwrap();
+    let downloaded = s.download_data().unwrap().unwrap();
+    assert!(downloaded.is_blocked(2));
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: upload and download recurrence rules
+fn test_upload_download_recurrence() {
+    let filename = temp_file("recurrence");
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let mut tasks = Tasks::default();
+    let recurrence = "weekly:mon,fri".parse::<Recurrence>().unwrap();
+    tasks.add_task_with("Weekly report".into(), "".into(), TaskOptions { recurrence: Some(recurrence.clone()), ..Default::default() }).unwrap();
+    tasks.add_task_with("Once".into(), "".into(), TaskOptions::default()).unwrap();
+    s.upload_data(&tasks).unwrap();
+    let content = fs::read_to_string(&filename).unwrap();
+    assert!(content.contains("\"Recurrence\": \"weekly:mon,fri\""));
+    let downloaded = s.download_data().unwrap().unwrap();
+    assert_eq!(downloaded[0].recurrence, Some(recurrence));
+    assert_eq!(downloaded[1].recurrence, None);
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: a failure in the middle of a write keeps the previous file intact
+fn test_upload_data_partial_write_failure() {
+    let filename = temp_file("partial_write");
+    let path = Path::new(&filename);
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title for testing".into(), "".into(), TaskOptions::default()).unwrap();
+    s.upload_data(&tasks).unwrap();
+    let original = fs::read_to_string(&filename).unwrap();
+
+    // Simulate a full disk after writing part of the content
+    let result = write_atomic(path, b"[{\"ID\": 1, \"Title\": \"Trunc", false, |file, content| {
+        file.write_all(&content[..content.len() / 2])?;
+        Err(io::Error::other("No space left on device"))
+    });
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&filename).unwrap(), original);
+    assert!(!temp_path(path).exists());
+    assert_eq!(s.download_data().unwrap().unwrap().len(), 1);
+    let _ = fs::remove_file(filename);
+}
+
+#[test]
+// Test: a failure when replacing the file removes the temporary file
+fn test_upload_data_rename_failure() {
+    let dirname = temp_file("rename_failure");
+    fs::create_dir(&dirname).unwrap();
+    let s: Storage<Tasks> = Storage::new(dirname.clone());
+    assert!(matches!(s.upload_data(&Tasks::default()), Err(StorageError::Io(_))));
+    assert!(Path::new(&dirname).is_dir());
+    assert!(!temp_path(Path::new(&dirname)).exists());
+    let _ = fs::remove_dir(dirname);
+}
+
+#[test]
+// Test: upload data keeping a backup of the previous version
+fn test_upload_data_with_backup() {
+    let filename = temp_file("backup");
+    let backup = backup_path(Path::new(&filename));
+    let s: Storage<Tasks> = Storage::new(filename.clone()).with_backup(true);
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("First version".into(), "".into(), TaskOptions::default()).unwrap();
+    s.upload_data(&tasks).unwrap();
+    assert!(!backup.exists());
+    let first = fs::read_to_string(&filename).unwrap();
+    tasks.add_task_with("Second version".into(), "".into(), TaskOptions::default()).unwrap();
+    s.upload_data(&tasks).unwrap();
+    assert_eq!(fs::read_to_string(&backup).unwrap(), first);
+    assert_eq!(s.download_data().unwrap().unwrap().len(), 2);
+
+    // Without backups the previous copy is left untouched
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    tasks.add_task_with("Third version".into(), "".into(), TaskOptions::default()).unwrap();
+    s.upload_data(&tasks).unwrap();
+    assert_eq!(fs::read_to_string(&backup).unwrap(), first);
+    let _ = fs::remove_file(filename);
+    let _ = fs::remove_file(backup);
+}
+
+#[test]
+// Test: lock the data file while another lock is held
+fn test_lock_held_by_another_invocation() {
+    let filename = temp_file("lock");
+    let s: Storage<Tasks> = Storage::new(filename.clone()).with_lock_timeout(Duration::from_millis(200));
+    let lock = s.lock().unwrap();
+    let started = Instant::now();
+    let err = s.lock().unwrap_err();
+    assert!(started.elapsed() >= Duration::from_millis(200));
+    assert!(matches!(err, StorageError::Locked(ref path, _) if path == &lock_path(Path::new(&filename))));
+    assert!(err.to_string().contains("otra ejecución"));
+
+    // The lock is released when the guard is dropped
+    drop(lock);
+    assert!(s.lock().is_ok());
+    let _ = fs::remove_file(lock_path(Path::new(&filename)));
+}
+
+#[test]
+// Test: wait for the lock until the other invocation releases it
+fn test_lock_waits_for_release() {
+    let filename = temp_file("lock_wait");
+    let s: Storage<Tasks> = Storage::new(filename.clone()).with_lock_timeout(Duration::from_secs(5));
+    let lock = s.lock().unwrap();
+    let holder = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        drop(lock);
+    });
+    assert!(s.lock().is_ok());
+    holder.join().unwrap();
+    let _ = fs::remove_file(lock_path(Path::new(&filename)));
+}
+
+#[test]
+// Test: lock with empty filename
+fn test_lock_with_empty_filename() {
+    let s: Storage<Tasks> = Storage::new("".to_string());
+    assert!(matches!(s.lock(), Err(StorageError::EmptyFileName)));
+}
+
+// Fixtures with the same tasks stored in each format version
+const FIXTURES: [&str; 3] = [
+    include_str!("fixtures/v0.json"),
+    include_str!("fixtures/v1.json"),
+    include_str!("fixtures/v2.json"),
+];
+
+// Parse a fixture
+fn fixture(version: usize) -> Value {
+    serde_json::from_str(FIXTURES[version]).unwrap()
+}
+
+#[test]
+// Test: every version has a fixture and a migration to the next one
+fn test_migrations_cover_all_versions() {
+    assert_eq!(FIXTURES.len() as u64, CURRENT_VERSION + 1);
+    assert_eq!(MIGRATIONS.len() as u64, CURRENT_VERSION);
+    for (version, _) in FIXTURES.iter().enumerate() {
+        assert_eq!(detect_version(&fixture(version)).unwrap(), version as u64);
+    }
+}
+
+#[test]
+// Test: migration v0 -> v1 (bare array to NextID/Tasks object)
+fn test_migration_v0_to_v1() {
+    assert_eq!((MIGRATIONS[0].apply)(fixture(0)).unwrap(), fixture(1));
+    assert_eq!((MIGRATIONS[0].apply)(json!([])).unwrap(), json!({"NextID": 1, "Tasks": []}));
+}
+
+#[test]
+// Test: migration v1 -> v2 (versioned envelope)
+fn test_migration_v1_to_v2() {
+    assert_eq!((MIGRATIONS[1].apply)(fixture(1)).unwrap(), fixture(2));
+}
+
+#[test]
+// Test: migrate every fixture to the current version
+fn test_migrate_fixtures_to_current_version() {
+    for version in 0..FIXTURES.len() {
+        let (migrated, from) = migrate(fixture(version)).unwrap();
+        assert_eq!(from, version as u64);
+        assert_eq!(migrated, fixture(CURRENT_VERSION as usize));
+    }
+}
+
+#[test]
+// Test: unknown formats and versions newer than the application
+fn test_migrate_unsupported_data() {
+    assert!(matches!(migrate(json!({"version": 99, "tasks": []})), Err(StorageError::UnsupportedVersion(99))));
+    assert!(matches!(migrate(json!({"version": "2"})), Err(StorageError::InvalidFormat)));
+    assert!(matches!(migrate(json!({"tasks": []})), Err(StorageError::InvalidFormat)));
+    assert!(matches!(migrate(json!("tasks")), Err(StorageError::InvalidFormat)));
+}
+
+#[test]
+// Test: download data stored in each format version
+fn test_download_data_from_every_version() {
+    for (version, content) in FIXTURES.iter().enumerate() {
+        let filename = temp_file(&format!("fixture_v{}", version));
+        fs::write(&filename, content).unwrap();
+        let s: Storage<Tasks> = Storage::new(filename.clone());
+        let mut downloaded = s.download_data().unwrap().unwrap();
+        assert_eq!(downloaded.len(), 2);
+        assert_eq!(downloaded.get_task(1).unwrap().status, TaskStatus::Completed);
+        assert!(!downloaded.get_task(3).unwrap().visible);
+        assert_eq!(downloaded.add_task_with("Four".into(), "".into(), TaskOptions::default()).unwrap(), 4);
+        let _ = fs::remove_file(filename);
+    }
+}
+
+#[test]
+// Test: migrate the data file (dry run first)
+fn test_storage_migrate() {
+    let filename = temp_file("migrate");
+    fs::write(&filename, FIXTURES[0]).unwrap();
+    let s: Storage<Tasks> = Storage::new(filename.clone());
+    let report = s.migrate(true).unwrap().unwrap();
+    assert_eq!(report.from, 0);
+    assert_eq!(report.to, CURRENT_VERSION);
+    assert_eq!(report.steps.len(), 2);
+    assert_eq!(fs::read_to_string(&filename).unwrap(), FIXTURES[0]);
+
+    s.migrate(false).unwrap().unwrap();
+    let migrated: Value = serde_json::from_str(&fs::read_to_string(&filename).unwrap()).unwrap();
+    assert_eq!(detect_version(&migrated).unwrap(), CURRENT_VERSION);
+    assert_eq!(migrated["next_id"], 4);
+    assert_eq!(s.migrate(false).unwrap().unwrap(), MigrationReport { from: CURRENT_VERSION, to: CURRENT_VERSION, steps: Vec::new() });
+
+    let _ = fs::remove_file(&filename);
+    assert!(s.migrate(true).unwrap().is_none());
+}
+
+#[test]
+// Test: per-task operations on the in-memory store
+fn test_memory_store_put_delete() {
+    let mut store = MemoryStore::default();
+    store.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+    let mut task = store.get_task(1).unwrap().clone();
+    task.title = "Changed".into();
+    store.put(task.clone()).unwrap();
+    task.id = 5;
+    store.put(task).unwrap();
+    let loaded = store.load().unwrap();
+    assert_eq!(loaded.get_task(1).unwrap().title, "Changed");
+    assert_eq!(loaded.len(), 2);
+    assert!(store.delete(1).unwrap());
+    assert!(!store.delete(1).unwrap());
+    assert!(store.get_task(1).is_none());
+    assert_eq!(store.get_task(5).unwrap().title, "Changed");
+
+    // The counter is kept after permanent deletes
+    assert_eq!(store.add_task_with("Next".into(), "".into(), TaskOptions::default()).unwrap(), 6);
+}
+
+#[test]
+// Test: save only the changes made to the loaded tasks
+fn test_save_changes() {
+    let mut store = MemoryStore::default();
+    store.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+    store.add_task_with("Second".into(), "".into(), TaskOptions::default()).unwrap();
+    let loaded = store.load().unwrap();
+    let mut tasks = loaded.clone();
+    tasks.change_task_status(2, TaskStatus::Completed).unwrap();
+    tasks.remove_task(1);
+    tasks.add_task_with("Third".into(), "".into(), TaskOptions::default()).unwrap();
+    store.save_changes(&loaded, &tasks).unwrap();
+    assert_eq!(store.load().unwrap(), tasks);
+}
+
+#[test]
+// Test: JSON file store (unchanged tasks are not written)
+fn test_json_store() {
+    let filename = temp_file("json_store");
+    let mut store = JsonStore::new(Storage::new(filename.clone()));
+    let loaded = store.load().unwrap();
+    assert!(loaded.is_empty());
+    store.save_changes(&loaded, &loaded).unwrap();
+    assert!(!Path::new(&filename).exists());
+    let mut tasks = loaded.clone();
+    tasks.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+    store.save_changes(&loaded, &tasks).unwrap();
+    assert!(store.delete(1).unwrap());
+    drop(store);
+
+    // The lock is released with the store
+    let mut store = JsonStore::new(Storage::new(filename.clone()).with_lock_timeout(Duration::ZERO));
+    let mut loaded = store.load().unwrap();
+    assert!(loaded.is_empty());
+    assert_eq!(loaded.add_task_with("Second".into(), "".into(), TaskOptions::default()).unwrap(), 2);
+    let _ = fs::remove_file(&filename);
+    let _ = fs::remove_file(lock_path(Path::new(&filename)));
+}
+
+#[test]
+// Test: releasing a store lets other invocations use the stored tasks until the next load
+fn test_store_release() {
+    let filename = temp_file("json_release");
+    let open = || JsonStore::new(Storage::new(filename.clone()).with_lock_timeout(Duration::ZERO));
+    let (mut first, mut second) = (open(), open());
+    first.load().unwrap();
+    assert!(matches!(second.load(), Err(StorageError::Locked(_, _))));
+    first.release();
+    second.load().unwrap();
+    assert!(matches!(first.load(), Err(StorageError::Locked(_, _))));
+    drop(second);
+    let _ = fs::remove_file(lock_path(Path::new(&filename)));
+
+    let filename = temp_file("sqlite_release");
+    let mut first = SqliteStore::open(&filename, Duration::ZERO).unwrap();
+    first.load().unwrap();
+    first.release();
+    let mut second = SqliteStore::open(&filename, Duration::ZERO).unwrap();
+    assert!(second.load().unwrap().is_empty());
+    drop(second);
+    drop(first);
+    let _ = fs::remove_file(&filename);
+}
+
+#[test]
+// Test: SQLite store keeps every field of the tasks and the next ID counter
+fn test_sqlite_store_round_trip() {
+    let filename = temp_file("sqlite_round_trip");
+    let mut store = SqliteStore::open(&filename, Duration::ZERO).unwrap();
+    let mut tasks = store.load().unwrap();
+    assert!(tasks.is_empty());
+    tasks.add_task_with("Parent".into(), "Description".into(), TaskOptions::default()).unwrap();
+    let options = TaskOptions {
+        priority: TaskPriority::Urgent,
+        due_at: Some(chrono::DateTime::parse_from_rfc3339("2025-11-02T17:00:00-04:00").unwrap()),
+        tags: vec!["trabajo".into(), "urgente".into()],
+        project: Some("trabajo.backend".into()),
+        parent: Some(1),
+        depends_on: vec![1],
+        recurrence: Some("monthly:15".parse::<Recurrence>().unwrap()),
+    };
+    tasks.add_task_with("Child".into(), "".into(), options).unwrap();
+    tasks.add_task_with("Deleted".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.delete_task(3).unwrap();
+    tasks.change_task_status(1, TaskStatus::Completed).unwrap();
+    store.save(&tasks).unwrap();
+    drop(store);
+
+    let mut store = SqliteStore::open(&filename, Duration::ZERO).unwrap();
+    let loaded = store.load().unwrap();
+    assert_eq!(loaded, tasks);
+    assert_eq!(loaded.next_id(), 4);
+    let _ = fs::remove_file(&filename);
+}
+
+#[test]
+// Test: SQLite store writes only the tasks that changed, and discards unsaved changes
+fn test_sqlite_store_save_changes() {
+    let filename = temp_file("sqlite_save_changes");
+    let mut store = SqliteStore::open(&filename, Duration::ZERO).unwrap();
+    let mut tasks = store.load().unwrap();
+    tasks.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Second".into(), "".into(), TaskOptions::default()).unwrap();
+    store.save(&tasks).unwrap();
+
+    let loaded = store.load().unwrap();
+    let mut tasks = loaded.clone();
+    tasks.add_task_tags(2, &["casa".into()]).unwrap();
+    tasks.remove_task(1);
+    tasks.add_task_with("Third".into(), "".into(), TaskOptions::default()).unwrap();
+    store.save_changes(&loaded, &tasks).unwrap();
+    assert_eq!(store.load().unwrap(), tasks);
+
+    // Changes written without save_changes are rolled back with the store
+    assert!(store.delete(2).unwrap());
+    drop(store);
+    let mut store = SqliteStore::open(&filename, Duration::ZERO).unwrap();
+    assert_eq!(store.load().unwrap(), tasks);
+    let _ = fs::remove_file(&filename);
+}
+
+#[test]
+// Test: SQLite store is locked while another invocation uses it
+fn test_sqlite_store_locked() {
+    let filename = temp_file("sqlite_locked");
+    let mut first = SqliteStore::open(&filename, Duration::ZERO).unwrap();
+    first.load().unwrap();
+    assert!(matches!(SqliteStore::open(&filename, Duration::ZERO), Err(StorageError::Locked(_, _))));
+    drop(first);
+    let mut second = SqliteStore::open(&filename, Duration::ZERO).unwrap();
+    assert!(second.load().unwrap().is_empty());
+    drop(second);
+    let _ = fs::remove_file(&filename);
+}
+
+#[test]
+// Test: SQLite store creates the indexes on status, due date and tags
+fn test_sqlite_store_indexes() {
+    let filename = temp_file("sqlite_indexes");
+    drop(SqliteStore::open(&filename, Duration::ZERO).unwrap());
+    let connection = rusqlite::Connection::open(&filename).unwrap();
+    let mut statement = connection.prepare("SELECT name FROM sqlite_master WHERE type = 'index' AND name LIKE 'idx_%' ORDER BY name").unwrap();
+    let indexes: Vec<String> = statement.query_map([], |row| row.get(0)).unwrap().map(Result::unwrap).collect();
+    assert_eq!(indexes, ["idx_task_tags_tag", "idx_tasks_due_at", "idx_tasks_status"]);
+    drop(statement);
+    drop(connection);
+    let _ = fs::remove_file(&filename);
+}
+
+// Remove the files of an event log store
+fn remove_log_files(filename: &str) {
+    let _ = fs::remove_file(filename);
+    let _ = fs::remove_file(snapshot_path(Path::new(filename)));
+    let _ = fs::remove_file(lock_path(Path::new(filename)));
+}
+
+// Save the changes of a function on the tasks of a store
+fn change(store: &mut LogStore, f: impl FnOnce(&mut Tasks)) -> Tasks {
+    let loaded = store.load().unwrap();
+    let mut tasks = loaded.clone();
+    f(&mut tasks);
+    store.save_changes(&loaded, &tasks).unwrap();
+    tasks
+}
+
+#[test]
+// Test: event log records each kind of change and rebuilds the same tasks
+fn test_log_store_events_and_replay() {
+    let filename = temp_file("log_store");
+    let mut store = LogStore::new(Storage::new(filename.clone()));
+    change(&mut store, |tasks| {
+        tasks.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+        tasks.add_task_with("Second".into(), "".into(), TaskOptions::default()).unwrap();
+    });
+    change(&mut store, |tasks| tasks.update_task(1, "Renamed".into(), "".into()).unwrap());
+    change(&mut store, |tasks| {
+        tasks.change_task_status(1, TaskStatus::Completed).unwrap();
+    });
+    change(&mut store, |tasks| tasks.delete_task(2).unwrap());
+    let tasks = change(&mut store, |tasks| {
+        tasks.add_task_with("Third".into(), "".into(), TaskOptions::default()).unwrap();
+        tasks.remove_task(3);
+    });
+
+    let entries = store.events().unwrap().unwrap();
+    let seqs: Vec<u64> = entries.iter().map(|entry| entry.seq).collect();
+    assert_eq!(seqs, [1, 2, 3, 4, 5]);
+    assert!(matches!(entries[0].event, Event::TaskAdded { .. }));
+    assert!(matches!(entries[2].event, Event::TaskUpdated { ref task } if task.title == "Renamed"));
+    assert_eq!(
+        entries[3].event,
+        Event::StatusChanged { id: 1, from: TaskStatus::Pending, to: TaskStatus::Completed, completed_at: tasks[0].completed_at }
+    );
+    assert_eq!(entries[4].event, Event::TaskDeleted { id: 2, purged: false });
+    drop(store);
+
+    // Adding and purging in the same command leaves no events, but the counter is kept
+    let mut store = LogStore::new(Storage::new(filename.clone()));
+    let replayed = store.load().unwrap();
+    assert_eq!(replayed, tasks);
+    assert_eq!(replayed.next_id(), 4);
+    remove_log_files(&filename);
+}
+
+#[test]
+// Test: event log takes snapshots and replays only the events after them
+fn test_log_store_snapshot_and_compact() {
+    let filename = temp_file("log_snapshot");
+    let mut store = LogStore::new(Storage::new(filename.clone())).with_snapshot_interval(2);
+    change(&mut store, |tasks| {
+        tasks.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+    });
+    assert!(!snapshot_path(Path::new(&filename)).exists());
+    change(&mut store, |tasks| {
+        tasks.add_task_with("Second".into(), "".into(), TaskOptions::default()).unwrap();
+    });
+    assert!(snapshot_path(Path::new(&filename)).exists());
+    let tasks = change(&mut store, |tasks| {
+        tasks.change_task_status(2, TaskStatus::InProgress).unwrap();
+    });
+    drop(store);
+
+    let mut store = LogStore::new(Storage::new(filename.clone()));
+    assert_eq!(store.load().unwrap(), tasks);
+    assert_eq!(store.events().unwrap().unwrap().len(), 3);
+    assert_eq!(store.compact().unwrap(), Some(3));
+    assert_eq!(store.events().unwrap().unwrap(), []);
+    let tasks = change(&mut store, |tasks| tasks.delete_task(1).unwrap());
+    drop(store);
+
+    // Numbering continues after the compacted events
+    let mut store = LogStore::new(Storage::new(filename.clone()));
+    assert_eq!(store.load().unwrap(), tasks);
+    assert_eq!(store.events().unwrap().unwrap()[0].seq, 4);
+    remove_log_files(&filename);
+}
+
+#[test]
+// Test: event log ignores an interrupted append and rejects invalid events
+fn test_log_store_invalid_lines() {
+    let filename = temp_file("log_invalid");
+    let mut store = LogStore::new(Storage::new(filename.clone()));
+    change(&mut store, |tasks| {
+        tasks.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+    });
+    drop(store);
+
+    let mut file = fs::OpenOptions::new().append(true).open(&filename).unwrap();
+    file.write_all(b"{\"seq\": 2, \"at\": ").unwrap();
+    drop(file);
+
+    let mut store = LogStore::new(Storage::new(filename.clone()));
+    let tasks = change(&mut store, |tasks| {
+        tasks.add_task_with("Second".into(), "".into(), TaskOptions::default()).unwrap();
+    });
+    drop(store);
+    let content = fs::read_to_string(&filename).unwrap();
+    assert_eq!(content.lines().count(), 2);
+    assert_eq!(LogStore::new(Storage::new(filename.clone())).load().unwrap(), tasks);
+
+    // A status change of a task that does not exist
+    let mut file = fs::OpenOptions::new().append(true).open(&filename).unwrap();
+    file.write_all(b"{\"seq\": 3, \"at\": \"2025-01-01T00:00:00+00:00\", \"event\": \"TaskDeleted\", \"id\": 9}\n").unwrap();
+    drop(file);
+    let result = LogStore::new(Storage::new(filename.clone())).load();
+    assert!(matches!(result, Err(StorageError::InvalidEvent(3))));
+    remove_log_files(&filename);
+}
+
+#[test]
+// Test: file and database stores keep the undo/redo journal
+fn test_store_journal() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+    let mut journal = Journal::default();
+    journal.record("add".into(), &Tasks::default(), &tasks);
+
+    let json = temp_file("journal_json");
+    let sqlite = temp_file("journal_sqlite");
+    let open_json = |file: &str| -> Box<dyn TaskStore> { Box::new(JsonStore::new(Storage::new(file.to_string()))) };
+    let open_sqlite = |file: &str| -> Box<dyn TaskStore> { Box::new(SqliteStore::open(file, Duration::ZERO).unwrap()) };
+
+    for (file, open) in [(&json, &open_json as &dyn Fn(&str) -> Box<dyn TaskStore>), (&sqlite, &open_sqlite)] {
+        let mut store = open(file);
+        assert_eq!(store.load_journal().unwrap(), Journal::default());
+        store.save_journal(&journal).unwrap();
+        drop(store);
+        assert_eq!(open(file).load_journal().unwrap(), journal);
+        let _ = fs::remove_file(file);
+        let _ = fs::remove_file(journal_path(Path::new(file)));
+        let _ = fs::remove_file(lock_path(Path::new(file)));
+    }
+}
+
+#[test]
+// Test: the search index is kept next to the data file (a damaged index is replaced by an empty one)
+fn test_store_search_index() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("First".into(), "".into(), TaskOptions::default()).unwrap();
+    let mut index = SearchIndex::default();
+    index.update(&tasks);
+
+    let file = temp_file("search_index");
+    let mut store = JsonStore::new(Storage::new(file.clone()));
+    assert_eq!(store.load_index().unwrap(), SearchIndex::default());
+    store.save_index(&index).unwrap();
+    assert_eq!(store.load_index().unwrap(), index);
+
+    fs::write(index_path(Path::new(&file)), "{not json").unwrap();
+    assert_eq!(store.load_index().unwrap(), SearchIndex::default());
+    assert_eq!(MemoryStore::default().load_index().unwrap(), SearchIndex::default());
+    let _ = fs::remove_file(index_path(Path::new(&file)));
+    let _ = fs::remove_file(lock_path(Path::new(&file)));
+}
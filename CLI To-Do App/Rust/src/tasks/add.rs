@@ -1,38 +1,60 @@
-use chrono::{DateTime, FixedOffset, Local};
-use chrono::Offset;
+use uuid::Uuid;
 
-use super::types::{zero_datetime, Task, TaskError, Tasks, TaskStatus};
+use crate::dates::now_fixed;
 
-// Get local datetime with timezone
-fn now_fixed() -> DateTime<FixedOffset> {
-    let local_now = Local::now();
-    let offset = local_now.offset().fix();
-    local_now.with_timezone(&offset)
-}
+use super::projects::{normalize_project, DEFAULT_PROJECT};
+use super::tags::normalize_tag;
+use super::types::{zero_datetime, Task, TaskError, TaskOptions, Tasks, TaskStatus};
 
 // Tasks implementation
 impl Tasks {
-    // POST method
-    pub fn add_task(&mut self, title: String, description: String) -> Result<(), TaskError> {
+    // POST method (returns the ID of the new task)
+    pub fn add_task_with(&mut self, title: String, description: String, options: TaskOptions) -> Result<usize, TaskError> {
         if title.trim().is_empty() {
             return Err(TaskError::EmptyTitle);
         }
 
+        let tags = options.tags.iter().map(|tag| normalize_tag(tag)).collect::<Result<_, _>>()?;
+
+        // Subtasks belong to an open parent and inherit its project unless one is given
+        let parent_project = match options.parent {
+            Some(parent) => {
+                let idx = self.validate_task_id(parent).map_err(|_| TaskError::ParentNotFound)?;
+                Some(self.tasks[idx].project.clone())
+            }
+            None => None,
+        };
+
+        let project = options.project.as_deref().or(parent_project.as_deref()).unwrap_or(DEFAULT_PROJECT);
+        let project = normalize_project(project)?;
+
+        let id = self.next_id;
+
+        self.validate_dependencies(id, &options.depends_on)?;
+
         let now = now_fixed();
 
         let task = Task {
-            id: self.len() + 1,
+            id,
+            uuid: Some(Uuid::new_v4()),
             title,
             description,
             created_at: now,
             updated_at: now, 
             completed_at: zero_datetime(),
+            due_at: options.due_at,
             status: TaskStatus::Pending,
+            priority: options.priority,
+            tags,
+            project,
+            parent: options.parent,
+            depends_on: options.depends_on.into_iter().collect(),
+            recurrence: options.recurrence,
             visible: true,
         };
 
-        self.push(task);
+        self.push_task(task);
         
-        Ok(())
+        Ok(id)
     }
 }
@@ -0,0 +1,33 @@
+use chrono::{DateTime, FixedOffset};
+
+use super::types::{Task, TaskStatus, Tasks};
+
+// Tasks implementation
+impl Tasks {
+    // Take out the tasks completed before `completed_before`, so they can be moved to the archive
+    // (tasks with open subtasks stay until their subtasks are completed). Subtasks go with their
+    // parent, and deleted subtasks that stay lose the parent, so no task points to an archived one
+    pub fn archive_tasks(&mut self, completed_before: DateTime<FixedOffset>) -> Vec<Task> {
+        let mut ids: Vec<usize> = self
+            .iter()
+            .filter(|task| task.visible && task.status == TaskStatus::Completed && task.completed_at < completed_before)
+            .filter(|task| self.open_descendants(task.id).is_empty())
+            .map(|task| task.id)
+            .collect();
+
+        for id in ids.clone() {
+            ids.extend(self.descendants(id));
+        }
+
+        ids.sort_unstable();
+        ids.dedup();
+
+        for task in &mut self.tasks {
+            if task.parent.is_some_and(|parent| ids.binary_search(&parent).is_ok()) && ids.binary_search(&task.id).is_err() {
+                task.parent = None;
+            }
+        }
+
+        ids.into_iter().filter_map(|id| self.remove_task(id)).collect()
+    }
+}
@@ -1,26 +1,25 @@
-use chrono::{DateTime, FixedOffset, Local};
-use chrono::Offset;
+use crate::dates::now_fixed;
 
 use super::types::{TaskError, Tasks};
 
-// Get local datetime with timezone
-fn now_fixed() -> DateTime<FixedOffset> {
-    let local_now = Local::now();
-    let offset = local_now.offset().fix();
-    local_now.with_timezone(&offset)
-}
-
 // Tasks implementation
 impl Tasks {
-    // DELETE method
+    // DELETE method (subtasks are deleted along with their parent)
     pub fn delete_task(&mut self, id: usize) -> Result<(), TaskError> {
-        self.validate_task_id(id)?;
+        let idx = self.validate_task_id(id)?;
+
+        let now = now_fixed();
 
-        let idx = id - 1;
+        for child in self.descendants(id) {
+            if let Some(&child_idx) = self.index.get(&child) {
+                self.tasks[child_idx].visible = false;
+                self.tasks[child_idx].updated_at = now;
+            }
+        }
 
-        self[idx].visible = false;
+        self.tasks[idx].visible = false;
 
-        self[idx].updated_at = now_fixed();
+        self.tasks[idx].updated_at = now;
 
         Ok(())
     }
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+
+use crate::dates::now_fixed;
+
+use super::types::{TaskError, TaskStatus, Tasks};
+
+// Tasks implementation
+impl Tasks {
+    // Open dependencies of a task (deleted or completed dependencies do not block)
+    pub fn blockers(&self, id: usize) -> Vec<usize> {
+        let Some(task) = self.get_task(id) else {
+            return Vec::new();
+        };
+
+        task.depends_on
+            .iter()
+            .copied()
+            .filter(|&dep| self.get_task(dep).is_some_and(|dep| dep.visible && dep.status != TaskStatus::Completed))
+            .collect()
+    }
+
+    // Check whether a task has open dependencies
+    pub fn is_blocked(&self, id: usize) -> bool {
+        !self.blockers(id).is_empty()
+    }
+
+    // Validate dependencies for a task: they must exist and must not create a cycle
+    pub(super) fn validate_dependencies(&self, id: usize, depends_on: &[usize]) -> Result<(), TaskError> {
+        for &dep in depends_on {
+            self.validate_task_id(dep).map_err(|_| TaskError::DependencyNotFound(dep))?;
+            self.validate_dependency_cycle(id, dep)?;
+        }
+
+        Ok(())
+    }
+
+    // Check that "id depends on dep" does not create a cycle (dep does not need to exist any more)
+    pub(super) fn validate_dependency_cycle(&self, id: usize, dep: usize) -> Result<(), TaskError> {
+        if dep == id {
+            return Err(TaskError::DependencyCycle(vec![id, id]));
+        }
+
+        // Adding "id depends on dep" closes a cycle if dep already (transitively) depends on id
+        if let Some(path) = self.dependency_path(dep, id) {
+            let mut cycle = vec![id];
+            cycle.extend(path);
+            return Err(TaskError::DependencyCycle(cycle));
+        }
+
+        Ok(())
+    }
+
+    // Path of dependencies from one task to another (both included), if any
+    fn dependency_path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![vec![from]];
+
+        while let Some(path) = stack.pop() {
+            let current = *path.last()?;
+
+            if current == to {
+                return Some(path);
+            }
+
+            if !visited.insert(current) {
+                continue;
+            }
+
+            if let Some(task) = self.get_task(current) {
+                for &dep in task.depends_on.iter().rev() {
+                    let mut next = path.clone();
+                    next.push(dep);
+                    stack.push(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    // Declare that a task depends on other tasks
+    pub fn add_task_dependencies(&mut self, id: usize, depends_on: &[usize]) -> Result<(), TaskError> {
+        let idx = self.validate_task_id(id)?;
+
+        self.validate_dependencies(id, depends_on)?;
+
+        self.tasks[idx].depends_on.extend(depends_on);
+
+        self.tasks[idx].updated_at = now_fixed();
+
+        Ok(())
+    }
+
+    // Remove dependencies from a task (tasks it does not depend on are ignored)
+    pub fn remove_task_dependencies(&mut self, id: usize, depends_on: &[usize]) -> Result<(), TaskError> {
+        let idx = self.validate_task_id(id)?;
+
+        for dep in depends_on {
+            self.tasks[idx].depends_on.remove(dep);
+        }
+
+        self.tasks[idx].updated_at = now_fixed();
+
+        Ok(())
+    }
+}
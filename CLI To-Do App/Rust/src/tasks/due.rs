@@ -0,0 +1,67 @@
+use chrono::{DateTime, Datelike, Days, FixedOffset, NaiveDate};
+
+use crate::dates::now_fixed;
+
+use super::types::{Task, TaskError, TaskStatus, Tasks};
+
+// Views of tasks based on their due date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueFilter {
+    Overdue,
+    Today,
+    Week,
+}
+
+// DueFilter implementation
+impl DueFilter {
+    // Check whether a task belongs to the view at the given moment
+    pub fn matches(self, task: &Task, now: DateTime<FixedOffset>) -> bool {
+        match self {
+            DueFilter::Overdue => task.is_overdue(now),
+            DueFilter::Today => task.is_due_today(now),
+            DueFilter::Week => task.is_due_this_week(now),
+        }
+    }
+}
+
+// Task implementation
+impl Task {
+    // Due date as a calendar day in the offset of `now`
+    fn due_date_at(&self, now: DateTime<FixedOffset>) -> Option<NaiveDate> {
+        self.due_at.map(|due| due.with_timezone(now.offset()).date_naive())
+    }
+
+    // Open task whose due date has already passed
+    pub fn is_overdue(&self, now: DateTime<FixedOffset>) -> bool {
+        self.status != TaskStatus::Completed && self.due_at.is_some_and(|due| due < now)
+    }
+
+    // Open task due on the same day as `now`
+    pub fn is_due_today(&self, now: DateTime<FixedOffset>) -> bool {
+        self.status != TaskStatus::Completed && self.due_date_at(now) == Some(now.date_naive())
+    }
+
+    // Open task due between Monday and Sunday of the week of `now`
+    pub fn is_due_this_week(&self, now: DateTime<FixedOffset>) -> bool {
+        let today = now.date_naive();
+        let monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+        let sunday = monday + Days::new(6);
+
+        self.status != TaskStatus::Completed
+            && self.due_date_at(now).is_some_and(|date| date >= monday && date <= sunday)
+    }
+}
+
+// Tasks implementation
+impl Tasks {
+    // Change due date method (None removes the due date)
+    pub fn change_task_due_date(&mut self, id: usize, due_at: Option<DateTime<FixedOffset>>) -> Result<(), TaskError> {
+        let idx = self.validate_task_id(id)?;
+
+        self.tasks[idx].due_at = due_at;
+
+        self.tasks[idx].updated_at = now_fixed();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use serde::de::{self, IgnoredAny, MapAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+use super::types::{Task, Tasks};
+
+// Implement Serialize for Tasks, stored as {"next_id": n, "tasks": [...]} (storage adds the version)
+impl Serialize for Tasks {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Tasks", 2)?;
+        state.serialize_field("next_id", &self.next_id)?;
+        state.serialize_field("tasks", &self.tasks)?;
+        state.end()
+    }
+}
+
+// Implement Deserialize for Tasks (older layouts are migrated by storage before reaching here)
+impl<'de> Deserialize<'de> for Tasks {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(TasksVisitor)
+    }
+}
+
+// Visitor for the stored layout
+struct TasksVisitor;
+
+// Implement Visitor for TasksVisitor
+impl<'de> Visitor<'de> for TasksVisitor {
+    type Value = Tasks;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "un objeto con next_id y tasks")
+    }
+
+    // Object with the persisted counter (the counter is rebuilt from the highest ID when missing)
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Tasks, A::Error> {
+        let mut next_id: Option<usize> = None;
+        let mut tasks: Option<Vec<Task>> = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "next_id" => next_id = Some(map.next_value()?),
+                "tasks" => tasks = Some(map.next_value()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        let tasks = tasks.ok_or_else(|| de::Error::missing_field("tasks"))?;
+
+        Tasks::from_parts(tasks, next_id.unwrap_or(1)).map_err(de::Error::custom)
+    }
+}
@@ -1,45 +1,74 @@
-use comfy_table::{Cell, Table};
+use chrono::{DateTime, FixedOffset};
 
-use super::types::{TaskStatus, Tasks};
+use super::due::DueFilter;
+use super::types::{Task, TaskPriority, TaskStatus, Tasks};
 
-// Tasks implementation
-impl Tasks {
-    // GET method
-    pub fn get_tasks(&self) {
-        let mut table = Table::new();
-        
-        table.set_header(vec![
-            Cell::new("ID"),
-            Cell::new("Título"),
-            Cell::new("Descripción"),
-            Cell::new("Creado en"),
-            Cell::new("Actualizado en"),
-            Cell::new("Estado"),
-            Cell::new("Completado en"),
-        ]);
-
-        for task in self.iter() {
-            if !task.visible {
-                continue;
-            }
-
-            let completed_at = if task.status == TaskStatus::Completed {
-                task.completed_at.format("%d-%m-%Y %H:%M:%S").to_string()
-            } else {
-                String::new()
-            };
-
-            table.add_row(vec![
-                Cell::new(task.id.to_string()),
-                Cell::new(&task.title),
-                Cell::new(&task.description),
-                Cell::new(task.created_at.format("%d-%m-%Y %H:%M:%S").to_string()),
-                Cell::new(task.updated_at.format("%d-%m-%Y %H:%M:%S").to_string()),
-                Cell::new(task.status.stringify()),
-                Cell::new(completed_at),
-            ]);
+// Filters applied when listing tasks (fields left as None match every task)
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    pub priority: Option<TaskPriority>,
+    pub due: Option<DueFilter>,
+    pub tags: Vec<String>,
+    pub not_tags: Vec<String>,
+    pub project: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub blocked: Option<bool>,
+}
+
+// TaskFilter implementation
+impl TaskFilter {
+    // Check whether a task passes the filter at the given moment
+    pub fn matches(&self, task: &Task, now: DateTime<FixedOffset>) -> bool {
+        if !task.visible {
+            return false;
+        }
+
+        if let Some(priority) = self.priority
+            && task.priority != priority
+        {
+            return false;
         }
 
-        println!("{}", table);
+        if let Some(due) = self.due
+            && !due.matches(task, now)
+        {
+            return false;
+        }
+
+        if !self.tags.iter().all(|tag| task.tags.contains(tag)) {
+            return false;
+        }
+
+        if self.not_tags.iter().any(|tag| task.tags.contains(tag)) {
+            return false;
+        }
+
+        if let Some(project) = &self.project
+            && !task.in_project(project)
+        {
+            return false;
+        }
+
+        if let Some(status) = self.status
+            && task.status != status
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+// Tasks implementation
+impl Tasks {
+    // Filter tasks at the given moment, sorted by priority (highest first) and then by ID
+    pub fn filter_tasks_at(&self, filter: &TaskFilter, now: DateTime<FixedOffset>) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self
+            .iter()
+            .filter(|task| filter.matches(task, now))
+            .filter(|task| filter.blocked.is_none_or(|blocked| self.is_blocked(task.id) == blocked))
+            .collect();
+        tasks.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.id.cmp(&b.id)));
+        tasks
     }
 }
@@ -1,12 +1,28 @@
 mod types;
 mod add;
+mod archive;
 mod delete;
+mod dependencies;
+mod due;
+mod file;
 mod list;
+mod priority;
+mod projects;
+mod recurrence;
 mod status;
+mod subtasks;
+mod tags;
+mod trash;
 mod update;
 mod validate;
 
-pub use types::{TaskStatus, Tasks};
+pub use due::DueFilter;
+pub use list::TaskFilter;
+pub use projects::normalize_project;
+pub use recurrence::Recurrence;
+pub use tags::normalize_tag;
+pub use types::{Task, TaskError, TaskOptions, TaskPriority, TaskStatus, Tasks};
+pub use update::TaskFields;
 
 // Test configuration
 #[cfg(test)]
@@ -0,0 +1,17 @@
+use crate::dates::now_fixed;
+
+use super::types::{TaskError, TaskPriority, Tasks};
+
+// Tasks implementation
+impl Tasks {
+    // Change TaskPriority method
+    pub fn change_task_priority(&mut self, id: usize, new_priority: TaskPriority) -> Result<(), TaskError> {
+        let idx = self.validate_task_id(id)?;
+
+        self.tasks[idx].priority = new_priority;
+
+        self.tasks[idx].updated_at = now_fixed();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use comfy_table::{Cell, CellAlignment, Table};
+use std::collections::BTreeMap;
+
+use crate::dates::now_fixed;
+
+use super::types::{Task, TaskError, TaskStatus, Tasks};
+
+// Project assigned to tasks created without one (and to tasks stored before projects existed)
+pub const DEFAULT_PROJECT: &str = "general";
+
+// Default value for the Project attribute when deserializing
+pub fn default_project() -> String {
+    DEFAULT_PROJECT.to_string()
+}
+
+// Normalize a project path: "Work.Backend" -> "work.backend" (nested projects are separated by dots)
+pub fn normalize_project(project: &str) -> Result<String, TaskError> {
+    let project = project.trim().to_lowercase();
+
+    let valid = project
+        .split('.')
+        .all(|segment| !segment.is_empty() && !segment.chars().any(|c| c.is_whitespace() || c == ','));
+
+    if !valid {
+        return Err(TaskError::InvalidProject);
+    }
+
+    Ok(project)
+}
+
+// Number of total and completed tasks in a project (including nested projects)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectSummary {
+    pub project: String,
+    pub total: usize,
+    pub completed: usize,
+}
+
+// ProjectSummary implementation
+impl ProjectSummary {
+    // Percentage of completed tasks
+    pub fn progress(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+
+        self.completed as f64 * 100.0 / self.total as f64
+    }
+}
+
+// Task implementation
+impl Task {
+    // Check whether the task belongs to the project or to one of its nested projects
+    pub fn in_project(&self, project: &str) -> bool {
+        self.project == project
+            || self.project.strip_prefix(project).is_some_and(|rest| rest.starts_with('.'))
+    }
+}
+
+// Tasks implementation
+impl Tasks {
+    // Move a task to another project
+    pub fn move_task(&mut self, id: usize, project: &str) -> Result<(), TaskError> {
+        let idx = self.validate_task_id(id)?;
+
+        let project = normalize_project(project)?;
+
+        self.tasks[idx].project = project;
+
+        self.tasks[idx].updated_at = now_fixed();
+
+        Ok(())
+    }
+
+    // Summarize visible tasks per project, adding nested projects to their parents
+    pub fn project_summary(&self) -> Vec<ProjectSummary> {
+        let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+
+        for task in self.iter().filter(|task| task.visible) {
+            let completed = usize::from(task.status == TaskStatus::Completed);
+
+            // "work.backend" counts for "work" and "work.backend"
+            let mut path = String::new();
+
+            for segment in task.project.split('.') {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+
+                path.push_str(segment);
+
+                let entry = counts.entry(path.clone()).or_default();
+                entry.0 += 1;
+                entry.1 += completed;
+            }
+        }
+
+        counts
+            .into_iter()
+            .map(|(project, (total, completed))| ProjectSummary { project, total, completed })
+            .collect()
+    }
+
+    // GET method for projects
+    pub fn get_projects(&self) {
+        let mut table = Table::new();
+
+        table.set_header(vec![
+            Cell::new("Proyecto"),
+            Cell::new("Tareas"),
+            Cell::new("Completadas"),
+            Cell::new("Progreso"),
+        ]);
+
+        for summary in self.project_summary() {
+            // Indent nested projects
+            let depth = summary.project.matches('.').count();
+            let name = summary.project.rsplit('.').next().unwrap_or_default();
+
+            table.add_row(vec![
+                Cell::new(format!("{}{}", "  ".repeat(depth), name)),
+                Cell::new(summary.total.to_string()).set_alignment(CellAlignment::Right),
+                Cell::new(summary.completed.to_string()).set_alignment(CellAlignment::Right),
+                Cell::new(format!("{:.0}%", summary.progress())).set_alignment(CellAlignment::Right),
+            ]);
+        }
+
+        println!("{}", table);
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::{DateTime, Datelike, Days, FixedOffset, Months, NaiveDate, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::dates::{end_of_day, normalize, now_fixed, parse_weekday};
+
+use super::types::{zero_datetime, Task, TaskError, TaskStatus, Tasks};
+
+// Recurrence rule of a task, stored as text ("daily", "weekly:mon,fri", "monthly:15", "every:3")
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Recurrence {
+    // Every day
+    Daily,
+    // On the given weekdays (the weekday of the due date when empty)
+    Weekly(Vec<Weekday>),
+    // On day N of every month (the last day in shorter months)
+    Monthly(u32),
+    // N days after the task was completed
+    EveryDays(u32),
+}
+
+// Spanish abbreviations for weekdays
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "lun",
+        Weekday::Tue => "mar",
+        Weekday::Wed => "mié",
+        Weekday::Thu => "jue",
+        Weekday::Fri => "vie",
+        Weekday::Sat => "sáb",
+        Weekday::Sun => "dom",
+    }
+}
+
+// Day N of the month starting at `first`, or its last day when the month is shorter
+fn day_of_month(first: NaiveDate, day: u32) -> Option<NaiveDate> {
+    let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+    first.with_day(day.min(last.day()))
+}
+
+// Recurrence implementation
+impl Recurrence {
+    // Recurrence stringify method
+    pub fn stringify(&self) -> String {
+        match self {
+            Recurrence::Daily => "Diaria".to_string(),
+            Recurrence::Weekly(days) if days.is_empty() => "Semanal".to_string(),
+            Recurrence::Weekly(days) => {
+                format!("Semanal ({})", days.iter().map(|&day| weekday_name(day)).collect::<Vec<_>>().join(", "))
+            }
+            Recurrence::Monthly(day) => format!("Mensual (día {})", day),
+            Recurrence::EveryDays(1) => "Cada día tras completarla".to_string(),
+            Recurrence::EveryDays(days) => format!("Cada {} días tras completarla", days),
+        }
+    }
+
+    // Next calendar date of the rule strictly after `date`
+    fn next_date(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Recurrence::Daily => date.checked_add_days(Days::new(1)),
+            Recurrence::Weekly(days) if days.is_empty() => date.checked_add_days(Days::new(7)),
+            Recurrence::Weekly(days) => (1..=7)
+                .filter_map(|n| date.checked_add_days(Days::new(n)))
+                .find(|next| days.contains(&next.weekday())),
+            Recurrence::Monthly(day) => {
+                let first = date.with_day(1)?;
+                let this_month = day_of_month(first, *day)?;
+
+                if this_month > date {
+                    Some(this_month)
+                } else {
+                    day_of_month(first.checked_add_months(Months::new(1))?, *day)
+                }
+            }
+            Recurrence::EveryDays(days) => date.checked_add_days(Days::new(*days as u64)),
+        }
+    }
+
+    // Due date of the next instance: calendar rules continue from the previous due date (or the
+    // completion day) until they are after the completion, keeping the time of the due date
+    pub fn next_due(&self, due_at: Option<DateTime<FixedOffset>>, completed_at: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+        let offset = due_at.map_or(*completed_at.offset(), |due| *due.offset());
+        let completed_at = completed_at.with_timezone(&offset);
+        let time = due_at.map_or_else(end_of_day, |due| due.time());
+        let resolve = |date: NaiveDate| offset.from_local_datetime(&date.and_time(time)).single();
+
+        if let Recurrence::EveryDays(_) = self {
+            return resolve(self.next_date(completed_at.date_naive())?);
+        }
+
+        let mut date = self.next_date(due_at.map_or(completed_at.date_naive(), |due| due.date_naive()))?;
+
+        while resolve(date)? <= completed_at {
+            date = self.next_date(date)?;
+        }
+
+        resolve(date)
+    }
+}
+
+// Implement fmt::Display for Recurrence (the stored representation)
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly(days) if days.is_empty() => write!(f, "weekly"),
+            Recurrence::Weekly(days) => {
+                let days: Vec<String> = days.iter().map(|day| day.to_string().to_lowercase()).collect();
+                write!(f, "weekly:{}", days.join(","))
+            }
+            Recurrence::Monthly(day) => write!(f, "monthly:{}", day),
+            Recurrence::EveryDays(days) => write!(f, "every:{}", days),
+        }
+    }
+}
+
+// Implement FromStr for Recurrence (English or Spanish names, e.g. "semanal:lun,vie", "cada:3")
+impl FromStr for Recurrence {
+    type Err = TaskError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let normalized = normalize(value.trim());
+
+        let (kind, argument) = match normalized.split_once(':') {
+            Some((kind, argument)) => (kind.trim(), Some(argument.trim())),
+            None => (normalized.as_str(), None),
+        };
+
+        match (kind, argument) {
+            ("daily" | "diaria", None) => Ok(Recurrence::Daily),
+            ("weekly" | "semanal", None) => Ok(Recurrence::Weekly(Vec::new())),
+            ("weekly" | "semanal", Some(days)) => {
+                let mut days = days
+                    .split(',')
+                    .map(|day| parse_weekday(day.trim()).ok_or(TaskError::InvalidRecurrence))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                days.sort_by_key(|day| day.num_days_from_monday());
+                days.dedup();
+
+                Ok(Recurrence::Weekly(days))
+            }
+            ("monthly" | "mensual", Some(day)) => match day.parse::<u32>() {
+                Ok(day) if (1..=31).contains(&day) => Ok(Recurrence::Monthly(day)),
+                _ => Err(TaskError::InvalidRecurrence),
+            },
+            ("every" | "cada", Some(days)) => match days.trim_end_matches('d').parse::<u32>() {
+                Ok(days) if days > 0 => Ok(Recurrence::EveryDays(days)),
+                _ => Err(TaskError::InvalidRecurrence),
+            },
+            _ => Err(TaskError::InvalidRecurrence),
+        }
+    }
+}
+
+// Convert stored text into Recurrence (used by serde)
+impl TryFrom<String> for Recurrence {
+    type Error = TaskError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+// Convert Recurrence into stored text (used by serde)
+impl From<Recurrence> for String {
+    fn from(recurrence: Recurrence) -> Self {
+        recurrence.to_string()
+    }
+}
+
+// Tasks implementation
+impl Tasks {
+    // Change (or remove) the recurrence rule of a task
+    pub fn change_task_recurrence(&mut self, id: usize, recurrence: Option<Recurrence>) -> Result<(), TaskError> {
+        let idx = self.validate_task_id(id)?;
+
+        self.tasks[idx].recurrence = recurrence;
+        self.tasks[idx].updated_at = now_fixed();
+
+        Ok(())
+    }
+
+    // Create the next instance of a completed recurring task (returns its ID). The rule moves to
+    // the new task, so the completed one stays in history and is not repeated again if reopened
+    pub(super) fn renew_task(&mut self, idx: usize) -> Option<usize> {
+        let recurrence = self.tasks[idx].recurrence.take()?;
+        let previous = &self.tasks[idx];
+        let now = now_fixed();
+        let id = self.next_id;
+
+        let task = Task {
+            id,
+            uuid: Some(Uuid::new_v4()),
+            title: previous.title.clone(),
+            description: previous.description.clone(),
+            created_at: now,
+            updated_at: now,
+            completed_at: zero_datetime(),
+            due_at: recurrence.next_due(previous.due_at, previous.completed_at),
+            status: TaskStatus::Pending,
+            priority: previous.priority,
+            tags: previous.tags.clone(),
+            project: previous.project.clone(),
+            parent: previous.parent,
+            depends_on: BTreeSet::new(),
+            recurrence: Some(recurrence),
+            visible: true,
+        };
+
+        self.push_task(task);
+
+        Some(id)
+    }
+}
@@ -1,37 +1,47 @@
-use chrono::{DateTime, FixedOffset, Local};
-use chrono::Offset;
+use crate::dates::now_fixed;
 
 use super::types::{TaskError, TaskStatus, Tasks};
 
-// Get local datetime with timezone
-fn now_fixed() -> DateTime<FixedOffset> {
-    let local_now = Local::now();
-    let offset = local_now.offset().fix();
-    local_now.with_timezone(&offset)
-}
-
 // Tasks implementation
 impl Tasks {
-    // Change TaskStatus method
-    pub fn change_task_status(&mut self, id: usize, new_status: TaskStatus) -> Result<(), TaskError> {
+    // Change TaskStatus method (blocked tasks cannot be started or completed). Returns the ID of
+    // the next instance when a recurring task is completed
+    pub fn change_task_status(&mut self, id: usize, new_status: TaskStatus) -> Result<Option<usize>, TaskError> {
         self.validate_task_id(id)?;
 
-        let idx = id - 1;
-
         match new_status {
-            TaskStatus::Pending | TaskStatus::InProgress | TaskStatus::Completed => {}
+            TaskStatus::InProgress | TaskStatus::Completed => {
+                let blockers = self.blockers(id);
+
+                if !blockers.is_empty() {
+                    return Err(TaskError::Blocked(blockers));
+                }
+            }
+            TaskStatus::Pending => {}
         }
 
-        self[idx].status = new_status;
+        self.force_task_status(id, new_status)
+    }
+
+    // Change TaskStatus method, ignoring dependencies
+    pub fn force_task_status(&mut self, id: usize, new_status: TaskStatus) -> Result<Option<usize>, TaskError> {
+        let idx = self.validate_task_id(id)?;
+        let was_completed = self.tasks[idx].status == TaskStatus::Completed;
+
+        self.tasks[idx].status = new_status;
 
         if matches!(new_status, TaskStatus::Completed) {
-            self[idx].completed_at = now_fixed();
+            self.tasks[idx].completed_at = now_fixed();
         } else {
-            self[idx].completed_at = super::types::zero_datetime();
+            self.tasks[idx].completed_at = super::types::zero_datetime();
         }
 
-        self[idx].updated_at = now_fixed();
+        self.tasks[idx].updated_at = now_fixed();
+
+        if new_status == TaskStatus::Completed && !was_completed {
+            return Ok(self.renew_task(idx));
+        }
 
-        return Ok(());
+        Ok(None)
     }
 }
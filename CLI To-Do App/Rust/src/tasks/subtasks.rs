@@ -0,0 +1,114 @@
+use std::collections::{HashMap, HashSet};
+
+use super::types::{Task, TaskError, TaskStatus, Tasks};
+
+// Tasks implementation
+impl Tasks {
+    // Visible direct children of a task, in insertion order
+    pub fn children(&self, id: usize) -> Vec<&Task> {
+        self.iter()
+            .filter(|task| task.visible && task.parent == Some(id))
+            .collect()
+    }
+
+    // IDs of all visible descendants of a task (children first, then their children)
+    pub fn descendants(&self, id: usize) -> Vec<usize> {
+        let mut by_parent: HashMap<usize, Vec<usize>> = HashMap::new();
+
+        for task in self.iter().filter(|task| task.visible) {
+            if let Some(parent) = task.parent {
+                by_parent.entry(parent).or_default().push(task.id);
+            }
+        }
+
+        // Visited set guards against cycles in hand-edited files
+        let mut visited = HashSet::from([id]);
+        let mut descendants = Vec::new();
+        let mut pending = vec![id];
+
+        while let Some(current) = pending.pop() {
+            for &child in by_parent.get(&current).into_iter().flatten() {
+                if visited.insert(child) {
+                    descendants.push(child);
+                    pending.push(child);
+                }
+            }
+        }
+
+        descendants
+    }
+
+    // Open (not completed) descendants of a task
+    pub fn open_descendants(&self, id: usize) -> Vec<usize> {
+        self.descendants(id)
+            .into_iter()
+            .filter(|&child| self.get_task(child).is_some_and(|task| task.status != TaskStatus::Completed))
+            .collect()
+    }
+
+    // Progress of the direct children of a task as (completed, total), None when it has no children
+    pub fn subtask_progress(&self, id: usize) -> Option<(usize, usize)> {
+        let children = self.children(id);
+
+        if children.is_empty() {
+            return None;
+        }
+
+        let completed = children.iter().filter(|task| task.status == TaskStatus::Completed).count();
+
+        Some((completed, children.len()))
+    }
+
+    // Complete a task and all of its open descendants (returns the completed descendants). Nothing
+    // changes if any of them is blocked
+    pub fn complete_task_tree(&mut self, id: usize) -> Result<Vec<usize>, TaskError> {
+        self.validate_task_id(id)?;
+
+        let open = self.open_descendants(id);
+        let mut completed = self.clone();
+
+        for &child in &open {
+            completed.change_task_status(child, TaskStatus::Completed)?;
+        }
+
+        completed.change_task_status(id, TaskStatus::Completed)?;
+        *self = completed;
+
+        Ok(open)
+    }
+
+    // Arrange tasks as a tree: each task is followed by its children (tasks whose parent is not
+    // in the list are shown as roots), keeping the given order among siblings
+    pub fn tree_order<'a>(&self, tasks: Vec<&'a Task>) -> Vec<(&'a Task, usize)> {
+        let ids: HashSet<usize> = tasks.iter().map(|task| task.id).collect();
+        let mut roots = Vec::new();
+        let mut by_parent: HashMap<usize, Vec<&'a Task>> = HashMap::new();
+
+        for task in tasks {
+            match task.parent {
+                Some(parent) if ids.contains(&parent) && parent != task.id => {
+                    by_parent.entry(parent).or_default().push(task)
+                }
+                _ => roots.push(task),
+            }
+        }
+
+        let mut ordered = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending: Vec<(&Task, usize)> = roots.into_iter().rev().map(|task| (task, 0)).collect();
+
+        while let Some((task, depth)) = pending.pop() {
+            if !visited.insert(task.id) {
+                continue;
+            }
+
+            ordered.push((task, depth));
+
+            if let Some(children) = by_parent.get(&task.id) {
+                pending.extend(children.iter().rev().map(|&child| (child, depth + 1)));
+            }
+        }
+
+        ordered
+    }
+}
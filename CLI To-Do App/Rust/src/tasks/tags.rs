@@ -0,0 +1,102 @@
+use comfy_table::{Cell, Table};
+use std::collections::BTreeMap;
+
+use crate::dates::now_fixed;
+
+use super::types::{TaskError, TaskStatus, Tasks};
+
+// Normalize a tag: "+Work" and "work" are the same tag
+pub fn normalize_tag(tag: &str) -> Result<String, TaskError> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix('+').unwrap_or(tag).to_lowercase();
+
+    if tag.is_empty() || tag.chars().any(|c| c.is_whitespace() || c == ',' || c == '+') {
+        return Err(TaskError::InvalidTag);
+    }
+
+    Ok(tag)
+}
+
+// Number of open and completed tasks with a tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagSummary {
+    pub tag: String,
+    pub open: usize,
+    pub completed: usize,
+}
+
+// Tasks implementation
+impl Tasks {
+    // Add tags to a task
+    pub fn add_task_tags(&mut self, id: usize, tags: &[String]) -> Result<(), TaskError> {
+        let idx = self.validate_task_id(id)?;
+
+        let tags = tags.iter().map(|tag| normalize_tag(tag)).collect::<Result<Vec<_>, _>>()?;
+
+        self.tasks[idx].tags.extend(tags);
+
+        self.tasks[idx].updated_at = now_fixed();
+
+        Ok(())
+    }
+
+    // Remove tags from a task (tags the task does not have are ignored)
+    pub fn remove_task_tags(&mut self, id: usize, tags: &[String]) -> Result<(), TaskError> {
+        let idx = self.validate_task_id(id)?;
+
+        let tags = tags.iter().map(|tag| normalize_tag(tag)).collect::<Result<Vec<_>, _>>()?;
+
+        for tag in &tags {
+            self.tasks[idx].tags.remove(tag);
+        }
+
+        self.tasks[idx].updated_at = now_fixed();
+
+        Ok(())
+    }
+
+    // Count open and completed visible tasks per tag, sorted by tag name
+    pub fn tag_summary(&self) -> Vec<TagSummary> {
+        let mut counts: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+
+        for task in self.iter().filter(|task| task.visible) {
+            for tag in &task.tags {
+                let entry = counts.entry(tag).or_default();
+
+                if task.status == TaskStatus::Completed {
+                    entry.1 += 1;
+                } else {
+                    entry.0 += 1;
+                }
+            }
+        }
+
+        counts
+            .into_iter()
+            .map(|(tag, (open, completed))| TagSummary { tag: tag.to_string(), open, completed })
+            .collect()
+    }
+
+    // GET method for tags
+    pub fn get_tags(&self) {
+        let mut table = Table::new();
+
+        table.set_header(vec![
+            Cell::new("Etiqueta"),
+            Cell::new("Abiertas"),
+            Cell::new("Completadas"),
+            Cell::new("Total"),
+        ]);
+
+        for summary in self.tag_summary() {
+            table.add_row(vec![
+                Cell::new(format!("+{}", summary.tag)),
+                Cell::new(summary.open.to_string()),
+                Cell::new(summary.completed.to_string()),
+                Cell::new((summary.open + summary.completed).to_string()),
+            ]);
+        }
+
+        println!("{}", table);
+    }
+}
@@ -1,5 +1,12 @@
 use super::Tasks;
+use crate::dates::reference_now;
+use crate::render::{render_tasks, Layout, OutputFormat};
 use super::TaskStatus;
+use super::{normalize_project, normalize_tag, DueFilter, Recurrence, TaskFields, TaskFilter, TaskOptions, TaskPriority};
+use super::projects::ProjectSummary;
+use super::tags::TagSummary;
+use super::types::{Task, TaskError};
+use chrono::{DateTime, Duration as ChronoDuration, Weekday};
 use std::{thread, time::Duration};
 
 #[test]
@@ -14,8 +21,8 @@ fn test_task_status_stringify() {
 // Test: Validate Task ID (valid ID)
 fn test_validate_task_id_valid() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
-    tasks.add_task("Title 2 for testing".into(), "Description 2 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 2 for testing".into(), "Description 2 for testing".into(), TaskOptions::default()).unwrap();
     assert!(tasks.validate_task_id(1).is_ok());
     assert!(tasks.validate_task_id(2).is_ok());
 }
@@ -24,7 +31,7 @@ fn test_validate_task_id_valid() {
 // Test: Validate Task ID (invalid ID)
 fn test_validate_task_id_invalid() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     assert!(tasks.validate_task_id(0).is_err());
     assert!(tasks.validate_task_id(5).is_err());
 }
@@ -33,25 +40,25 @@ fn test_validate_task_id_invalid() {
 // Test: Validate Task ID (deleted task)
 fn test_validate_task_id_invisible() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     tasks.delete_task(1).unwrap();
     assert!(tasks.validate_task_id(1).is_err());
 }
 
 #[test]
-// Test: Get tasks method
-fn test_get_tasks() {
+// Test: List tasks as a table
+fn test_list_tasks_table() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
-    tasks.add_task("Title 2 for testing".into(), "Description 2 for testing".into()).unwrap();
-    tasks.get_tasks();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 2 for testing".into(), "Description 2 for testing".into(), TaskOptions::default()).unwrap();
+    assert!(render_tasks(&tasks, &TaskFilter::default(), None, OutputFormat::Table, &Layout::default(), reference_now()).unwrap().contains("Title"));
 }
 
 #[test]
 // Test: Add task method (with title and description)
 fn test_add_task_valid() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     assert_eq!(tasks.len(), 1);
     let task = &tasks[0];
     assert_eq!(task.title, "Title 1 for testing");
@@ -65,14 +72,14 @@ fn test_add_task_valid() {
 // Test: Add task method (with empty title)
 fn test_add_task_empty_title() {
     let mut tasks = Tasks::default();
-    assert!(tasks.add_task("".into(), "Description for testing".into()).is_err());
+    assert!(tasks.add_task_with("".into(), "Description for testing".into(), TaskOptions::default()).is_err());
 }
 
 #[test]
 // Test: Update task (with title)
 fn test_update_task_title() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     tasks.update_task(1, "Title 2 for testing".into(), "".into()).unwrap();
     assert_eq!(tasks[0].title, "Title 2 for testing");
     assert_eq!(tasks[0].description, "Description 1 for testing");
@@ -82,7 +89,7 @@ fn test_update_task_title() {
 // Test: Update task (with description)
 fn test_update_task_description() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     tasks.update_task(1, "".into(), "Description 2 for testing".into()).unwrap();
     assert_eq!(tasks[0].title, "Title 1 for testing");
     assert_eq!(tasks[0].description, "Description 2 for testing");
@@ -92,7 +99,7 @@ fn test_update_task_description() {
 // Test: Update task (with both title and description)
 fn test_update_task_both() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     tasks.update_task(1, "Title 2 for testing".into(), "Description 2 for testing".into()).unwrap();
     assert_eq!(tasks[0].title, "Title 2 for testing");
     assert_eq!(tasks[0].description, "Description 2 for testing");
@@ -102,7 +109,7 @@ fn test_update_task_both() {
 // Test: Update task (with empty fields)
 fn test_update_task_empty_fields() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     assert!(tasks.update_task(1, "".into(), "".into()).is_err());
 }
 
@@ -117,18 +124,66 @@ fn test_update_task_invalid_id() {
 // Test: Update task (modifies updated_at timestamp)
 fn test_update_task_updates_timestamp() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     let created_at = tasks[0].updated_at;
     thread::sleep(Duration::from_millis(10));
     tasks.update_task(1, "Title 2 for testing".into(), "Description 2 for testing".into()).unwrap();
     assert!(tasks[0].updated_at > created_at);
 }
 
+#[test]
+// Test: Replace every editable field (the description can be cleared; invalid fields change nothing)
+fn test_replace_task_fields() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 2 for testing".into(), "".into(), TaskOptions::default()).unwrap();
+
+    let mut fields = TaskFields::from(&tasks[0]);
+    assert!(!tasks.replace_task_fields(1, fields.clone()).unwrap());
+
+    fields.description = String::new();
+    fields.tags = ["+Trabajo".to_string()].into();
+    fields.project = "Casa.Jardin".into();
+    fields.depends_on = [2].into();
+    fields.recurrence = Some(Recurrence::Daily);
+    assert!(tasks.replace_task_fields(1, fields.clone()).unwrap());
+    assert_eq!(tasks[0].description, "");
+    assert_eq!(tasks[0].tags, ["trabajo".to_string()].into());
+    assert_eq!(tasks[0].project, "casa.jardin");
+    assert!(tasks.is_blocked(1));
+
+    let before = tasks.clone();
+    assert!(matches!(tasks.replace_task_fields(1, TaskFields { title: " ".into(), ..fields.clone() }), Err(TaskError::EmptyTitle)));
+    assert!(matches!(tasks.replace_task_fields(1, TaskFields { depends_on: [3].into(), ..fields.clone() }), Err(TaskError::DependencyNotFound(3))));
+    assert!(matches!(tasks.replace_task_fields(2, TaskFields { depends_on: [1].into(), ..TaskFields::from(&tasks[1]) }), Err(TaskError::DependencyCycle(_))));
+    assert!(matches!(tasks.replace_task_fields(1, TaskFields { tags: ["a b".to_string()].into(), ..fields }), Err(TaskError::InvalidTag)));
+    assert_eq!(tasks, before);
+}
+
+#[test]
+// Test: Replace the fields of a task whose dependency was deleted (only new dependencies must exist)
+fn test_replace_task_fields_with_deleted_dependency() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 2 for testing".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 3 for testing".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_dependencies(1, &[2]).unwrap();
+    tasks.delete_task(2).unwrap();
+
+    let fields = TaskFields { title: "Edited".into(), ..TaskFields::from(&tasks[0]) };
+    assert!(tasks.replace_task_fields(1, fields.clone()).unwrap());
+    assert_eq!(tasks[0].title, "Edited");
+    assert_eq!(tasks[0].depends_on, [2].into());
+
+    tasks.delete_task(3).unwrap();
+    assert!(matches!(tasks.replace_task_fields(1, TaskFields { depends_on: [2, 3].into(), ..fields }), Err(TaskError::DependencyNotFound(3))));
+}
+
 #[test]
 // Test: Change task status to Pending
 fn test_change_task_status_to_pending() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     tasks.change_task_status(1, TaskStatus::Completed).unwrap();
     tasks.change_task_status(1, TaskStatus::Pending).unwrap();
     assert_eq!(tasks[0].status, TaskStatus::Pending);
@@ -138,7 +193,7 @@ fn test_change_task_status_to_pending() {
 // Test: Change task status to In Progress
 fn test_change_task_status_to_in_progress() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     tasks.change_task_status(1, TaskStatus::InProgress).unwrap();
     assert_eq!(tasks[0].status, TaskStatus::InProgress);
 }
@@ -147,7 +202,7 @@ fn test_change_task_status_to_in_progress() {
 // Test: Change task status to Completed
 fn test_change_task_status_to_completed() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     tasks.change_task_status(1, TaskStatus::Completed).unwrap();
     assert_eq!(tasks[0].status, TaskStatus::Completed);
     assert!(tasks[0].completed_at >= tasks[0].created_at);
@@ -164,7 +219,7 @@ fn test_change_task_status_invalid_id() {
 // Test: Change task status (updates updated_at timestamp)
 fn test_change_task_status_updates_timestamp() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     let created_at = tasks[0].updated_at;
     thread::sleep(Duration::from_millis(10));
     tasks.change_task_status(1, TaskStatus::Completed).unwrap();
@@ -175,7 +230,7 @@ fn test_change_task_status_updates_timestamp() {
 // Test: Delete task
 fn test_delete_task() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     tasks.delete_task(1).unwrap();
     assert!(!tasks[0].visible);
 }
@@ -191,9 +246,620 @@ fn test_delete_task_invalid_id() {
 // Test: Delete task (updates updated_at timestamp)
 fn test_delete_task_updates_timestamp() {
     let mut tasks = Tasks::default();
-    tasks.add_task("Title 1 for testing".into(), "Description 1 for testing".into()).unwrap();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
     let created_at = tasks[0].updated_at;
     thread::sleep(Duration::from_millis(10));
     tasks.delete_task(1).unwrap();
     assert!(tasks[0].updated_at > created_at);
 }
+
+#[test]
+// Test: TaskPriority stringify method
+fn test_task_priority_stringify() {
+    assert_eq!(TaskPriority::Low.stringify(), "Baja");
+    assert_eq!(TaskPriority::Medium.stringify(), "Media");
+    assert_eq!(TaskPriority::High.stringify(), "Alta");
+    assert_eq!(TaskPriority::Urgent.stringify(), "Urgente");
+}
+
+#[test]
+// Test: Add task method (default priority)
+fn test_add_task_default_priority() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    assert_eq!(tasks[0].priority, TaskPriority::Medium);
+}
+
+#[test]
+// Test: Add task method (with priority)
+fn test_add_task_with_priority() {
+    let mut tasks = Tasks::default();
+    let options = TaskOptions { priority: TaskPriority::Urgent, ..Default::default() };
+    let id = tasks.add_task_with("Title 1 for testing".into(), "".into(), options).unwrap();
+    assert_eq!(id, 1);
+    assert_eq!(tasks[0].priority, TaskPriority::Urgent);
+}
+
+#[test]
+// Test: Change task priority
+fn test_change_task_priority() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.change_task_priority(1, TaskPriority::High).unwrap();
+    assert_eq!(tasks[0].priority, TaskPriority::High);
+}
+
+#[test]
+// Test: Change task priority (with invalid ID)
+fn test_change_task_priority_invalid_id() {
+    let mut tasks = Tasks::default();
+    assert!(tasks.change_task_priority(1, TaskPriority::High).is_err());
+}
+
+#[test]
+// Test: Filter tasks (sorted by priority, then by ID)
+fn test_filter_tasks_sorted_by_priority() {
+    let mut tasks = Tasks::default();
+    for priority in [TaskPriority::Low, TaskPriority::Urgent, TaskPriority::Medium, TaskPriority::Urgent] {
+        tasks.add_task_with("Title for testing".into(), "".into(), TaskOptions { priority, ..Default::default() }).unwrap();
+    }
+    tasks.delete_task(3).unwrap();
+    let ids: Vec<usize> = tasks.filter_tasks_at(&TaskFilter::default(), reference_now()).iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![2, 4, 1]);
+}
+
+#[test]
+// Test: Filter tasks (by priority)
+fn test_filter_tasks_by_priority() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "".into(), TaskOptions { priority: TaskPriority::High, ..Default::default() }).unwrap();
+    tasks.add_task_with("Title 2 for testing".into(), "".into(), TaskOptions { priority: TaskPriority::Low, ..Default::default() }).unwrap();
+    let filter = TaskFilter { priority: Some(TaskPriority::Low), ..Default::default() };
+    let filtered = tasks.filter_tasks_at(&filter, reference_now());
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].id, 2);
+}
+
+#[test]
+// Test: Add task method (with due date)
+fn test_add_task_with_due_date() {
+    let mut tasks = Tasks::default();
+    let due_at = Some(reference_now());
+    tasks.add_task_with("Title 1 for testing".into(), "".into(), TaskOptions { due_at, ..Default::default() }).unwrap();
+    assert_eq!(tasks[0].due_at, due_at);
+}
+
+#[test]
+// Test: Change task due date (set and remove)
+fn test_change_task_due_date() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.change_task_due_date(1, Some(reference_now())).unwrap();
+    assert_eq!(tasks[0].due_at, Some(reference_now()));
+    tasks.change_task_due_date(1, None).unwrap();
+    assert!(tasks[0].due_at.is_none());
+    assert!(tasks.change_task_due_date(2, None).is_err());
+}
+
+#[test]
+// Test: Overdue, due today and due this week views
+fn test_due_views() {
+    let now = reference_now();
+    let mut tasks = Tasks::default();
+    for due_at in [
+        now - ChronoDuration::days(1),  // 1: overdue (Tuesday)
+        now + ChronoDuration::hours(2), // 2: today
+        now + ChronoDuration::days(4),  // 3: Sunday, this week
+        now + ChronoDuration::days(5),  // 4: next Monday
+        now - ChronoDuration::days(3),  // 5: overdue, but completed
+    ] {
+        tasks.add_task_with("Title for testing".into(), "".into(), TaskOptions { due_at: Some(due_at), ..Default::default() }).unwrap();
+    }
+    tasks.add_task_with("Title without due date".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.change_task_status(5, TaskStatus::Completed).unwrap();
+
+    let ids = |due: DueFilter| -> Vec<usize> {
+        let filter = TaskFilter { due: Some(due), ..Default::default() };
+        tasks.filter_tasks_at(&filter, now).iter().map(|t| t.id).collect()
+    };
+
+    assert_eq!(ids(DueFilter::Overdue), vec![1]);
+    assert_eq!(ids(DueFilter::Today), vec![2]);
+    assert_eq!(ids(DueFilter::Week), vec![1, 2, 3]);
+}
+
+#[test]
+// Test: Due date uses the offset of the reference moment
+fn test_due_today_with_other_offset() {
+    let now = reference_now();
+    let mut tasks = Tasks::default();
+    let due_at = DateTime::parse_from_rfc3339("2025-11-06T02:00:00+00:00").unwrap(); // 22:00 at -04:00
+    tasks.add_task_with("Title for testing".into(), "".into(), TaskOptions { due_at: Some(due_at), ..Default::default() }).unwrap();
+    assert!(tasks[0].is_due_today(now));
+}
+
+#[test]
+// Test: Change task status to Completed keeps the due date
+fn test_change_task_status_keeps_due_date() {
+    let mut tasks = Tasks::default();
+    let due_at = Some(reference_now());
+    tasks.add_task_with("Title 1 for testing".into(), "".into(), TaskOptions { due_at, ..Default::default() }).unwrap();
+    tasks.change_task_status(1, TaskStatus::Completed).unwrap();
+    assert_eq!(tasks[0].due_at, due_at);
+    assert!(!tasks[0].is_overdue(reference_now() + ChronoDuration::days(1)));
+}
+
+#[test]
+// Test: Normalize tag
+fn test_normalize_tag() {
+    assert_eq!(normalize_tag("+Work").unwrap(), "work");
+    assert_eq!(normalize_tag(" urgente ").unwrap(), "urgente");
+    assert!(normalize_tag("+").is_err());
+    assert!(normalize_tag("two words").is_err());
+    assert!(normalize_tag("a,b").is_err());
+}
+
+#[test]
+// Test: Add task method (with tags)
+fn test_add_task_with_tags() {
+    let mut tasks = Tasks::default();
+    let tags = vec!["+work".to_string(), "Urgent".to_string(), "work".to_string()];
+    tasks.add_task_with("Title 1 for testing".into(), "".into(), TaskOptions { tags, ..Default::default() }).unwrap();
+    assert_eq!(tasks[0].tags.iter().collect::<Vec<_>>(), vec!["urgent", "work"]);
+    let tags = vec!["bad tag".to_string()];
+    assert!(tasks.add_task_with("Title 2 for testing".into(), "".into(), TaskOptions { tags, ..Default::default() }).is_err());
+    assert_eq!(tasks.len(), 1);
+}
+
+#[test]
+// Test: Add and remove task tags
+fn test_add_remove_task_tags() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "Description 1 for testing".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_tags(1, &["+work".into(), "+blocked".into()]).unwrap();
+    assert_eq!(tasks[0].tags.len(), 2);
+    tasks.remove_task_tags(1, &["blocked".into(), "missing".into()]).unwrap();
+    assert!(tasks[0].tags.contains("work"));
+    assert!(!tasks[0].tags.contains("blocked"));
+    assert!(tasks.add_task_tags(2, &["work".into()]).is_err());
+    assert!(tasks.add_task_tags(1, &["".into()]).is_err());
+}
+
+#[test]
+// Test: Filter tasks (by tags)
+fn test_filter_tasks_by_tags() {
+    let mut tasks = Tasks::default();
+    for tags in [vec!["work"], vec!["work", "blocked"], vec!["home"]] {
+        let tags = tags.into_iter().map(String::from).collect();
+        tasks.add_task_with("Title for testing".into(), "".into(), TaskOptions { tags, ..Default::default() }).unwrap();
+    }
+    let filter = TaskFilter { tags: vec!["work".into()], not_tags: vec!["blocked".into()], ..Default::default() };
+    let ids: Vec<usize> = tasks.filter_tasks_at(&filter, reference_now()).iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![1]);
+}
+
+#[test]
+// Test: Tag summary (open and completed counts, ignoring deleted tasks)
+fn test_tag_summary() {
+    let mut tasks = Tasks::default();
+    for tags in [vec!["work"], vec!["work", "home"], vec!["home"], vec!["work"]] {
+        let tags = tags.into_iter().map(String::from).collect();
+        tasks.add_task_with("Title for testing".into(), "".into(), TaskOptions { tags, ..Default::default() }).unwrap();
+    }
+    tasks.change_task_status(2, TaskStatus::Completed).unwrap();
+    tasks.delete_task(4).unwrap();
+    assert_eq!(tasks.tag_summary(), vec![
+        TagSummary { tag: "home".into(), open: 1, completed: 1 },
+        TagSummary { tag: "work".into(), open: 1, completed: 1 },
+    ]);
+    tasks.get_tags();
+}
+
+#[test]
+// Test: Normalize project
+fn test_normalize_project() {
+    assert_eq!(normalize_project("Work.Backend").unwrap(), "work.backend");
+    assert!(normalize_project("").is_err());
+    assert!(normalize_project("work..backend").is_err());
+    assert!(normalize_project("my work").is_err());
+}
+
+#[test]
+// Test: Add task method (default project and given project)
+fn test_add_task_with_project() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "".into(), TaskOptions::default()).unwrap();
+    let project = Some("Work.Backend".to_string());
+    tasks.add_task_with("Title 2 for testing".into(), "".into(), TaskOptions { project, ..Default::default() }).unwrap();
+    assert_eq!(tasks[0].project, "general");
+    assert_eq!(tasks[1].project, "work.backend");
+}
+
+#[test]
+// Test: Move task between projects
+fn test_move_task() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.move_task(1, "home").unwrap();
+    assert_eq!(tasks[0].project, "home");
+    assert!(tasks.move_task(1, "bad project").is_err());
+    assert!(tasks.move_task(2, "home").is_err());
+}
+
+#[test]
+// Test: Filter tasks (by project, including nested projects)
+fn test_filter_tasks_by_project() {
+    let mut tasks = Tasks::default();
+    for project in ["work", "work.backend", "workshop", "home"] {
+        let project = Some(project.to_string());
+        tasks.add_task_with("Title for testing".into(), "".into(), TaskOptions { project, ..Default::default() }).unwrap();
+    }
+    let filter = TaskFilter { project: Some("work".into()), ..Default::default() };
+    let ids: Vec<usize> = tasks.filter_tasks_at(&filter, reference_now()).iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+#[test]
+// Test: Project summary (nested projects count for their parents)
+fn test_project_summary() {
+    let mut tasks = Tasks::default();
+    for project in ["work", "work.backend", "work.backend", "home"] {
+        let project = Some(project.to_string());
+        tasks.add_task_with("Title for testing".into(), "".into(), TaskOptions { project, ..Default::default() }).unwrap();
+    }
+    tasks.change_task_status(2, TaskStatus::Completed).unwrap();
+    let summary = tasks.project_summary();
+    assert_eq!(summary, vec![
+        ProjectSummary { project: "home".into(), total: 1, completed: 0 },
+        ProjectSummary { project: "work".into(), total: 3, completed: 1 },
+        ProjectSummary { project: "work.backend".into(), total: 2, completed: 1 },
+    ]);
+    assert_eq!(summary[2].progress(), 50.0);
+    tasks.get_projects();
+}
+
+// Build a stored task with the given ID and title
+fn stored_task(id: usize, title: &str) -> Task {
+    Task { id, title: title.into(), ..Default::default() }
+}
+
+#[test]
+// Test: Task IDs are looked up by value, not by position
+fn test_task_ids_independent_of_position() {
+    let mut tasks = Tasks::from_parts(vec![stored_task(3, "Third"), stored_task(1, "First")], 1).unwrap();
+    assert!(tasks.validate_task_id(2).is_err());
+    tasks.update_task(1, "First (updated)".into(), "".into()).unwrap();
+    assert_eq!(tasks[1].title, "First (updated)");
+    assert_eq!(tasks[0].title, "Third");
+    assert_eq!(tasks.get_task(3).unwrap().title, "Third");
+}
+
+#[test]
+// Test: Next ID is monotonic (never below the highest stored ID, never reused)
+fn test_next_id_monotonic() {
+    let mut tasks = Tasks::from_parts(vec![stored_task(1, "First"), stored_task(5, "Fifth")], 2).unwrap();
+    assert_eq!(tasks.add_task_with("New".into(), "".into(), TaskOptions::default()).unwrap(), 6);
+    let mut tasks = Tasks::from_parts(vec![stored_task(1, "First")], 10).unwrap();
+    assert_eq!(tasks.add_task_with("New".into(), "".into(), TaskOptions::default()).unwrap(), 10);
+    assert_eq!(tasks.add_task_with("New".into(), "".into(), TaskOptions::default()).unwrap(), 11);
+}
+
+#[test]
+// Test: Duplicate IDs are rejected
+fn test_duplicate_task_ids() {
+    let err = Tasks::from_parts(vec![stored_task(1, "A"), stored_task(1, "B")], 1).unwrap_err();
+    assert!(matches!(err, TaskError::DuplicateTaskId(1)));
+}
+
+#[test]
+// Test: Add task method assigns a UUID
+fn test_add_task_assigns_uuid() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Title 1 for testing".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Title 2 for testing".into(), "".into(), TaskOptions::default()).unwrap();
+    assert!(tasks[0].uuid.is_some());
+    assert_ne!(tasks[0].uuid, tasks[1].uuid);
+}
+
+// Add a subtask with default attributes
+fn add_subtask(tasks: &mut Tasks, parent: usize) -> usize {
+    tasks.add_task_with("Subtask for testing".into(), "".into(), TaskOptions { parent: Some(parent), ..Default::default() }).unwrap()
+}
+
+#[test]
+// Test: Add subtask (inherits the project of its parent)
+fn test_add_subtask() {
+    let mut tasks = Tasks::default();
+    let project = Some("work".to_string());
+    tasks.add_task_with("Parent".into(), "".into(), TaskOptions { project, ..Default::default() }).unwrap();
+    let id = add_subtask(&mut tasks, 1);
+    assert_eq!(tasks.get_task(id).unwrap().parent, Some(1));
+    assert_eq!(tasks.get_task(id).unwrap().project, "work");
+    let options = TaskOptions { parent: Some(9), ..Default::default() };
+    assert!(matches!(tasks.add_task_with("Orphan".into(), "".into(), options), Err(TaskError::ParentNotFound)));
+}
+
+#[test]
+// Test: Children, descendants and progress of a parent task
+fn test_subtask_progress() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Parent".into(), "".into(), TaskOptions::default()).unwrap();
+    let child_1 = add_subtask(&mut tasks, 1);
+    let child_2 = add_subtask(&mut tasks, 1);
+    let grandchild = add_subtask(&mut tasks, child_2);
+    tasks.change_task_status(child_1, TaskStatus::Completed).unwrap();
+    assert_eq!(tasks.children(1).len(), 2);
+    assert_eq!(tasks.descendants(1), vec![child_1, child_2, grandchild]);
+    assert_eq!(tasks.open_descendants(1), vec![child_2, grandchild]);
+    assert_eq!(tasks.subtask_progress(1), Some((1, 2)));
+    assert_eq!(tasks.subtask_progress(child_1), None);
+}
+
+#[test]
+// Test: Complete a task tree
+fn test_complete_task_tree() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Parent".into(), "".into(), TaskOptions::default()).unwrap();
+    let child = add_subtask(&mut tasks, 1);
+    let grandchild = add_subtask(&mut tasks, child);
+    assert_eq!(tasks.complete_task_tree(1).unwrap(), vec![child, grandchild]);
+    assert!(tasks.iter().all(|task| task.status == TaskStatus::Completed));
+    assert!(tasks.open_descendants(1).is_empty());
+}
+
+#[test]
+// Test: Complete a task tree with a blocked grandchild changes nothing
+fn test_complete_task_tree_blocked() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Parent".into(), "".into(), TaskOptions::default()).unwrap();
+    let child = add_subtask(&mut tasks, 1);
+    let grandchild = add_subtask(&mut tasks, child);
+    let blocker = tasks.add_task_with("Blocker".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_dependencies(grandchild, &[blocker]).unwrap();
+
+    let before = tasks.clone();
+    assert!(matches!(tasks.complete_task_tree(1), Err(TaskError::Blocked(ids)) if ids == vec![blocker]));
+    assert_eq!(tasks, before);
+}
+
+#[test]
+// Test: Delete a parent task deletes its subtasks
+fn test_delete_task_with_subtasks() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Parent".into(), "".into(), TaskOptions::default()).unwrap();
+    let child = add_subtask(&mut tasks, 1);
+    add_subtask(&mut tasks, child);
+    tasks.add_task_with("Other".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.delete_task(1).unwrap();
+    assert_eq!(tasks.iter().filter(|task| task.visible).count(), 1);
+    assert!(tasks.validate_task_id(child).is_err());
+}
+
+#[test]
+// Test: Tree order (children under their parent, with depth)
+fn test_tree_order() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Parent".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Other".into(), "".into(), TaskOptions::default()).unwrap();
+    let child = add_subtask(&mut tasks, 1);
+    let grandchild = add_subtask(&mut tasks, child);
+    let ordered: Vec<(usize, usize)> = tasks
+        .tree_order(tasks.filter_tasks_at(&TaskFilter::default(), reference_now()))
+        .iter()
+        .map(|(task, depth)| (task.id, *depth))
+        .collect();
+    assert_eq!(ordered, vec![(1, 0), (child, 1), (grandchild, 2), (2, 0)]);
+    // A subtask listed without its parent is shown as a root
+    let only_child = vec![tasks.get_task(child).unwrap()];
+    assert_eq!(tasks.tree_order(only_child)[0].1, 0);
+    assert!(render_tasks(&tasks, &TaskFilter::default(), None, OutputFormat::Table, &Layout::default(), reference_now()).unwrap().contains("└─ "));
+}
+
+#[test]
+// Test: Blocked and actionable tasks
+fn test_task_dependencies_blocked() {
+    let mut tasks = Tasks::default();
+    for _ in 0..3 {
+        tasks.add_task_with("Title for testing".into(), "".into(), TaskOptions::default()).unwrap();
+    }
+    tasks.add_task_with("Depends on 1 and 2".into(), "".into(), TaskOptions { depends_on: vec![1, 2], ..Default::default() }).unwrap();
+    assert_eq!(tasks.blockers(4), vec![1, 2]);
+    tasks.change_task_status(1, TaskStatus::Completed).unwrap();
+    tasks.delete_task(2).unwrap();
+    assert!(!tasks.is_blocked(4));
+    tasks.add_task_dependencies(4, &[3]).unwrap();
+    assert!(tasks.is_blocked(4));
+    let filter = TaskFilter { blocked: Some(false), status: Some(TaskStatus::Pending), ..Default::default() };
+    let ids: Vec<usize> = tasks.filter_tasks_at(&filter, reference_now()).iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![3]);
+    tasks.remove_task_dependencies(4, &[3]).unwrap();
+    assert!(!tasks.is_blocked(4));
+}
+
+#[test]
+// Test: Change task status of a blocked task
+fn test_change_task_status_blocked() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Dependency".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Blocked".into(), "".into(), TaskOptions { depends_on: vec![1], ..Default::default() }).unwrap();
+    assert!(matches!(tasks.change_task_status(2, TaskStatus::InProgress), Err(TaskError::Blocked(ref ids)) if ids == &vec![1]));
+    assert!(tasks.change_task_status(2, TaskStatus::Completed).is_err());
+    assert!(tasks.change_task_status(2, TaskStatus::Pending).is_ok());
+    tasks.force_task_status(2, TaskStatus::Completed).unwrap();
+    assert_eq!(tasks[1].status, TaskStatus::Completed);
+}
+
+#[test]
+// Test: Dependency cycles and missing dependencies are rejected
+fn test_task_dependencies_cycles() {
+    let mut tasks = Tasks::default();
+    for _ in 0..3 {
+        tasks.add_task_with("Title for testing".into(), "".into(), TaskOptions::default()).unwrap();
+    }
+    tasks.add_task_dependencies(3, &[2]).unwrap();
+    tasks.add_task_dependencies(2, &[1]).unwrap();
+    let err = tasks.add_task_dependencies(1, &[3]).unwrap_err();
+    assert!(matches!(err, TaskError::DependencyCycle(ref cycle) if cycle == &vec![1, 3, 2, 1]));
+    assert_eq!(err.to_string(), "Dependencia circular: 1 -> 3 -> 2 -> 1");
+    assert!(matches!(tasks.add_task_dependencies(1, &[1]), Err(TaskError::DependencyCycle(_))));
+    assert!(matches!(tasks.add_task_dependencies(1, &[9]), Err(TaskError::DependencyNotFound(9))));
+    assert!(tasks[0].depends_on.is_empty());
+}
+
+#[test]
+// Test: Parse and format recurrence rules
+fn test_recurrence_parse() {
+    assert_eq!("daily".parse::<Recurrence>().unwrap(), Recurrence::Daily);
+    assert_eq!("Semanal".parse::<Recurrence>().unwrap(), Recurrence::Weekly(Vec::new()));
+    assert_eq!("weekly:fri,lun,mon".parse::<Recurrence>().unwrap(), Recurrence::Weekly(vec![Weekday::Mon, Weekday::Fri]));
+    assert_eq!("mensual:15".parse::<Recurrence>().unwrap(), Recurrence::Monthly(15));
+    assert_eq!("every:3d".parse::<Recurrence>().unwrap(), Recurrence::EveryDays(3));
+    for invalid in ["", "hourly", "weekly:someday", "monthly", "monthly:32", "every:0", "daily:2"] {
+        assert!(matches!(invalid.parse::<Recurrence>(), Err(TaskError::InvalidRecurrence)), "{}", invalid);
+    }
+    for rule in ["daily", "weekly", "weekly:mon,fri", "monthly:15", "every:3"] {
+        assert_eq!(rule.parse::<Recurrence>().unwrap().to_string(), rule);
+    }
+    assert_eq!(Recurrence::Weekly(vec![Weekday::Mon, Weekday::Wed]).stringify(), "Semanal (lun, mié)");
+    assert_eq!(Recurrence::EveryDays(3).stringify(), "Cada 3 días tras completarla");
+}
+
+#[test]
+// Test: Next due date of each recurrence rule
+fn test_recurrence_next_due() {
+    let now = reference_now();
+    let date = |value: &str| Some(DateTime::parse_from_rfc3339(value).unwrap());
+
+    // Calendar rules continue from the due date and skip occurrences before the completion
+    assert_eq!(Recurrence::Daily.next_due(date("2025-11-05T09:00:00-04:00"), now), date("2025-11-06T09:00:00-04:00"));
+    assert_eq!(Recurrence::Daily.next_due(date("2025-11-01T09:00:00-04:00"), now), date("2025-11-06T09:00:00-04:00"));
+    assert_eq!(Recurrence::Daily.next_due(date("2025-11-07T09:00:00-04:00"), now), date("2025-11-08T09:00:00-04:00"));
+    let weekly = Recurrence::Weekly(vec![Weekday::Mon, Weekday::Fri]);
+    assert_eq!(weekly.next_due(date("2025-11-05T17:00:00-04:00"), now), date("2025-11-07T17:00:00-04:00"));
+    assert_eq!(weekly.next_due(date("2025-11-07T17:00:00-04:00"), now), date("2025-11-10T17:00:00-04:00"));
+    assert_eq!(Recurrence::Weekly(Vec::new()).next_due(date("2025-11-05T17:00:00-04:00"), now), date("2025-11-12T17:00:00-04:00"));
+    assert_eq!(Recurrence::Monthly(31).next_due(date("2026-01-31T10:00:00-04:00"), now), date("2026-02-28T10:00:00-04:00"));
+    assert_eq!(Recurrence::Monthly(15).next_due(date("2025-11-01T10:00:00-04:00"), now), date("2025-11-15T10:00:00-04:00"));
+
+    // Without a due date the rule starts from the completion day, at the end of the day
+    assert_eq!(Recurrence::Monthly(15).next_due(None, now), date("2025-11-15T23:59:59-04:00"));
+
+    // Interval rules count from the completion
+    assert_eq!(Recurrence::EveryDays(3).next_due(date("2025-10-01T08:30:00-04:00"), now), date("2025-11-08T08:30:00-04:00"));
+    assert_eq!(Recurrence::EveryDays(3).next_due(None, now), date("2025-11-08T23:59:59-04:00"));
+}
+
+#[test]
+// Test: Completing a recurring task creates its next instance
+fn test_complete_recurring_task() {
+    let mut tasks = Tasks::default();
+    let options = TaskOptions {
+        priority: TaskPriority::High,
+        due_at: Some(reference_now()),
+        tags: vec!["chores".into()],
+        recurrence: Some(Recurrence::Daily),
+        ..Default::default()
+    };
+    tasks.add_task_with("Weekly report".into(), "".into(), options).unwrap();
+    assert_eq!(tasks.change_task_status(1, TaskStatus::InProgress).unwrap(), None);
+    let next = tasks.change_task_status(1, TaskStatus::Completed).unwrap().unwrap();
+    assert_eq!(next, 2);
+
+    // The completed task stays in history without the rule, the new one is pending
+    assert_eq!(tasks[0].status, TaskStatus::Completed);
+    assert_eq!(tasks[0].recurrence, None);
+    assert_eq!(tasks[1].status, TaskStatus::Pending);
+    assert_eq!(tasks[1].title, "Weekly report");
+    assert_eq!(tasks[1].priority, TaskPriority::High);
+    assert!(tasks[1].tags.contains("chores"));
+    assert_eq!(tasks[1].recurrence, Some(Recurrence::Daily));
+    assert!(tasks[1].due_at.unwrap() > tasks[0].due_at.unwrap());
+
+    // Reopening and completing again does not repeat the task twice
+    tasks.change_task_status(1, TaskStatus::Pending).unwrap();
+    assert_eq!(tasks.change_task_status(1, TaskStatus::Completed).unwrap(), None);
+    tasks.change_task_recurrence(2, None).unwrap();
+    assert_eq!(tasks.change_task_status(2, TaskStatus::Completed).unwrap(), None);
+    assert_eq!(tasks.len(), 2);
+}
+
+#[test]
+// Test: list and restore deleted tasks (subtasks deleted with their parent are restored too)
+fn test_trash_and_restore_task() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Parent".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Child".into(), "".into(), TaskOptions { parent: Some(1), ..Default::default() }).unwrap();
+    tasks.add_task_with("Deleted before".into(), "".into(), TaskOptions { parent: Some(1), ..Default::default() }).unwrap();
+    tasks.delete_task(3).unwrap();
+    thread::sleep(Duration::from_millis(5));
+    tasks.delete_task(1).unwrap();
+
+    let trash: Vec<usize> = tasks.trash().iter().map(|task| task.id).collect();
+    assert_eq!(trash, [1, 2, 3]);
+    assert!(matches!(tasks.restore_task(2), Err(TaskError::ParentDeleted(1))));
+    assert!(matches!(tasks.restore_task(9), Err(TaskError::InvalidTaskId)));
+
+    assert_eq!(tasks.restore_task(1).unwrap(), [1, 2]);
+    assert!(tasks.validate_task_id(2).is_ok());
+    assert!(tasks.validate_task_id(3).is_err());
+    assert!(matches!(tasks.restore_task(1), Err(TaskError::NotDeleted)));
+}
+
+#[test]
+// Test: purge deleted tasks (optionally only the older ones)
+fn test_purge_tasks() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Old".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Old child".into(), "".into(), TaskOptions { parent: Some(1), ..Default::default() }).unwrap();
+    tasks.add_task_with("Recent".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Kept".into(), "".into(), TaskOptions { depends_on: vec![1, 3], ..Default::default() }).unwrap();
+    tasks.delete_task(1).unwrap();
+    thread::sleep(Duration::from_millis(5));
+    let cutoff = tasks.get_task(1).unwrap().updated_at + ChronoDuration::milliseconds(1);
+    tasks.delete_task(3).unwrap();
+
+    assert_eq!(tasks.purge_tasks(Some(cutoff)), [1, 2]);
+    assert!(tasks.get_task(1).is_none());
+    assert_eq!(tasks.get_task(4).unwrap().depends_on.iter().copied().collect::<Vec<_>>(), [3]);
+    assert_eq!(tasks.purge_tasks(None), [3]);
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks.next_id(), 5);
+}
+
+#[test]
+// Test: take out old completed tasks for the archive (not those with open subtasks)
+fn test_archive_tasks() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Done".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Open".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Done parent".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Open child".into(), "".into(), TaskOptions { parent: Some(3), ..Default::default() }).unwrap();
+    tasks.change_task_status(1, TaskStatus::Completed).unwrap();
+    tasks.force_task_status(3, TaskStatus::Completed).unwrap();
+    let now = tasks.get_task(3).unwrap().completed_at + ChronoDuration::seconds(1);
+
+    assert!(tasks.archive_tasks(now - ChronoDuration::days(1)).is_empty());
+    let archived: Vec<usize> = tasks.archive_tasks(now).iter().map(|task| task.id).collect();
+    assert_eq!(archived, [1]);
+    assert_eq!(tasks.len(), 3);
+}
+
+#[test]
+// Test: archived tasks take their subtasks with them, even those completed later
+fn test_archive_tasks_subtree() {
+    let mut tasks = Tasks::default();
+    tasks.add_task_with("Parent".into(), "".into(), TaskOptions::default()).unwrap();
+    tasks.add_task_with("Child".into(), "".into(), TaskOptions { parent: Some(1), ..Default::default() }).unwrap();
+    tasks.add_task_with("Deleted child".into(), "".into(), TaskOptions { parent: Some(1), ..Default::default() }).unwrap();
+    tasks.delete_task(3).unwrap();
+    tasks.force_task_status(1, TaskStatus::Completed).unwrap();
+    let now = tasks.get_task(1).unwrap().completed_at + ChronoDuration::seconds(1);
+    tasks.change_task_status(2, TaskStatus::Completed).unwrap();
+    let mut child = tasks.get_task(2).unwrap().clone();
+    child.completed_at = now + ChronoDuration::days(1);
+    tasks.put_task(child);
+
+    let archived: Vec<usize> = tasks.archive_tasks(now).iter().map(|task| task.id).collect();
+    assert_eq!(archived, [1, 2]);
+    assert_eq!(tasks.get_task(3).unwrap().parent, None);
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, FixedOffset};
+use comfy_table::{Cell, Table};
+use std::collections::HashSet;
+
+use crate::dates::now_fixed;
+
+use super::types::{Task, TaskError, Tasks};
+
+// Tasks implementation
+impl Tasks {
+    // Deleted (hidden) tasks, most recently deleted first
+    pub fn trash(&self) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.iter().filter(|task| !task.visible).collect();
+        tasks.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then(a.id.cmp(&b.id)));
+        tasks
+    }
+
+    // GET method for deleted tasks
+    pub fn get_trash(&self) {
+        let mut table = Table::new();
+
+        table.set_header(vec![
+            Cell::new("ID"),
+            Cell::new("Título"),
+            Cell::new("Proyecto"),
+            Cell::new("Estado"),
+            Cell::new("Eliminada en"),
+        ]);
+
+        for task in self.trash() {
+            table.add_row(vec![
+                Cell::new(task.id.to_string()),
+                Cell::new(&task.title),
+                Cell::new(&task.project),
+                Cell::new(task.status.stringify()),
+                Cell::new(task.updated_at.format("%d-%m-%Y %H:%M:%S").to_string()),
+            ]);
+        }
+
+        println!("{}", table);
+    }
+
+    // Hidden tasks below the given ones that match a condition (children first, then their children)
+    fn hidden_descendants(&self, ids: &[usize], matches: impl Fn(&Task) -> bool) -> Vec<usize> {
+        let mut found: HashSet<usize> = ids.iter().copied().collect();
+        let mut descendants = Vec::new();
+        let mut pending = ids.to_vec();
+
+        while let Some(current) = pending.pop() {
+            for task in self.iter().filter(|task| !task.visible && task.parent == Some(current) && matches(task)) {
+                if found.insert(task.id) {
+                    descendants.push(task.id);
+                    pending.push(task.id);
+                }
+            }
+        }
+
+        descendants
+    }
+
+    // Restore a deleted task, along with the subtasks deleted with it (returns the restored IDs)
+    pub fn restore_task(&mut self, id: usize) -> Result<Vec<usize>, TaskError> {
+        let task = self.get_task(id).ok_or(TaskError::InvalidTaskId)?;
+
+        if task.visible {
+            return Err(TaskError::NotDeleted);
+        }
+
+        if let Some(parent) = task.parent
+            && self.get_task(parent).is_some_and(|parent| !parent.visible)
+        {
+            return Err(TaskError::ParentDeleted(parent));
+        }
+
+        // Subtasks deleted along with the task share its deletion moment
+        let deleted_at = task.updated_at;
+        let mut restored = vec![id];
+        restored.extend(self.hidden_descendants(&[id], |task| task.updated_at == deleted_at));
+
+        let now = now_fixed();
+
+        for &restored_id in &restored {
+            let idx = self.index[&restored_id];
+            self.tasks[idx].visible = true;
+            self.tasks[idx].updated_at = now;
+        }
+
+        Ok(restored)
+    }
+
+    // Permanently remove deleted tasks (only those deleted before `deleted_before`, when given),
+    // with their deleted subtasks. Returns the removed IDs
+    pub fn purge_tasks(&mut self, deleted_before: Option<DateTime<FixedOffset>>) -> Vec<usize> {
+        let mut purged: Vec<usize> = self
+            .iter()
+            .filter(|task| !task.visible && deleted_before.is_none_or(|before| task.updated_at < before))
+            .map(|task| task.id)
+            .collect();
+
+        purged.extend(self.hidden_descendants(&purged, |_| true));
+
+        for &id in &purged {
+            self.remove_task(id);
+        }
+
+        // Forget dependencies on tasks that no longer exist
+        for task in &mut self.tasks {
+            task.depends_on.retain(|dep| !purged.contains(dep));
+        }
+
+        purged
+    }
+}
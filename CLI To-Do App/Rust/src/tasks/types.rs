@@ -1,8 +1,13 @@
 use chrono::{DateTime, FixedOffset, TimeZone};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::collections::{BTreeSet, HashMap};
 use std::fmt;
-use std::ops::{Deref, DerefMut};
+use std::ops::Deref;
+use uuid::Uuid;
+
+use super::projects::{default_project, DEFAULT_PROJECT};
+use super::recurrence::Recurrence;
 
 // Errors shown to the user while executing CRUD functions
 #[derive(Debug, Clone)]
@@ -11,6 +16,16 @@ pub enum TaskError {
     TaskNotFound,
     EmptyTitle,
     EmptyFields,
+    InvalidTag,
+    InvalidProject,
+    DuplicateTaskId(usize),
+    ParentNotFound,
+    DependencyNotFound(usize),
+    DependencyCycle(Vec<usize>),
+    Blocked(Vec<usize>),
+    InvalidRecurrence,
+    NotDeleted,
+    ParentDeleted(usize),
 }
 
 // Implement fmt::Display for TaskError
@@ -21,10 +36,28 @@ impl fmt::Display for TaskError {
             TaskError::TaskNotFound => write!(f, "Tarea no encontrada"),
             TaskError::EmptyTitle => write!(f, "El título de la tarea no puede estar vacío"),
             TaskError::EmptyFields => write!(f, "Al menos un campo debe ser proporcionado para actualizar la tarea"),
+            TaskError::InvalidTag => write!(f, "Etiqueta inválida (no puede estar vacía ni contener espacios, comas o '+')"),
+            TaskError::DuplicateTaskId(id) => write!(f, "El ID de tarea {} está repetido", id),
+            TaskError::ParentNotFound => write!(f, "La tarea padre no existe"),
+            TaskError::DependencyNotFound(id) => write!(f, "La dependencia {} no existe", id),
+            TaskError::DependencyCycle(cycle) => write!(f, "Dependencia circular: {}", join_ids(cycle, " -> ")),
+            TaskError::Blocked(blockers) => write!(f, "La tarea está bloqueada por tareas sin completar: {}", join_ids(blockers, ", ")),
+            TaskError::InvalidRecurrence => write!(f, "Regla de repetición inválida (use daily, weekly, weekly:mon,fri, monthly:15 o every:3)"),
+            TaskError::NotDeleted => write!(f, "La tarea no está eliminada"),
+            TaskError::ParentDeleted(id) => write!(f, "La tarea padre {} está eliminada; restáurela primero", id),
+            TaskError::InvalidProject => write!(f, "Proyecto inválido (use nombres sin espacios separados por puntos, p. ej. trabajo.backend)"),
         }
     }
 }
 
+// Implement std::error::Error for TaskError
+impl std::error::Error for TaskError {}
+
+// Format task IDs for error messages
+fn join_ids(ids: &[usize], separator: &str) -> String {
+    ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(separator)
+}
+
 // TaskStatus enum, serialized as an integer (using Serialize_repr/Deserialize_repr)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr)]
 #[repr(u8)]
@@ -46,6 +79,42 @@ impl TaskStatus {
     }
 }
 
+// TaskPriority enum, serialized as an integer (using Serialize_repr/Deserialize_repr)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum TaskPriority {
+    Low = 0,
+    #[default]
+    Medium = 1,
+    High = 2,
+    Urgent = 3,
+}
+
+// TaskPriority implementation
+impl TaskPriority {
+    // TaskPriority stringify method
+    pub fn stringify(self) -> &'static str {
+        match self {
+            TaskPriority::Low => "Baja",
+            TaskPriority::Medium => "Media",
+            TaskPriority::High => "Alta",
+            TaskPriority::Urgent => "Urgente",
+        }
+    }
+}
+
+// Optional attributes used when creating a task
+#[derive(Debug, Clone, Default)]
+pub struct TaskOptions {
+    pub priority: TaskPriority,
+    pub due_at: Option<DateTime<FixedOffset>>,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub parent: Option<usize>,
+    pub depends_on: Vec<usize>,
+    pub recurrence: Option<Recurrence>,
+}
+
 // Zero datetime representation for CompletedAt attribute (when a task has not been completed)
 pub fn zero_datetime() -> DateTime<FixedOffset> {
     FixedOffset::east_opt(0)
@@ -55,11 +124,15 @@ pub fn zero_datetime() -> DateTime<FixedOffset> {
 }
 
 // Task struct
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     #[serde(rename = "ID")]
     pub id: usize,
 
+    // Identifier used to match tasks between machines (missing in tasks created before it existed)
+    #[serde(rename = "UUID", default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+
     #[serde(rename = "Title")]
     pub title: String,
 
@@ -75,9 +148,37 @@ pub struct Task {
     #[serde(rename = "CompletedAt")]
     pub completed_at: DateTime<FixedOffset>, 
 
+    // Omitted from the file when the task has no due date
+    #[serde(rename = "DueAt", default, skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<DateTime<FixedOffset>>,
+
     #[serde(rename = "Status")]
     pub status: TaskStatus,
 
+    // Missing in files written before priorities existed (or by the Go version)
+    #[serde(rename = "Priority", default)]
+    pub priority: TaskPriority,
+
+    // Omitted from the file when the task has no tags
+    #[serde(rename = "Tags", default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+
+    // Tasks stored before projects existed belong to the default project
+    #[serde(rename = "Project", default = "default_project")]
+    pub project: String,
+
+    // Omitted from the file for top-level tasks
+    #[serde(rename = "ParentID", default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<usize>,
+
+    // IDs of the tasks that must be completed first
+    #[serde(rename = "DependsOn", default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub depends_on: BTreeSet<usize>,
+
+    // Omitted from the file for tasks that do not repeat
+    #[serde(rename = "Recurrence", default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+
     #[serde(rename = "Visible")]
     pub visible: bool,
 }
@@ -87,33 +188,101 @@ impl Default for Task {
     fn default() -> Self {
         Self {
             id: 0,
+            uuid: None,
             title: String::new(),
             description: String::new(),
             created_at: FixedOffset::east_opt(0).unwrap().from_local_datetime(&chrono::NaiveDate::from_ymd_opt(1970,1,1).unwrap().and_hms_opt(0,0,0).unwrap()).unwrap(),
             updated_at: FixedOffset::east_opt(0).unwrap().from_local_datetime(&chrono::NaiveDate::from_ymd_opt(1970,1,1).unwrap().and_hms_opt(0,0,0).unwrap()).unwrap(),
             completed_at: zero_datetime(),
+            due_at: None,
             status: TaskStatus::Pending,
+            priority: TaskPriority::default(),
+            tags: BTreeSet::new(),
+            project: DEFAULT_PROJECT.to_string(),
+            parent: None,
+            depends_on: BTreeSet::new(),
+            recurrence: None,
             visible: true,
         }
     }
 }
 
-// Tasks struct
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct Tasks(pub Vec<Task>);
+// Tasks struct: tasks in insertion order, indexed by ID (IDs are never reused)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tasks {
+    pub(super) tasks: Vec<Task>,
+    pub(super) next_id: usize,
+    pub(super) index: HashMap<usize, usize>,
+}
+
+// Implement Default for Tasks
+impl Default for Tasks {
+    fn default() -> Self {
+        Self { tasks: Vec::new(), next_id: 1, index: HashMap::new() }
+    }
+}
+
+// Tasks implementation
+impl Tasks {
+    // Build the task list from stored tasks (the counter never goes below the highest stored ID)
+    pub fn from_parts(tasks: Vec<Task>, next_id: usize) -> Result<Self, TaskError> {
+        let mut index = HashMap::with_capacity(tasks.len());
+
+        for (position, task) in tasks.iter().enumerate() {
+            if index.insert(task.id, position).is_some() {
+                return Err(TaskError::DuplicateTaskId(task.id));
+            }
+        }
+
+        let max_id = tasks.iter().map(|task| task.id).max().unwrap_or(0);
+        let next_id = next_id.max(max_id + 1);
+
+        Ok(Self { tasks, next_id, index })
+    }
+
+    // ID that the next task will receive
+    pub fn next_id(&self) -> usize {
+        self.next_id
+    }
+
+    // Find a task by ID (including deleted tasks)
+    pub fn get_task(&self, id: usize) -> Option<&Task> {
+        self.index.get(&id).map(|&position| &self.tasks[position])
+    }
+
+    // Append a task with an already assigned ID
+    pub(super) fn push_task(&mut self, task: Task) {
+        self.next_id = self.next_id.max(task.id + 1);
+        self.index.insert(task.id, self.tasks.len());
+        self.tasks.push(task);
+    }
+
+    // Insert a stored task, replacing the task with the same ID (used by storage backends)
+    pub fn put_task(&mut self, task: Task) {
+        match self.index.get(&task.id) {
+            Some(&position) => self.tasks[position] = task,
+            None => self.push_task(task),
+        }
+    }
+
+    // Remove a task permanently (used by storage backends; the CLI only hides deleted tasks)
+    pub fn remove_task(&mut self, id: usize) -> Option<Task> {
+        let position = self.index.remove(&id)?;
+        let task = self.tasks.remove(position);
+
+        for task in &self.tasks[position..] {
+            self.index.insert(task.id, self.index[&task.id] - 1);
+        }
+
+        Some(task)
+    }
+}
 
 // Implement Deref for Tasks (allows read-only access)
 impl Deref for Tasks {
     type Target = Vec<Task>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-// Implement DerefMut for Tasks (allows mutable access)
-impl DerefMut for Tasks {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &self.tasks
     }
 }
@@ -1,37 +1,103 @@
-use chrono::{DateTime, FixedOffset, Local};
-use chrono::Offset;
+use chrono::{DateTime, FixedOffset};
+use std::collections::BTreeSet;
 
-use super::types::{TaskError, Tasks};
+use crate::dates::now_fixed;
 
-// Get local datetime with timezone
-fn now_fixed() -> DateTime<FixedOffset> {
-    let local_now = Local::now();
-    let offset = local_now.offset().fix();
-    local_now.with_timezone(&offset)
+use super::projects::normalize_project;
+use super::recurrence::Recurrence;
+use super::tags::normalize_tag;
+use super::types::{Task, TaskError, TaskPriority, Tasks};
+
+// Every editable field of a task (the status is changed on its own, since it has side effects)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskFields {
+    pub title: String,
+    pub description: String,
+    pub priority: TaskPriority,
+    pub due_at: Option<DateTime<FixedOffset>>,
+    pub tags: BTreeSet<String>,
+    pub project: String,
+    pub depends_on: BTreeSet<usize>,
+    pub recurrence: Option<Recurrence>,
+}
+
+// Take the editable fields of a task
+impl From<&Task> for TaskFields {
+    fn from(task: &Task) -> Self {
+        Self {
+            title: task.title.clone(),
+            description: task.description.clone(),
+            priority: task.priority,
+            due_at: task.due_at,
+            tags: task.tags.clone(),
+            project: task.project.clone(),
+            depends_on: task.depends_on.clone(),
+            recurrence: task.recurrence.clone(),
+        }
+    }
 }
 
 // Tasks implementation
 impl Tasks {
     // PUT method
     pub fn update_task(&mut self, id: usize, title: String, description: String) -> Result<(), TaskError> {
-        self.validate_task_id(id)?;
+        let idx = self.validate_task_id(id)?;
 
         if title.trim().is_empty() && description.trim().is_empty() {
             return Err(TaskError::EmptyFields);
         }
 
-        let idx = id - 1;
-
         if !title.trim().is_empty() {
-            self[idx].title = title;
+            self.tasks[idx].title = title;
         }
 
         if !description.trim().is_empty() {
-            self[idx].description = description;
+            self.tasks[idx].description = description;
         }
 
-        self[idx].updated_at = now_fixed(); 
+        self.tasks[idx].updated_at = now_fixed(); 
         
         Ok(())
     }
+
+    // Replace every editable field of a task (an empty description clears it). Nothing changes if a
+    // field is not valid; returns whether the task changed
+    pub fn replace_task_fields(&mut self, id: usize, fields: TaskFields) -> Result<bool, TaskError> {
+        let idx = self.validate_task_id(id)?;
+
+        if fields.title.trim().is_empty() {
+            return Err(TaskError::EmptyTitle);
+        }
+
+        let tags = fields.tags.iter().map(|tag| normalize_tag(tag)).collect::<Result<BTreeSet<_>, _>>()?;
+        let project = normalize_project(&fields.project)?;
+
+        // Dependencies the task already had may have been deleted since; only new ones must exist
+        for &dep in &fields.depends_on {
+            if self.tasks[idx].depends_on.contains(&dep) {
+                self.validate_dependency_cycle(id, dep)?;
+            } else {
+                self.validate_dependencies(id, &[dep])?;
+            }
+        }
+
+        let fields = TaskFields { tags, project, ..fields };
+
+        if fields == TaskFields::from(&self.tasks[idx]) {
+            return Ok(false);
+        }
+
+        let task = &mut self.tasks[idx];
+        task.title = fields.title;
+        task.description = fields.description;
+        task.priority = fields.priority;
+        task.due_at = fields.due_at;
+        task.tags = fields.tags;
+        task.project = fields.project;
+        task.depends_on = fields.depends_on;
+        task.recurrence = fields.recurrence;
+        task.updated_at = now_fixed();
+
+        Ok(true)
+    }
 }
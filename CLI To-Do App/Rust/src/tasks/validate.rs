@@ -2,16 +2,16 @@ use super::types::{TaskError, Tasks};
 
 // Tasks implementation
 impl Tasks {
-    // Validate Task ID method
-    pub fn validate_task_id(&self, id: usize) -> Result<(), TaskError> {
-        if id < 1 || id > self.len() {
+    // Validate Task ID method (returns the position of the task)
+    pub fn validate_task_id(&self, id: usize) -> Result<usize, TaskError> {
+        let Some(&idx) = self.index.get(&id) else {
             return Err(TaskError::InvalidTaskId);
-        }
+        };
 
-        if !self[id - 1].visible {
+        if !self.tasks[idx].visible {
             return Err(TaskError::TaskNotFound);
         }
 
-        Ok(())
+        Ok(idx)
     }
 }
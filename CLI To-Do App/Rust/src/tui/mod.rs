@@ -0,0 +1,540 @@
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::BTreeMap;
+use std::io;
+
+use crate::dates::now_fixed;
+use crate::query::Query;
+use crate::search::SearchQuery;
+use crate::storage::{StorageError, TaskStore};
+use crate::tasks::{Task, TaskFields, TaskOptions, TaskPriority, TaskStatus, Tasks};
+
+// Keys shown at the bottom of the screen in each mode
+const NORMAL_HELP: &str = "j/k mover  espacio completar  s estado  +/- prioridad  a nueva  e editar  dd eliminar  / filtrar  q salir";
+const FILTER_HELP: &str = "Consulta (p. ej. tag:trabajo due<friday) o texto a buscar  Enter aceptar  Esc cancelar";
+const EDIT_HELP: &str = "Tab cambiar de campo  Enter guardar  Esc cancelar";
+
+// Field of the inline editor being typed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Title,
+    Description,
+}
+
+// Task being written in the inline editor (id is None for a new task)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Draft {
+    id: Option<usize>,
+    title: String,
+    description: String,
+    field: Field,
+}
+
+// What the keys do: move around the list, type the filter or type in the inline editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    Normal,
+    Filter { previous: String },
+    Edit(Draft),
+}
+
+// Filter typed in the filter bar: a query, or words to search when it is not a valid query
+enum Filter {
+    All,
+    Query(Query),
+    Search(SearchQuery),
+}
+
+// State of the terminal interface: the tasks, the visible rows and what is being typed
+pub struct App {
+    tasks: Tasks,
+    // Tasks as they were last saved
+    saved: Tasks,
+    queries: BTreeMap<String, String>,
+    filter_text: String,
+    filter: Filter,
+    // IDs of the tasks shown, in order
+    rows: Vec<usize>,
+    state: TableState,
+    mode: Mode,
+    // First key of a two-key command (gg, dd)
+    pending: Option<char>,
+    message: Option<String>,
+    // Rows that fit in the list (updated when drawing)
+    page: usize,
+    quit: bool,
+}
+
+// Next status when cycling with s
+fn next_status(status: TaskStatus) -> TaskStatus {
+    match status {
+        TaskStatus::Pending => TaskStatus::InProgress,
+        TaskStatus::InProgress => TaskStatus::Completed,
+        TaskStatus::Completed => TaskStatus::Pending,
+    }
+}
+
+// Priority one step up or down (it stays at the ends)
+fn step_priority(priority: TaskPriority, up: bool) -> TaskPriority {
+    match (priority, up) {
+        (TaskPriority::Low, true) | (TaskPriority::High, false) => TaskPriority::Medium,
+        (TaskPriority::Medium, true) | (TaskPriority::Urgent, false) => TaskPriority::High,
+        (TaskPriority::High | TaskPriority::Urgent, true) => TaskPriority::Urgent,
+        (TaskPriority::Low | TaskPriority::Medium, false) => TaskPriority::Low,
+    }
+}
+
+// Mark shown for each status
+fn status_mark(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "[ ]",
+        TaskStatus::InProgress => "[~]",
+        TaskStatus::Completed => "[x]",
+    }
+}
+
+// Color used to display each priority
+fn priority_color(priority: TaskPriority) -> Color {
+    match priority {
+        TaskPriority::Low => Color::DarkGray,
+        TaskPriority::Medium => Color::Blue,
+        TaskPriority::High => Color::Yellow,
+        TaskPriority::Urgent => Color::Red,
+    }
+}
+
+// Apply the changes made since the tasks were saved on top of the stored tasks, which other
+// invocations may have changed meanwhile (a new task takes the next stored ID when its own was
+// taken)
+fn merge(stored: &Tasks, saved: &Tasks, tasks: &Tasks) -> Tasks {
+    let mut merged = stored.clone();
+
+    for task in tasks.iter().filter(|task| saved.get_task(task.id) != Some(task)) {
+        let mut task = task.clone();
+
+        if saved.get_task(task.id).is_none() {
+            task.id = task.id.max(merged.next_id());
+        }
+
+        merged.put_task(task);
+    }
+
+    for task in saved.iter().filter(|task| tasks.get_task(task.id).is_none()) {
+        merged.remove_task(task.id);
+    }
+
+    merged
+}
+
+// Save the changes on top of the stored tasks, reloaded under the lock of the store, and record
+// them so they can be undone with the undo command (only once they are saved). The lock is
+// released afterwards, so other invocations can run while the interface is open
+fn persist(store: &mut dyn TaskStore, label: String, saved: &Tasks, tasks: &Tasks) -> Result<Tasks, StorageError> {
+    let result = store.load().and_then(|stored| {
+        let merged = merge(&stored, saved, tasks);
+        store.save_changes(&stored, &merged)?;
+
+        let mut journal = store.load_journal()?;
+        journal.record(label, &stored, &merged);
+        store.save_journal(&journal)?;
+
+        Ok(merged)
+    });
+
+    store.release();
+    result
+}
+
+// App implementation
+impl App {
+    pub fn new(tasks: Tasks, queries: BTreeMap<String, String>) -> Self {
+        let mut app = Self {
+            saved: tasks.clone(),
+            tasks,
+            queries,
+            filter_text: String::new(),
+            filter: Filter::All,
+            rows: Vec::new(),
+            state: TableState::default(),
+            mode: Mode::Normal,
+            pending: None,
+            message: None,
+            page: 10,
+            quit: false,
+        };
+
+        app.refresh();
+        app
+    }
+
+    // Whether the user asked to quit
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    // ID of the selected task
+    fn selected(&self) -> Option<usize> {
+        self.state.selected().and_then(|row| self.rows.get(row).copied())
+    }
+
+    // Select a row, keeping it inside the list
+    fn select(&mut self, row: usize) {
+        self.state.select(if self.rows.is_empty() { None } else { Some(row.min(self.rows.len() - 1)) });
+    }
+
+    // Compute the rows again, keeping the selected task when it is still shown
+    fn refresh(&mut self) {
+        let selected = self.selected();
+        let now = now_fixed();
+
+        self.rows = self
+            .tasks
+            .iter()
+            .filter(|task| task.visible)
+            .filter(|task| match &self.filter {
+                Filter::All => true,
+                Filter::Query(query) => query.matches(&self.tasks, task, now),
+                Filter::Search(search) => search.highlight(&task.title).is_some() || search.highlight(&task.description).is_some(),
+            })
+            .map(|task| task.id)
+            .collect();
+
+        let row = selected
+            .and_then(|id| self.rows.iter().position(|row| *row == id))
+            .unwrap_or_else(|| self.state.selected().unwrap_or(0));
+        self.select(row);
+    }
+
+    // Filter the rows with the typed text
+    fn apply_filter(&mut self, text: &str) {
+        self.filter_text = text.to_string();
+        self.filter = if text.trim().is_empty() {
+            Filter::All
+        } else if let Ok(query) = Query::parse(text, &self.queries, now_fixed()) {
+            Filter::Query(query)
+        } else {
+            SearchQuery::parse(text).map(Filter::Search).unwrap_or(Filter::All)
+        };
+
+        self.refresh();
+    }
+
+    // Handle a key. Returns a description of the change when the tasks changed (to save them)
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<String> {
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return None;
+        }
+
+        match self.mode.clone() {
+            Mode::Normal => self.normal_key(key),
+            Mode::Filter { previous } => {
+                self.filter_key(key, previous);
+                None
+            }
+            Mode::Edit(draft) => self.edit_key(key, draft),
+        }
+    }
+
+    // Keys in the list
+    fn normal_key(&mut self, key: KeyEvent) -> Option<String> {
+        self.message = None;
+        let pending = self.pending.take();
+        let row = self.state.selected().unwrap_or(0);
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc => self.apply_filter(""),
+            KeyCode::Char('j') | KeyCode::Down => self.select(row + 1),
+            KeyCode::Char('k') | KeyCode::Up => self.select(row.saturating_sub(1)),
+            KeyCode::Char('d') if control => self.select(row + self.page / 2),
+            KeyCode::Char('u') if control => self.select(row.saturating_sub(self.page / 2)),
+            KeyCode::PageDown => self.select(row + self.page),
+            KeyCode::PageUp => self.select(row.saturating_sub(self.page)),
+            KeyCode::Char('g') if pending == Some('g') => self.select(0),
+            KeyCode::Char('G') => self.select(self.rows.len().saturating_sub(1)),
+            KeyCode::Char('d') if pending == Some('d') => return self.delete(),
+            KeyCode::Char(c @ ('g' | 'd')) => self.pending = Some(c),
+            KeyCode::Char(' ' | 'x') => {
+                let status = self.selected().and_then(|id| self.tasks.get_task(id)).map(|task| task.status)?;
+                let status = if status == TaskStatus::Completed { TaskStatus::Pending } else { TaskStatus::Completed };
+                return self.change_status(status);
+            }
+            KeyCode::Char('s') => {
+                let status = self.selected().and_then(|id| self.tasks.get_task(id)).map(|task| task.status)?;
+                return self.change_status(next_status(status));
+            }
+            KeyCode::Char(c @ ('+' | '-')) => return self.change_priority(c == '+'),
+            KeyCode::Char('a') => {
+                self.mode = Mode::Edit(Draft { id: None, title: String::new(), description: String::new(), field: Field::Title });
+            }
+            KeyCode::Char('e') | KeyCode::Enter => {
+                let task = self.selected().and_then(|id| self.tasks.get_task(id))?;
+                self.mode = Mode::Edit(Draft { id: Some(task.id), title: task.title.clone(), description: task.description.clone(), field: Field::Title });
+            }
+            KeyCode::Char('/') => self.mode = Mode::Filter { previous: self.filter_text.clone() },
+            _ => {}
+        }
+
+        None
+    }
+
+    // Keys in the filter bar (the list is filtered while typing)
+    fn filter_key(&mut self, key: KeyEvent, previous: String) {
+        let mut text = self.filter_text.clone();
+
+        match key.code {
+            KeyCode::Enter => self.mode = Mode::Normal,
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+                text = previous;
+            }
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) => text.push(c),
+            _ => {}
+        }
+
+        self.apply_filter(&text);
+    }
+
+    // Keys in the inline editor
+    fn edit_key(&mut self, key: KeyEvent, mut draft: Draft) -> Option<String> {
+        let text = match draft.field {
+            Field::Title => &mut draft.title,
+            Field::Description => &mut draft.description,
+        };
+
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+                return None;
+            }
+            KeyCode::Enter => return self.save_draft(draft),
+            KeyCode::Tab | KeyCode::BackTab => {
+                draft.field = if draft.field == Field::Title { Field::Description } else { Field::Title };
+            }
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) => text.push(c),
+            _ => {}
+        }
+
+        self.mode = Mode::Edit(draft);
+        None
+    }
+
+    // Create or update the task of the inline editor (an empty description clears it)
+    fn save_draft(&mut self, draft: Draft) -> Option<String> {
+        let result = match draft.id {
+            None => self
+                .tasks
+                .add_task_with(draft.title.clone(), draft.description.clone(), TaskOptions::default())
+                .map(|id| (id, format!("crear la tarea \"{}\"", draft.title.trim()))),
+            Some(id) => {
+                let task = self.tasks.get_task(id)?;
+                let fields = TaskFields { title: draft.title.clone(), description: draft.description.clone(), ..TaskFields::from(task) };
+                self.tasks.replace_task_fields(id, fields).map(|_| (id, format!("editar la tarea {}", id)))
+            }
+        };
+
+        match result {
+            Ok((id, label)) => {
+                self.mode = Mode::Normal;
+                self.refresh();
+
+                if let Some(row) = self.rows.iter().position(|row| *row == id) {
+                    self.select(row);
+                }
+
+                Some(label)
+            }
+            Err(e) => {
+                // The draft stays open so it can be fixed
+                self.message = Some(e.to_string());
+                self.mode = Mode::Edit(draft);
+                None
+            }
+        }
+    }
+
+    // Change the status of the selected task
+    fn change_status(&mut self, status: TaskStatus) -> Option<String> {
+        let id = self.selected()?;
+
+        if let Err(e) = self.tasks.change_task_status(id, status) {
+            self.message = Some(e.to_string());
+            return None;
+        }
+
+        self.refresh();
+        Some(format!("marcar la tarea {} como \"{}\"", id, status.stringify()))
+    }
+
+    // Raise or lower the priority of the selected task
+    fn change_priority(&mut self, up: bool) -> Option<String> {
+        let id = self.selected()?;
+        let task = self.tasks.get_task(id)?;
+        let priority = step_priority(task.priority, up);
+
+        if priority == task.priority {
+            return None;
+        }
+
+        self.tasks.change_task_priority(id, priority).ok()?;
+        self.refresh();
+        Some(format!("cambiar la prioridad de la tarea {}", id))
+    }
+
+    // Delete the selected task
+    fn delete(&mut self) -> Option<String> {
+        let id = self.selected()?;
+
+        if let Err(e) = self.tasks.delete_task(id) {
+            self.message = Some(e.to_string());
+            return None;
+        }
+
+        self.refresh();
+        self.message = Some(format!("Tarea {} eliminada (use undo para recuperarla)", id));
+        Some(format!("eliminar la tarea {}", id))
+    }
+
+    // Save the tasks after a change, showing the changes made meanwhile by other invocations (a
+    // failure is shown, and the change is saved with the next one)
+    pub fn save(&mut self, store: &mut dyn TaskStore, label: String) {
+        match persist(store, label, &self.saved, &self.tasks) {
+            Ok(stored) => {
+                self.tasks = stored.clone();
+                self.saved = stored;
+                self.refresh();
+            }
+            Err(e) => self.message = Some(format!("Error al guardar las tareas -> {}", e)),
+        }
+    }
+
+    // Row of the list for a task
+    fn row<'a>(&self, task: &'a Task) -> Row<'a> {
+        let tags: Vec<String> = task.tags.iter().map(|tag| format!("+{}", tag)).collect();
+        let due = task.due_at.map(|due| due.format("%d-%m-%Y").to_string()).unwrap_or_default();
+
+        let style = if task.status == TaskStatus::Completed {
+            Style::new().fg(Color::DarkGray).add_modifier(Modifier::CROSSED_OUT)
+        } else if self.tasks.is_blocked(task.id) {
+            Style::new().fg(Color::DarkGray)
+        } else {
+            Style::new()
+        };
+
+        Row::new([
+            Cell::from(task.id.to_string()),
+            Cell::from(status_mark(task.status)),
+            Cell::from(task.priority.stringify()).style(Style::new().fg(priority_color(task.priority))),
+            Cell::from(task.title.lines().next().unwrap_or_default()),
+            Cell::from(task.project.as_str()),
+            Cell::from(tags.join(" ")),
+            Cell::from(due),
+        ])
+        .style(style)
+    }
+
+    // Draw the list, the input bar and the status line
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [list, bar, status] = Layout::vertical([Constraint::Min(3), Constraint::Length(1), Constraint::Length(1)]).areas(frame.area());
+        self.draw_list(frame, list);
+
+        let (text, cursor) = match &self.mode {
+            Mode::Normal if self.filter_text.is_empty() => (String::new(), false),
+            Mode::Normal => (format!("Filtro: {}", self.filter_text), false),
+            Mode::Filter { .. } => (format!("/{}", self.filter_text), true),
+            Mode::Edit(draft) => match draft.field {
+                Field::Title => (format!("Título: {}", draft.title), true),
+                Field::Description => (format!("Descripción: {}", draft.description), true),
+            },
+        };
+
+        if cursor {
+            let x = bar.x.saturating_add(text.chars().count() as u16).min(bar.right().saturating_sub(1));
+            frame.set_cursor_position(Position::new(x, bar.y));
+        }
+
+        frame.render_widget(Paragraph::new(text), bar);
+
+        let help = match (&self.message, &self.mode) {
+            (Some(message), _) => message.as_str(),
+            (None, Mode::Normal) => NORMAL_HELP,
+            (None, Mode::Filter { .. }) => FILTER_HELP,
+            (None, Mode::Edit(_)) => EDIT_HELP,
+        };
+        let style = if self.message.is_some() { Style::new().fg(Color::Yellow) } else { Style::new().fg(Color::DarkGray) };
+
+        frame.render_widget(Paragraph::new(help).style(style), status);
+    }
+
+    // Draw the task list, scrolled so the selected task is visible
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        // Borders and header take three lines
+        self.page = usize::from(area.height.saturating_sub(3)).max(1);
+
+        let rows: Vec<Row> = self.rows.iter().filter_map(|id| self.tasks.get_task(*id)).map(|task| self.row(task)).collect();
+        let header = Row::new(["ID", "Estado", "Prioridad", "Título", "Proyecto", "Etiquetas", "Vence"]).style(Style::new().add_modifier(Modifier::BOLD));
+        let widths = [
+            Constraint::Length(4),
+            Constraint::Length(6),
+            Constraint::Length(9),
+            Constraint::Fill(3),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(10),
+        ];
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title(format!(" Tareas ({}) ", self.rows.len())))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
+
+// Run the interface on the terminal until the user quits, saving each change through the store
+// (the store is only locked while a change is saved)
+pub fn run(store: &mut dyn TaskStore, tasks: Tasks, queries: BTreeMap<String, String>) -> io::Result<()> {
+    store.release();
+
+    let mut app = App::new(tasks, queries);
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, &mut app, store);
+
+    ratatui::try_restore()?;
+    result
+}
+
+// Draw and handle keys until the user quits
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App, store: &mut dyn TaskStore) -> io::Result<()> {
+    while !app.should_quit() {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if let Event::Key(key) = event::read()?
+            && let Some(label) = app.handle_key(key)
+        {
+            app.save(store, label);
+        }
+    }
+
+    Ok(())
+}
+
+// Test configuration
+#[cfg(test)]
+mod tests;
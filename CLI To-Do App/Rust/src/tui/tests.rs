@@ -0,0 +1,199 @@
+use super::App;
+use crate::storage::{MemoryStore, TaskStore};
+use crate::tasks::{TaskOptions, TaskPriority, TaskStatus, Tasks};
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Terminal;
+use std::collections::BTreeMap;
+
+// Press keys, saving the changes in the store like the interface does
+fn press(app: &mut App, store: &mut MemoryStore, keys: &[KeyCode]) {
+    for &code in keys {
+        if let Some(label) = app.handle_key(KeyEvent::new(code, KeyModifiers::NONE)) {
+            app.save(store, label);
+        }
+    }
+}
+
+// Type a text, one key per character
+fn typed(text: &str) -> Vec<KeyCode> {
+    text.chars().map(KeyCode::Char).collect()
+}
+
+// Draw the interface on a headless terminal and return the screen, one line per row
+fn screen(app: &mut App, width: u16, height: u16) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| app.draw(frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    (0..height)
+        .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string())
+        .collect()
+}
+
+// A store with numbered tasks
+fn sample_store(count: usize) -> MemoryStore {
+    let mut store = MemoryStore::default();
+
+    for i in 1..=count {
+        store.add_task_with(format!("Tarea {}", i), "".into(), TaskOptions::default()).unwrap();
+    }
+
+    store
+}
+
+#[test]
+// Test: vim-style movement scrolls the list to keep the selected task visible
+fn test_tui_navigation() {
+    let mut store = sample_store(30);
+    let mut app = App::new(store.load().unwrap(), BTreeMap::new());
+    screen(&mut app, 80, 12);
+
+    press(&mut app, &mut store, &typed("jjk"));
+    assert_eq!(app.selected(), Some(2));
+    press(&mut app, &mut store, &[KeyCode::Char('G')]);
+    assert_eq!(app.selected(), Some(30));
+
+    let lines = screen(&mut app, 80, 12);
+    assert!(lines.iter().any(|line| line.contains("Tarea 30")));
+    assert!(!lines.iter().any(|line| line.contains("Tarea 1 ")));
+
+    press(&mut app, &mut store, &typed("gg"));
+    assert_eq!(app.selected(), Some(1));
+    app.handle_key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL));
+    assert_eq!(app.selected(), Some(4));
+    press(&mut app, &mut store, &[KeyCode::Char('q')]);
+    assert!(app.should_quit());
+}
+
+#[test]
+// Test: the filter bar takes a query, or words to search when it is not a query
+fn test_tui_filter() {
+    let mut store = MemoryStore::default();
+    let options = TaskOptions { tags: vec!["casa".into()], ..Default::default() };
+    store.add_task_with("Comprar pan".into(), "".into(), options).unwrap();
+    store.add_task_with("Preparar informe".into(), "Informe mensual".into(), TaskOptions::default()).unwrap();
+    store.add_task_with("Llamar".into(), "".into(), TaskOptions::default()).unwrap();
+    let queries = [("casa".to_string(), "tag:casa".to_string())].into();
+    let mut app = App::new(store.load().unwrap(), queries);
+
+    press(&mut app, &mut store, &[KeyCode::Char('/')]);
+    press(&mut app, &mut store, &typed("informe"));
+    assert_eq!(app.rows, [2]);
+    press(&mut app, &mut store, &[KeyCode::Enter]);
+    assert!(screen(&mut app, 80, 8).iter().any(|line| line == "Filtro: informe"));
+
+    press(&mut app, &mut store, &[KeyCode::Char('/')]);
+    press(&mut app, &mut store, &[KeyCode::Backspace; 7]);
+    press(&mut app, &mut store, &typed("title~informe or @casa"));
+    assert_eq!(app.rows, [1, 2]);
+
+    // Esc cancels the typed filter, and Esc again shows every task
+    press(&mut app, &mut store, &[KeyCode::Esc]);
+    assert_eq!(app.filter_text, "informe");
+    press(&mut app, &mut store, &[KeyCode::Esc]);
+    assert_eq!(app.rows, [1, 2, 3]);
+}
+
+#[test]
+// Test: single keys change the selected task, and each change is saved and can be undone
+fn test_tui_changes_saved() {
+    let mut store = sample_store(3);
+    let options = TaskOptions { depends_on: vec![3], ..Default::default() };
+    store.add_task_with("Bloqueada".into(), "".into(), options).unwrap();
+    let mut app = App::new(store.load().unwrap(), BTreeMap::new());
+
+    press(&mut app, &mut store, &[KeyCode::Char(' ')]);
+    assert_eq!(store[0].status, TaskStatus::Completed);
+    press(&mut app, &mut store, &typed("js+++"));
+    assert_eq!(store[1].status, TaskStatus::InProgress);
+    assert_eq!(store[1].priority, TaskPriority::Urgent);
+
+    // Blocked tasks cannot be completed
+    press(&mut app, &mut store, &typed("G "));
+    assert_eq!(store[3].status, TaskStatus::Pending);
+    assert!(app.message.as_deref().is_some_and(|message| message.contains("bloqueada")));
+
+    press(&mut app, &mut store, &typed("kdd"));
+    assert!(!store[2].visible);
+    assert_eq!(app.rows, [1, 2, 4]);
+    assert_eq!(app.selected(), Some(4));
+
+    let mut journal = store.load_journal().unwrap();
+    let mut tasks: Tasks = store.load().unwrap();
+    assert_eq!(journal.undo(&mut tasks).unwrap().unwrap().command, "eliminar la tarea 3");
+    assert!(tasks[2].visible);
+}
+
+#[test]
+// Test: changes made meanwhile by another invocation are kept, and new tasks do not take their IDs
+fn test_tui_changes_merged() {
+    let mut store = sample_store(2);
+    let mut app = App::new(store.load().unwrap(), BTreeMap::new());
+
+    store.add_task_with("Otra".into(), "".into(), TaskOptions::default()).unwrap();
+    store.change_task_status(2, TaskStatus::InProgress).unwrap();
+    press(&mut app, &mut store, &[KeyCode::Char(' ')]);
+    assert_eq!(store[0].status, TaskStatus::Completed);
+    assert_eq!(store[1].status, TaskStatus::InProgress);
+    assert_eq!(app.rows, [1, 2, 3]);
+
+    store.add_task_with("Otra más".into(), "".into(), TaskOptions::default()).unwrap();
+    press(&mut app, &mut store, &[KeyCode::Char('a')]);
+    press(&mut app, &mut store, &typed("Nueva"));
+    press(&mut app, &mut store, &[KeyCode::Enter]);
+    assert_eq!(store.get_task(4).unwrap().title, "Otra más");
+    assert_eq!(store.get_task(5).unwrap().title, "Nueva");
+    assert_eq!(app.rows, [1, 2, 3, 4, 5]);
+}
+
+#[test]
+// Test: the inline editor creates and edits tasks (the description can be cleared)
+fn test_tui_inline_editor() {
+    let mut store = sample_store(1);
+    store.update_task(1, "Tarea 1".into(), "Con descripción".into()).unwrap();
+    let mut app = App::new(store.load().unwrap(), BTreeMap::new());
+
+    press(&mut app, &mut store, &[KeyCode::Char('a')]);
+    press(&mut app, &mut store, &typed("Nueva"));
+    press(&mut app, &mut store, &[KeyCode::Tab]);
+    press(&mut app, &mut store, &typed("Detalle"));
+    let lines = screen(&mut app, 80, 8);
+    assert_eq!(lines[6], "Descripción: Detalle");
+    press(&mut app, &mut store, &[KeyCode::Enter]);
+    assert_eq!(store[1].title, "Nueva");
+    assert_eq!(store[1].description, "Detalle");
+    assert_eq!(app.selected(), Some(2));
+
+    // An empty title is rejected and the editor stays open
+    press(&mut app, &mut store, &[KeyCode::Char('a'), KeyCode::Enter]);
+    assert_eq!(store.len(), 2);
+    assert!(app.message.is_some());
+    press(&mut app, &mut store, &[KeyCode::Esc]);
+
+    press(&mut app, &mut store, &typed("gge"));
+    press(&mut app, &mut store, &[KeyCode::Tab]);
+    press(&mut app, &mut store, &[KeyCode::Backspace; 15]);
+    press(&mut app, &mut store, &[KeyCode::Enter]);
+    assert_eq!(store[0].title, "Tarea 1");
+    assert_eq!(store[0].description, "");
+}
+
+#[test]
+// Test: the list shows the status, priority, tags and the keys of the current mode
+fn test_tui_draw() {
+    let mut store = MemoryStore::default();
+    let options = TaskOptions { priority: TaskPriority::High, tags: vec!["trabajo".into()], ..Default::default() };
+    store.add_task_with("Informe".into(), "".into(), options).unwrap();
+    store.change_task_status(1, TaskStatus::InProgress).unwrap();
+    let mut app = App::new(store.load().unwrap(), BTreeMap::new());
+
+    let lines = screen(&mut app, 100, 6);
+    assert!(lines[0].contains("Tareas (1)"));
+    assert!(lines[1].contains("ID") && lines[1].contains("Estado") && lines[1].contains("Etiquetas"));
+    assert!(lines[2].contains("[~]") && lines[2].contains("Alta") && lines[2].contains("Informe") && lines[2].contains("+trabajo"));
+    assert!(lines[5].starts_with("j/k mover"));
+
+    press(&mut app, &mut store, &[KeyCode::Char('/')]);
+    assert!(screen(&mut app, 100, 6)[5].starts_with("Consulta"));
+}